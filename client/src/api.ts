@@ -36,6 +36,50 @@ export interface IApiResponse<T = null> {
 	readonly payload: T;
 }
 
+export interface IPage<T> {
+	readonly items: ReadonlyArray<T>;
+	readonly next_cursor: string | null;
+}
+
+const MAX_PAGE_LIMIT = "200";
+
+// notifications screen shows only the latest page
+const pageItems = <T>(
+	res: IApiResponse<IPage<T>> | null,
+): IApiResponse<ReadonlyArray<T>> | null =>
+	res && { ...res, payload: res.payload ? res.payload.items : [] };
+
+// lists are paginated; screens show whole lists, so pages are requested
+// until the last one
+const readAllPages = async <T>(
+	path: string,
+	query: URLSearchParams,
+	abortController?: AbortController,
+): Promise<IApiResponse<ReadonlyArray<T>> | null> => {
+	const items: T[] = [];
+	query.set("limit", MAX_PAGE_LIMIT);
+
+	for (;;) {
+		const res = await ajax<IPage<T>>(
+			`${path}?${query}`,
+			null,
+			abortController,
+		);
+
+		if (!res?.payload || res.status !== EScenarioStatus.SCENARIO_SUCCESS) {
+			return res && { ...res, payload: items };
+		}
+
+		items.push(...res.payload.items);
+
+		if (!res.payload.next_cursor) {
+			return { ...res, payload: items };
+		}
+
+		query.set("cursor", res.payload.next_cursor);
+	}
+};
+
 export interface IRequestInit {
 	readonly body?: string | FormData | URLSearchParams;
 	readonly headers?: Record<string, string>;
@@ -249,6 +293,18 @@ export interface IApiLocation {
 	readonly region: string | null;
 	readonly city: string | null;
 	readonly map_link: string | null;
+	readonly latitude: number | null;
+	readonly longitude: number | null;
+	readonly tables: number | null;
+	/** Only filled in when a single location is read */
+	readonly opening_hours: ReadonlyArray<IWeeklyWindow> | null;
+}
+
+/** Weekly window in local time; `to` before `from` means it ends the next day */
+export interface IWeeklyWindow {
+	readonly weekday: number; // 1 - Monday
+	readonly from: string; // "19:00:00"
+	readonly to: string;
 }
 
 export const readLocations = (nameFilter?: string | null) => {
@@ -257,7 +313,7 @@ export const readLocations = (nameFilter?: string | null) => {
 		query.append("name", nameFilter);
 	}
 
-	return ajax<ReadonlyArray<IApiLocation>>(`/api/locations?${query}`);
+	return readAllPages<IApiLocation>("/api/locations", query);
 };
 
 export const readLocationById = (locId: UUID) =>
@@ -315,9 +371,9 @@ export const readMyCompanies = (
 		query.append("name", nameFilter);
 	}
 
-	return ajax<ReadonlyArray<IApiCompany>>(
-		`/api/companies/my?${query}`,
-		undefined,
+	return readAllPages<IApiCompany>(
+		"/api/companies/my",
+		query,
 		abortController,
 	);
 };
@@ -360,8 +416,8 @@ export interface IApiEvent {
 	readonly company_id: UUID;
 	readonly master: string;
 	readonly master_id: UUID;
-	readonly location: string;
-	readonly location_id: UUID;
+	readonly location: string | null;
+	readonly location_id: UUID | null;
 	readonly location_map_link: string | null;
 	readonly date: string;
 	readonly max_slots: number | null;
@@ -371,6 +427,10 @@ export interface IApiEvent {
 	readonly you_are_master: boolean;
 	readonly your_approval: boolean | null;
 	readonly cancelled: boolean;
+	readonly venue_type: "offline" | "online";
+	readonly online_platform: string | null;
+	/** видна только одобренным игрокам и ведущим */
+	readonly join_link: string | null;
 }
 
 export interface IEventsFilter {
@@ -399,8 +459,9 @@ export const readEventsList = (
 		});
 	}
 
-	return ajax<ReadonlyArray<IApiShortEvent>>(
-		`/api/events?${new URLSearchParams(query)}`,
+	return readAllPages<IApiShortEvent>(
+		"/api/events",
+		new URLSearchParams(query),
 	);
 };
 
@@ -408,6 +469,30 @@ export const readEvent = (eventId: UUID) => {
 	return ajax<IApiEvent>(`/api/events/${eventId}`);
 };
 
+/** Another game of the user that overlaps the new one in time */
+export interface IScheduleConflict {
+	readonly event_id: UUID;
+	readonly company_name: string;
+	readonly date: string;
+	readonly end: string;
+	readonly role: "player" | "master";
+	readonly approval: boolean | null;
+}
+
+export type TLocationIssue =
+	| {
+			readonly kind: "full";
+			readonly tables: number;
+			readonly concurrent: ReadonlyArray<UUID>;
+	  }
+	| { readonly kind: "closed" };
+
+export interface ICreatedWithConflicts {
+	readonly id: UUID;
+	readonly conflicts: ReadonlyArray<IScheduleConflict>;
+	readonly location_issues?: ReadonlyArray<TLocationIssue>;
+}
+
 export const createEvent = (
 	company: UUID,
 	date: string,
@@ -415,14 +500,14 @@ export const createEvent = (
 	max_slots: number | null,
 	plan_duration: number | null,
 ) => {
-	return ajax<UUID>(
+	return ajax<ICreatedWithConflicts>(
 		"/api/events",
 		prepareAjax({ company, date, location, max_slots, plan_duration }, POST),
 	);
 };
 
 export const applyEvent = (eventId: UUID) => {
-	return ajax<UUID>(
+	return ajax<ICreatedWithConflicts>(
 		`/api/events/apply/${eventId}`,
 		prepareAjax(undefined, POST),
 	);
@@ -435,7 +520,7 @@ export const updateEvent = (
 	max_slots: number | null,
 	plan_duration: number | null,
 ) => {
-	return ajax<null>(
+	return ajax<Omit<ICreatedWithConflicts, "id">>(
 		`/api/events/${eventId}`,
 		prepareAjax({ date, location, max_slots, plan_duration }, PUT),
 	);
@@ -463,14 +548,18 @@ export interface IPlayerApp {
 	readonly event_date: string; // "2025-04-15T07:24:00Z"
 	readonly event_id: UUID;
 	readonly id: UUID;
-	readonly location_id: UUID;
-	readonly location_name: string;
+	readonly location_id: UUID | null;
+	readonly location_name: string | null;
 	readonly master_id: UUID;
 	readonly master_name: string;
 }
 
 export const readPlayerAppsList = (abortController?: AbortController) =>
-	ajax<ReadonlyArray<IPlayerApp>>(`/api/apps`, null, abortController);
+	readAllPages<IPlayerApp>(
+		"/api/apps",
+		new URLSearchParams(),
+		abortController,
+	);
 export const readPlayerApp = (appId: UUID) =>
 	ajax<IPlayerApp | null>(`/api/apps/${appId}`);
 export const readPlayerAppByEvent = (eventId: UUID) =>
@@ -486,14 +575,18 @@ export interface IMasterApp {
 	readonly event_date: string; // "2025-04-15T07:24:00Z"
 	readonly event_id: UUID;
 	readonly id: UUID;
-	readonly location_id: UUID;
-	readonly location_name: string;
+	readonly location_id: UUID | null;
+	readonly location_name: string | null;
 	readonly player_id: UUID;
 	readonly player_name: string;
 }
 
 export const readMasterAppsList = (abortController?: AbortController) =>
-	ajax<ReadonlyArray<IMasterApp>>(`/api/apps/master`, null, abortController);
+	readAllPages<IMasterApp>(
+		"/api/apps/master",
+		new URLSearchParams(),
+		abortController,
+	);
 export const readMasterAppsListByEvent = (eventId: UUID) =>
 	ajax<ReadonlyArray<IMasterApp>>(`/api/apps/master/by_event/${eventId}`);
 export const readMasterAppsListCompanyClosest = (companyId: UUID) =>
@@ -513,6 +606,73 @@ export const rejectApplication = (appId: UUID) => {
 	return ajax<null>(`/api/apps/reject/${appId}`, prepareAjax(undefined, POST));
 };
 
+/** SSE event names; each one matches the `type` field of the payload */
+export const NOTIFICATION_TYPES = [
+	"application_created",
+	"application_approved",
+	"application_rejected",
+	"event_invite",
+	"event_scheduled",
+	"event_rescheduled",
+	"event_cancelled",
+	"member_invited",
+	"member_removed",
+	"transfer_proposed",
+	"transfer_accepted",
+	"transfer_cancelled",
+] as const;
+
+export type TNotificationType = (typeof NOTIFICATION_TYPES)[number];
+
+export interface INotificationPayload {
+	readonly type: TNotificationType;
+	readonly event_id?: UUID;
+	readonly company_id?: UUID;
+	readonly company_name?: string;
+	readonly date?: string;
+	readonly [field: string]: unknown;
+}
+
+/** `data` of a notification SSE event */
+export interface INotificationEvent extends INotificationPayload {
+	readonly id: UUID | null;
+	readonly message: string;
+}
+
+export interface INotification {
+	readonly id: UUID;
+	readonly message: string;
+	readonly payload: INotificationPayload | null;
+	readonly created_at: string;
+	readonly read_at: string | null;
+}
+
+export const readNotifications = (unread = false) =>
+	ajax<IPage<INotification>>(
+		`/api/notifications?direction=desc&unread=${unread}&limit=${MAX_PAGE_LIMIT}`,
+	).then(pageItems);
+export const readUnreadCount = () =>
+	ajax<{ readonly count: number }>("/api/notifications/unread");
+export const markNotificationRead = (notificationId: UUID) =>
+	ajax<null>(
+		`/api/notifications/${notificationId}/read`,
+		prepareAjax(undefined, POST),
+	);
+export const markAllNotificationsRead = () =>
+	ajax<{ readonly updated: number }>(
+		"/api/notifications/read",
+		prepareAjax(undefined, POST),
+	);
+
+/** Ссылка на бота: нажатие Start в нём подключает уведомления в Telegram */
+export const createTelegramLink = () =>
+	ajax<{ readonly link: string; readonly expires_at: string }>(
+		"/api/telegram/link",
+		prepareAjax(undefined, POST),
+	);
+export const setTelegramNotifications = (enabled: boolean) =>
+	ajax<null>("/api/telegram/notifications", prepareAjax({ enabled }, PUT));
+
 export const enum ETzVariant {
 	CITY = "city",
 	DEVICE = "device",
@@ -524,6 +684,8 @@ export interface IApiProfile {
 	readonly email: string | null;
 	readonly email_verified: boolean;
 	readonly tg_id: number | null;
+	readonly tg_linked: boolean;
+	readonly tg_notifications: boolean;
 	readonly nickname: string;
 	readonly about_me: string | null;
 	readonly avatar_link: string | null;
@@ -64,8 +64,8 @@ export const DialogItem = ({
 		},
 		{
 			label: "Локация",
-			value: item.location_name,
-			link: `/location/${item.location_id}`,
+			value: item.location_name ?? "Онлайн",
+			link: item.location_id ? `/location/${item.location_id}` : undefined,
 		},
 		{
 			label: "player_name" in item ? "Игрок" : "Мастер",
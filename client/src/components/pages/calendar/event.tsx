@@ -180,10 +180,20 @@ const Event = (props: IEventProps) => {
 			Number(plan_duration) || null,
 		)
 			.then((res) => {
-				if (res) {
+				if (res?.payload) {
 					toaster.success({ title: "Событие успешно создано" });
+					if (res.payload.conflicts.length) {
+						toaster.warning({
+							title: "Игра пересекается с другими вашими играми",
+						});
+					}
+					if (res.payload.location_issues?.length) {
+						toaster.warning({
+							title: "Локация занята или закрыта в это время",
+						});
+					}
 					props.setOpenDraw(false);
-					props.getNewEvent(res.payload);
+					props.getNewEvent(res.payload.id);
 					reset();
 				}
 			})
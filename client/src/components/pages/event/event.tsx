@@ -86,8 +86,8 @@ const EventCard = ({
 		},
 		{
 			label: "Место проведения",
-			value: event.location,
-			href: `/location/${event.location_id}`,
+			value: event.location ?? "Онлайн",
+			href: event.location_id ? `/location/${event.location_id}` : undefined,
 			mapLink: event.location_map_link,
 		},
 		{ label: "Дата", value: eventDate.format("DD MMMM") },
@@ -580,7 +580,7 @@ export const EventPage = () => {
 														{...register("location", {
 															required: "Заполните",
 														})}
-														defaultValue={event.location_id}
+														defaultValue={event.location_id ?? undefined}
 													>
 														{locations.items.map((location) => (
 															<option
@@ -7,7 +7,14 @@ import utc from "dayjs/plugin/utc";
 import { atom, computed, map, task } from "nanostores";
 import { procetar } from "procetar";
 
-import { API_HOST, ETzVariant, getTgAvatar, IApiProfile } from "../api";
+import {
+	API_HOST,
+	ETzVariant,
+	getTgAvatar,
+	IApiProfile,
+	INotificationEvent,
+	NOTIFICATION_TYPES,
+} from "../api";
 import { toaster } from "../components/ui/toaster";
 import { YYYY_MM_DD } from "../utils";
 
@@ -178,8 +185,9 @@ $profile.listen((p) => {
 			withCredentials: true,
 		});
 
-		eventSource.onmessage = (event) => {
-			let msg: string = event.data;
+		const showNotification = (event: MessageEvent<string>) => {
+			const notification: INotificationEvent = JSON.parse(event.data);
+			let msg = notification.message;
 			const match = msg.match(DATE_REGEXP)?.[0];
 			if (match) {
 				const date = dayjs(match).tz($tz.get()).format(YYYY_MM_DD);
@@ -188,6 +196,12 @@ $profile.listen((p) => {
 			toaster.success({ title: msg });
 		};
 
+		for (const type of NOTIFICATION_TYPES) {
+			eventSource.addEventListener(type, showNotification);
+		}
+		// объявление для всех: тот же JSON с полем message
+		eventSource.addEventListener("announcement", showNotification);
+
 		eventSource.onerror = (event) => {
 			console.info("sse error:");
 			console.error(event);
@@ -0,0 +1,2 @@
+ALTER TABLE "companies"
+ADD COLUMN "one_shot" BOOL NOT NULL DEFAULT false;
@@ -0,0 +1,5 @@
+ALTER TABLE "events"
+DROP CONSTRAINT "FK_events_event_series",
+DROP COLUMN "series";
+
+DROP TABLE "event_series";
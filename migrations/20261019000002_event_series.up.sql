@@ -0,0 +1,22 @@
+CREATE TABLE "event_series" (
+	"id"          UUID  DEFAULT uuid_v6(),
+	"company"     UUID  NOT NULL,
+	"frequency"   VARCHAR(16)  NOT NULL
+						CONSTRAINT "series_frequency_check" CHECK ("frequency" IN ('weekly', 'biweekly', 'monthly_weekday')),
+	"until"       DATE  DEFAULT NULL,
+	"count"       smallint
+						CONSTRAINT "positive_count" CHECK ("count" > 0)
+						DEFAULT NULL,
+	"exceptions"  DATE[]  NOT NULL  DEFAULT '{}',
+
+	CONSTRAINT "PK_event_series" PRIMARY KEY ("id"),
+	CONSTRAINT "FK_event_series_companies" FOREIGN KEY ("company")
+		REFERENCES "companies"("id")
+		ON DELETE CASCADE
+);
+
+ALTER TABLE "events"
+ADD COLUMN "series" UUID DEFAULT NULL,
+ADD CONSTRAINT "FK_events_event_series" FOREIGN KEY ("series")
+	REFERENCES "event_series"("id")
+	ON DELETE SET NULL;
@@ -0,0 +1,14 @@
+CREATE TABLE "company_members" (
+	"company"   UUID  NOT NULL,
+	"user_id"   UUID  NOT NULL,
+	"status"    VARCHAR(8)  NOT NULL  DEFAULT 'invited'
+					CONSTRAINT "member_status_check" CHECK ("status" IN ('invited', 'active')),
+
+	CONSTRAINT "PK_company_members" PRIMARY KEY ("company", "user_id"),
+	CONSTRAINT "FK_company_members_companies" FOREIGN KEY ("company")
+		REFERENCES "companies"("id")
+		ON DELETE CASCADE,
+	CONSTRAINT "FK_company_members_users" FOREIGN KEY ("user_id")
+		REFERENCES "users"("id")
+		ON DELETE CASCADE
+);
@@ -0,0 +1,2 @@
+ALTER TABLE "events" DROP COLUMN "visibility";
+ALTER TABLE "companies" DROP COLUMN "visibility";
@@ -0,0 +1,7 @@
+ALTER TABLE "companies"
+ADD COLUMN "visibility" VARCHAR(8) NOT NULL DEFAULT 'public'
+	CONSTRAINT "company_visibility_check" CHECK ("visibility" IN ('public', 'unlisted', 'private'));
+
+ALTER TABLE "events"
+ADD COLUMN "visibility" VARCHAR(8) NOT NULL DEFAULT 'public'
+	CONSTRAINT "event_visibility_check" CHECK ("visibility" IN ('public', 'unlisted', 'private'));
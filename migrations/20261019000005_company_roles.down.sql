@@ -0,0 +1,3 @@
+DROP FUNCTION "has_company_permission"(UUID, UUID, TEXT);
+
+ALTER TABLE "company_members" DROP COLUMN "role";
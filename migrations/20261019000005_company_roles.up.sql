@@ -0,0 +1,25 @@
+ALTER TABLE "company_members"
+ADD COLUMN "role" VARCHAR(16) NOT NULL DEFAULT 'player'
+	CONSTRAINT "member_role_check" CHECK ("role" IN ('player', 'assistant', 'co_master'));
+
+-- events - создание и редактирование игр, apps - работа с заявками, company - настройки кампании
+CREATE FUNCTION "has_company_permission"("p_company" UUID, "p_user" UUID, "p_permission" TEXT)
+RETURNS BOOLEAN
+LANGUAGE sql
+STABLE
+AS $$
+	select exists (
+		select 1 from "companies"
+		where "id" = "p_company"
+		and "master" = "p_user"
+	) or exists (
+		select 1 from "company_members"
+		where "company" = "p_company"
+		and "user_id" = "p_user"
+		and "status" = 'active'
+		and (
+			"role" = 'co_master'
+			or ("role" = 'assistant' and "p_permission" IN ('events', 'apps'))
+		)
+	);
+$$;
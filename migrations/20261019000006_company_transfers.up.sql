@@ -0,0 +1,13 @@
+CREATE TABLE "company_transfers" (
+	"company"      UUID         NOT NULL,
+	"to_user"      UUID         NOT NULL,
+	"created_at"   TIMESTAMPTZ  NOT NULL  DEFAULT CURRENT_TIMESTAMP,
+
+	CONSTRAINT "PK_company_transfers" PRIMARY KEY ("company"),
+	CONSTRAINT "FK_company_transfers_companies" FOREIGN KEY ("company")
+		REFERENCES "companies"("id")
+		ON DELETE CASCADE,
+	CONSTRAINT "FK_company_transfers_users" FOREIGN KEY ("to_user")
+		REFERENCES "users"("id")
+		ON DELETE CASCADE
+);
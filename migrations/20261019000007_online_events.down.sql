@@ -0,0 +1,5 @@
+ALTER TABLE "events"
+DROP CONSTRAINT "venue_consistency_check",
+DROP COLUMN "join_link",
+DROP COLUMN "online_platform",
+DROP COLUMN "venue_type";
@@ -0,0 +1,12 @@
+ALTER TABLE "events"
+ADD COLUMN "venue_type" VARCHAR(8) NOT NULL DEFAULT 'offline'
+	CONSTRAINT "venue_type_check" CHECK ("venue_type" IN ('offline', 'online')),
+ADD COLUMN "online_platform" VARCHAR(32),
+ADD COLUMN "join_link" TEXT;
+
+-- у онлайн-игры нет локации, у офлайн-игры нет платформы и ссылки
+ALTER TABLE "events"
+ADD CONSTRAINT "venue_consistency_check" CHECK (
+	("venue_type" = 'offline' AND "online_platform" IS NULL AND "join_link" IS NULL)
+	OR ("venue_type" = 'online' AND "location" IS NULL)
+);
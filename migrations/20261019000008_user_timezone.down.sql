@@ -0,0 +1 @@
+DROP FUNCTION "user_timezone"(UUID);
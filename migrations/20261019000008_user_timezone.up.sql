@@ -0,0 +1,20 @@
+-- часовой пояс пользователя по настройкам профиля (tz_variant)
+CREATE FUNCTION "user_timezone"("p_user" UUID)
+RETURNS TEXT
+LANGUAGE sql
+STABLE
+AS $$
+	select case
+		when u."tz_variant" = 'city' then coalesce(c."own_timezone", r."timezone")
+		-- персональное смещение хранится числом часов, в зонах Etc/GMT знак инвертирован
+		when u."tz_variant" = 'own' and u."own_tz" ~ '^[+-]?[0-9]{1,2}$' then
+			'Etc/GMT' || case when u."own_tz"::int > 0 then '-' else '+' end || abs(u."own_tz"::int)
+		when u."tz_variant" = 'own' then u."own_tz"
+	end
+	from "users" u
+	left join "cities" c
+		on c."name" = u."city"
+	left join "regions" r
+		on r."name" = c."region"
+	where u."id" = "p_user";
+$$;
@@ -0,0 +1,3 @@
+ALTER TABLE "users"
+ADD COLUMN "calendar_token" VARCHAR(64) DEFAULT NULL
+	CONSTRAINT "UQ_users_calendar_token" UNIQUE;
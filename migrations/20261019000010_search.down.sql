@@ -0,0 +1,7 @@
+DROP INDEX "IX_users_search";
+DROP INDEX "IX_locations_search";
+DROP INDEX "IX_companies_search";
+
+ALTER TABLE "users" DROP COLUMN "search_vector";
+ALTER TABLE "locations" DROP COLUMN "search_vector";
+ALTER TABLE "companies" DROP COLUMN "search_vector";
@@ -0,0 +1,23 @@
+-- имена и описания ищутся с русской морфологией, никнеймы - как есть
+ALTER TABLE "companies"
+ADD COLUMN "search_vector" TSVECTOR GENERATED ALWAYS AS (
+	setweight(to_tsvector('russian', coalesce("name", '')), 'A')
+	|| setweight(to_tsvector('russian', coalesce("system", '')), 'B')
+	|| setweight(to_tsvector('russian', coalesce("description", '')), 'C')
+) STORED;
+
+ALTER TABLE "locations"
+ADD COLUMN "search_vector" TSVECTOR GENERATED ALWAYS AS (
+	setweight(to_tsvector('russian', coalesce("name", '')), 'A')
+	|| setweight(to_tsvector('russian', coalesce("address", '')), 'B')
+	|| setweight(to_tsvector('russian', coalesce("description", '')), 'C')
+) STORED;
+
+ALTER TABLE "users"
+ADD COLUMN "search_vector" TSVECTOR GENERATED ALWAYS AS (
+	to_tsvector('simple', coalesce("nickname", ''))
+) STORED;
+
+CREATE INDEX "IX_companies_search" ON "companies" USING GIN ("search_vector");
+CREATE INDEX "IX_locations_search" ON "locations" USING GIN ("search_vector");
+CREATE INDEX "IX_users_search" ON "users" USING GIN ("search_vector");
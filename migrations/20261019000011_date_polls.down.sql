@@ -0,0 +1,3 @@
+DROP TABLE "date_poll_votes";
+DROP TABLE "date_poll_slots";
+DROP TABLE "date_polls";
@@ -0,0 +1,42 @@
+CREATE TABLE "date_polls" (
+	"id"       UUID  DEFAULT uuid_v6(),
+	"company"  UUID  NOT NULL,
+	"title"    TEXT  DEFAULT NULL,
+	-- событие, созданное по итогам опроса; после этого голосование закрыто
+	"event"    UUID  DEFAULT NULL,
+
+	CONSTRAINT "PK_date_polls" PRIMARY KEY ("id"),
+	CONSTRAINT "FK_date_polls_companies" FOREIGN KEY ("company")
+		REFERENCES "companies"("id")
+		ON DELETE CASCADE,
+	CONSTRAINT "FK_date_polls_events" FOREIGN KEY ("event")
+		REFERENCES "events"("id")
+		ON DELETE SET NULL
+);
+
+CREATE TABLE "date_poll_slots" (
+	"id"    UUID         DEFAULT uuid_v6(),
+	"poll"  UUID         NOT NULL,
+	"date"  TIMESTAMPTZ  NOT NULL,
+
+	CONSTRAINT "PK_date_poll_slots" PRIMARY KEY ("id"),
+	CONSTRAINT "UQ_date_poll_slots" UNIQUE ("poll", "date"),
+	CONSTRAINT "FK_date_poll_slots_date_polls" FOREIGN KEY ("poll")
+		REFERENCES "date_polls"("id")
+		ON DELETE CASCADE
+);
+
+CREATE TABLE "date_poll_votes" (
+	"slot"     UUID  NOT NULL,
+	"user_id"  UUID  NOT NULL,
+	"answer"   VARCHAR(8)  NOT NULL
+					CONSTRAINT "poll_answer_check" CHECK ("answer" IN ('yes', 'maybe', 'no')),
+
+	CONSTRAINT "PK_date_poll_votes" PRIMARY KEY ("slot", "user_id"),
+	CONSTRAINT "FK_date_poll_votes_date_poll_slots" FOREIGN KEY ("slot")
+		REFERENCES "date_poll_slots"("id")
+		ON DELETE CASCADE,
+	CONSTRAINT "FK_date_poll_votes_users" FOREIGN KEY ("user_id")
+		REFERENCES "users"("id")
+		ON DELETE CASCADE
+);
@@ -0,0 +1,15 @@
+-- еженедельное расписание свободного времени игрока в его часовом поясе.
+-- окно, у которого конец раньше начала, переходит через полночь
+CREATE TABLE "user_availability" (
+	"user_id"     UUID      NOT NULL,
+	"weekday"     SMALLINT  NOT NULL
+					CONSTRAINT "availability_weekday_check" CHECK ("weekday" BETWEEN 1 AND 7),
+	"start_time"  TIME      NOT NULL,
+	"end_time"    TIME      NOT NULL,
+
+	CONSTRAINT "PK_user_availability" PRIMARY KEY ("user_id", "weekday", "start_time"),
+	CONSTRAINT "availability_range_check" CHECK ("start_time" <> "end_time"),
+	CONSTRAINT "FK_user_availability_users" FOREIGN KEY ("user_id")
+		REFERENCES "users"("id")
+		ON DELETE CASCADE
+);
@@ -0,0 +1,4 @@
+DROP TABLE "location_hours";
+
+ALTER TABLE "locations"
+DROP COLUMN "tables";
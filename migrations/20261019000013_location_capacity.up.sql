@@ -0,0 +1,19 @@
+-- сколько игр локация может принять одновременно (столов), NULL - без ограничений
+ALTER TABLE "locations"
+ADD COLUMN "tables" SMALLINT DEFAULT NULL
+	CONSTRAINT "positive_tables" CHECK ("tables" > 0);
+
+-- часы работы в поясе города локации. если их нет, локация считается открытой всегда
+CREATE TABLE "location_hours" (
+	"location"    UUID      NOT NULL,
+	"weekday"     SMALLINT  NOT NULL
+					CONSTRAINT "location_hours_weekday_check" CHECK ("weekday" BETWEEN 1 AND 7),
+	"open_time"   TIME      NOT NULL,
+	"close_time"  TIME      NOT NULL,
+
+	CONSTRAINT "PK_location_hours" PRIMARY KEY ("location", "weekday", "open_time"),
+	CONSTRAINT "location_hours_range_check" CHECK ("open_time" <> "close_time"),
+	CONSTRAINT "FK_location_hours_locations" FOREIGN KEY ("location")
+		REFERENCES "locations"("id")
+		ON DELETE CASCADE
+);
@@ -0,0 +1,8 @@
+DROP FUNCTION "geo_distance_km";
+
+DROP INDEX "IX_locations_coordinates";
+
+ALTER TABLE "locations"
+DROP CONSTRAINT "coordinates_pair_check",
+DROP COLUMN "longitude",
+DROP COLUMN "latitude";
@@ -0,0 +1,24 @@
+ALTER TABLE "locations"
+ADD COLUMN "latitude"  DOUBLE PRECISION DEFAULT NULL
+	CONSTRAINT "latitude_range_check" CHECK ("latitude" BETWEEN -90 AND 90),
+ADD COLUMN "longitude" DOUBLE PRECISION DEFAULT NULL
+	CONSTRAINT "longitude_range_check" CHECK ("longitude" BETWEEN -180 AND 180),
+ADD CONSTRAINT "coordinates_pair_check" CHECK (("latitude" IS NULL) = ("longitude" IS NULL));
+
+-- грубый отбор по широте перед точным расчётом расстояния
+CREATE INDEX "IX_locations_coordinates" ON "locations" ("latitude", "longitude")
+	WHERE "latitude" IS NOT NULL;
+
+-- расстояние по большому кругу (гаверсинус) в километрах
+CREATE FUNCTION "geo_distance_km"("p_lat1" FLOAT8, "p_lon1" FLOAT8, "p_lat2" FLOAT8, "p_lon2" FLOAT8)
+RETURNS FLOAT8
+LANGUAGE sql
+IMMUTABLE
+STRICT
+AS $$
+	select 2 * 6371 * asin(sqrt(
+		power(sin(radians("p_lat2" - "p_lat1") / 2), 2)
+		+ cos(radians("p_lat1")) * cos(radians("p_lat2"))
+			* power(sin(radians("p_lon2" - "p_lon1") / 2), 2)
+	));
+$$;
@@ -0,0 +1,17 @@
+-- входящие уведомления пользователя. SSE доставляет их сразу, если пользователь
+-- онлайн, а здесь они дожидаются его, пока не будут прочитаны
+CREATE TABLE "notifications" (
+	"id"          UUID         DEFAULT uuid_v6(),
+	"user_id"     UUID         NOT NULL,
+	"message"     TEXT         NOT NULL,
+	"created_at"  TIMESTAMPTZ  NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	"read_at"     TIMESTAMPTZ  DEFAULT NULL,
+
+	CONSTRAINT "PK_notifications" PRIMARY KEY ("id"),
+	CONSTRAINT "FK_notifications_users" FOREIGN KEY ("user_id")
+		REFERENCES "users"("id")
+		ON DELETE CASCADE
+);
+
+CREATE INDEX "IX_notifications_user" ON "notifications" ("user_id", "id");
+CREATE INDEX "IX_notifications_unread" ON "notifications" ("user_id") WHERE "read_at" IS NULL;
@@ -0,0 +1,3 @@
+-- структурированные данные уведомления; тип лежит в поле "type".
+-- у уведомлений, сохранённых до появления типов, остаётся только текст
+ALTER TABLE "notifications" ADD COLUMN "payload" JSONB DEFAULT NULL;
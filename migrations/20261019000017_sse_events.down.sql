@@ -0,0 +1 @@
+DROP SEQUENCE "sse_event_id";
@@ -0,0 +1,3 @@
+-- id событий SSE общие для всех экземпляров сервера: клиент может переподключиться
+-- к другому экземпляру и продолжить с того же Last-Event-ID
+CREATE SEQUENCE "sse_event_id" AS BIGINT;
@@ -0,0 +1,5 @@
+ALTER TABLE "users"
+DROP COLUMN "tg_link_expires_at",
+DROP COLUMN "tg_link_code",
+DROP COLUMN "tg_notifications",
+DROP COLUMN "tg_chat_id";
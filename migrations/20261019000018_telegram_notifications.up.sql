@@ -0,0 +1,10 @@
+-- чат с ботом, куда отправляются уведомления. Бот может писать только тем,
+-- кто сам начал с ним диалог, поэтому tg_id для этого недостаточно
+ALTER TABLE "users"
+ADD COLUMN "tg_chat_id" BIGINT DEFAULT NULL
+	CONSTRAINT "UQ_users_tg_chat_id" UNIQUE,
+ADD COLUMN "tg_notifications" BOOLEAN NOT NULL DEFAULT FALSE,
+-- одноразовый код для ссылки t.me/<бот>?start=<код>
+ADD COLUMN "tg_link_code" VARCHAR(64) DEFAULT NULL
+	CONSTRAINT "UQ_users_tg_link_code" UNIQUE,
+ADD COLUMN "tg_link_expires_at" TIMESTAMPTZ DEFAULT NULL;
@@ -0,0 +1,6 @@
+DROP FUNCTION "series_utc"(TIMESTAMP, TEXT, INTERVAL);
+DROP FUNCTION "series_local"(TIMESTAMPTZ, TEXT, INTERVAL);
+
+ALTER TABLE "event_series"
+DROP COLUMN "utc_offset",
+DROP COLUMN "timezone";
@@ -0,0 +1,29 @@
+-- пояс, в котором повторяется серия: переносы и исключения считаются по местному
+-- времени серии. Серия, созданная по абсолютному времени, хранит смещение первой игры
+ALTER TABLE "event_series"
+ADD COLUMN "timezone" TEXT DEFAULT NULL,
+ADD COLUMN "utc_offset" INTERVAL NOT NULL DEFAULT '0';
+
+-- местное время игры серии
+CREATE FUNCTION "series_local"("p_date" TIMESTAMPTZ, "p_timezone" TEXT, "p_offset" INTERVAL)
+RETURNS TIMESTAMP
+LANGUAGE sql
+STABLE
+AS $$
+	select case
+		when "p_timezone" is not null then "p_date" at time zone "p_timezone"
+		else "p_date" at time zone "p_offset"
+	end;
+$$;
+
+-- абсолютное время по местному времени серии
+CREATE FUNCTION "series_utc"("p_local" TIMESTAMP, "p_timezone" TEXT, "p_offset" INTERVAL)
+RETURNS TIMESTAMPTZ
+LANGUAGE sql
+STABLE
+AS $$
+	select case
+		when "p_timezone" is not null then "p_local" at time zone "p_timezone"
+		else "p_local" at time zone "p_offset"
+	end;
+$$;
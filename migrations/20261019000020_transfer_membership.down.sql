@@ -0,0 +1,2 @@
+ALTER TABLE "company_transfers"
+DROP COLUMN "stay_as";
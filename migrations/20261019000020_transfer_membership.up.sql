@@ -0,0 +1,5 @@
+-- роль, с которой прежний мастер остаётся в кампании после передачи.
+-- NULL - прежний мастер покидает кампанию
+ALTER TABLE "company_transfers"
+ADD COLUMN "stay_as" VARCHAR(16) DEFAULT NULL
+	CONSTRAINT "transfer_stay_as_check" CHECK ("stay_as" IN ('player', 'assistant', 'co_master'));
@@ -0,0 +1 @@
+DROP TABLE "sse_events";
@@ -0,0 +1,13 @@
+-- тела событий SSE. NOTIFY передаёт только id: полезная нагрузка NOTIFY
+-- ограничена 8000 байт. Хранятся недолго - только пока их не прочитают экземпляры сервера
+CREATE TABLE "sse_events" (
+	"id"          BIGINT       DEFAULT nextval('sse_event_id'),
+	"user_id"     UUID         DEFAULT NULL,
+	"event"       VARCHAR(64)  NOT NULL,
+	"data"        TEXT         NOT NULL,
+	"created_at"  TIMESTAMPTZ  NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+	CONSTRAINT "PK_sse_events" PRIMARY KEY ("id")
+);
+
+CREATE INDEX "IX_sse_events_created_at" ON "sse_events" ("created_at");
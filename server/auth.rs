@@ -1,5 +1,5 @@
 use std::{
-	sync::LazyLock,
+	sync::{Arc, LazyLock},
 	time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
@@ -8,7 +8,9 @@ use argon2::{
 	password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString, rand_core::OsRng},
 };
 use axum::{
+	Extension,
 	body::Body,
+	extract::State,
 	http::Request,
 	middleware::Next,
 	response::{IntoResponse, Response},
@@ -19,11 +21,13 @@ use josekit::{
 	jwt::{self, JwtPayload},
 };
 use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
 use uuid::Uuid;
 
 use crate::{
 	cookie::{extract_jwt_from_cookie, remove_auth_cookie},
 	shared::prevent_timing_attack,
+	state::AppState,
 	system_models::{AppError, AppResponse, CoreResult},
 };
 
@@ -148,6 +152,21 @@ pub(super) async fn auth_and_verified_middleware(
 	next.run(req).await
 }
 
+/// должен идти после `auth_and_verified_middleware`, от которого получает id пользователя
+pub(super) async fn admin_middleware(
+	State(state): State<Arc<AppState>>,
+	Extension(user_id): Extension<Uuid>,
+	req: Request<Body>,
+	next: Next,
+) -> Response {
+	match state.repo.is_admin(user_id).await {
+		Ok(true) => next.run(req).await,
+		// пользователь вошёл, но прав не хватает - это не ошибка входа
+		Ok(false) => AppError::scenario_error("Недостаточно прав", None::<&str>).into_response(),
+		Err(err) => err.into_response(),
+	}
+}
+
 pub(super) async fn optional_auth_middleware(
 	cookie_jar: CookieJar,
 	mut req: Request<Body>,
@@ -233,6 +252,15 @@ pub(super) fn generate_jwt(user_id: Uuid, verified: bool) -> CoreResult<String>
 	Ok(token)
 }
 
+/// Ключ для подписи служебных токенов, производный от приватного ключа сервера
+pub(crate) fn derive_secret(purpose: &str) -> [u8; 32] {
+	let mut hasher = Sha256::new();
+	hasher.update(purpose.as_bytes());
+	hasher.update(&*PRIVATE_KEY);
+
+	hasher.finalize().into()
+}
+
 pub(super) fn init_static() {
 	let _ = *PRIVATE_KEY;
 	println!("+ a private key is ok");
@@ -3,6 +3,7 @@ pub fn init_static() {
 	crate::dto::init_static();
 
 	crate::email::init_static();
+	crate::invite::init_static();
 	crate::telegram::init_static();
 
 	#[cfg(feature = "vite")]
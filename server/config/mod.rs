@@ -48,6 +48,14 @@ pub(super) fn get_db_max_pool_size() -> u32 {
 	);
 }
 
+/// Пользователь, которому при запуске выдаётся роль администратора: иначе
+/// первого администратора назначить некому
+pub(super) fn get_admin_email() -> Option<String> {
+	return readEnvVar("ADMIN_EMAIL")
+		.ok()
+		.filter(|email| !email.trim().is_empty());
+}
+
 #[cfg(not(feature = "https"))]
 pub(super) fn get_cookie_params() -> (&'static str, &'static str) {
 	if is_test() {
@@ -0,0 +1,40 @@
+use serde::Deserialize;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct MergeLocationsDto {
+	pub duplicate: Uuid,
+	pub canonical: Uuid,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct MergeCitiesDto {
+	pub duplicate: String,
+	pub canonical: String,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum UserRole {
+	User,
+	Admin,
+}
+
+impl UserRole {
+	pub const fn as_str(self) -> &'static str {
+		match self {
+			UserRole::User => "user",
+			UserRole::Admin => "admin",
+		}
+	}
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct UpdateRoleDto {
+	pub role: UserRole,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct AnnouncementDto {
+	pub message: String,
+}
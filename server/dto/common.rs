@@ -2,10 +2,10 @@ use ::std::{error::Error, sync::LazyLock};
 use axum::{
 	Form, Json, RequestExt,
 	extract::{
-		FromRequest, Query, Request,
-		rejection::{FormRejection, JsonRejection},
+		FromRequest, FromRequestParts, Query, Request,
+		rejection::{FormRejection, JsonRejection, QueryRejection},
 	},
-	http::{HeaderValue, Method, header},
+	http::{HeaderValue, Method, header, request::Parts},
 };
 use serde::{Deserialize, de::DeserializeOwned};
 
@@ -33,9 +33,9 @@ where
 	async fn from_request(req: Request, _: &S) -> Result<Self, Self::Rejection> {
 		if req.method() == Method::GET {
 			let query = req.extract::<Query<T>, _>().await;
-			query.map(|Query(dto)| Dto(dto)).map_err(|err| {
-				AppError::scenario_error("Переданы некорректные параметры запроса", Some(err))
-			})
+			query
+				.map(|Query(dto)| Dto(dto))
+				.map_err(handle_query_rejection)
 		} else {
 			if let Some(content_type) = req.headers().get(header::CONTENT_TYPE)
 				&& content_type == URL_ENCODED.as_ref()
@@ -54,6 +54,28 @@ where
 	}
 }
 
+/// Параметры строки запроса у запросов с телом, которое читает `Dto`
+pub(crate) struct QueryDto<T>(pub(crate) T);
+
+impl<T, S> FromRequestParts<S> for QueryDto<T>
+where
+	T: DeserializeOwned,
+	S: Send + Sync,
+{
+	type Rejection = AppError;
+
+	async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+		let query = Query::<T>::from_request_parts(parts, state).await;
+		query
+			.map(|Query(dto)| QueryDto(dto))
+			.map_err(handle_query_rejection)
+	}
+}
+
+fn handle_query_rejection(err: QueryRejection) -> AppError {
+	return AppError::scenario_error("Переданы некорректные параметры запроса", Some(err));
+}
+
 fn handle_json_rejection(err: JsonRejection) -> AppError {
 	return match err {
 		JsonRejection::JsonDataError(data_err) => match data_err.source() {
@@ -1,6 +1,31 @@
 use serde::Deserialize;
+use uuid::Uuid;
 
-use crate::shared::deserialize_missed;
+use crate::{
+	dto::event::{EventTimeDto, VenueDto},
+	shared::deserialize_missed,
+};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Visibility {
+	#[default]
+	Public,
+	// доступна по прямой ссылке, но не попадает в списки
+	Unlisted,
+	// видна только мастеру, участникам кампании и приглашенным
+	Private,
+}
+
+impl Visibility {
+	pub const fn as_str(self) -> &'static str {
+		match self {
+			Visibility::Public => "public",
+			Visibility::Unlisted => "unlisted",
+			Visibility::Private => "private",
+		}
+	}
+}
 
 #[derive(Deserialize)]
 pub(crate) struct ReadCompaniesDto {
@@ -18,6 +43,32 @@ pub(crate) struct ApiCompanyDto {
 	pub cover_link: Option<String>,
 	#[serde(default)]
 	pub event_style: Option<String>,
+	#[serde(default)]
+	pub visibility: Visibility,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct NewOneShotDto {
+	pub name: String,
+	pub system: String,
+	#[serde(default)]
+	pub description: Option<String>,
+	#[serde(default)]
+	pub cover_link: Option<String>,
+	#[serde(default)]
+	pub event_style: Option<String>,
+	#[serde(default)]
+	pub visibility: Visibility,
+	#[serde(default)]
+	pub location: Option<Uuid>,
+	#[serde(flatten)]
+	pub time: EventTimeDto,
+	#[serde(default)]
+	pub max_slots: Option<i16>,
+	#[serde(default)]
+	pub plan_duration: Option<i16>,
+	#[serde(flatten)]
+	pub venue: VenueDto,
 }
 
 #[derive(Deserialize)]
@@ -30,10 +81,71 @@ pub(crate) struct ApiUpdateCompanyDto {
 	pub description: Option<Option<String>>,
 	#[serde(default, deserialize_with = "deserialize_missed")]
 	pub event_style: Option<Option<String>>,
+	#[serde(default)]
+	pub visibility: Option<Visibility>,
 }
 
 impl ApiUpdateCompanyDto {
 	pub const fn is_empty(&self) -> bool {
-		self.name.is_none() && self.system.is_none() && self.description.is_none()
+		self.name.is_none()
+			&& self.system.is_none()
+			&& self.description.is_none()
+			&& self.visibility.is_none()
+	}
+}
+
+#[derive(Deserialize)]
+pub(crate) struct InviteMemberDto {
+	pub user: Uuid,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct TransferCompanyDto {
+	pub user: Uuid,
+	/// роль, с которой мастер остаётся в кампании после передачи.
+	/// Без неё он покидает кампанию
+	#[serde(default)]
+	pub stay_as: Option<MemberRole>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum MemberRole {
+	Player,
+	// может вести игры и разбирать заявки
+	Assistant,
+	// дополнительно может менять настройки кампании и состав участников
+	CoMaster,
+}
+
+impl MemberRole {
+	pub const fn as_str(self) -> &'static str {
+		match self {
+			MemberRole::Player => "player",
+			MemberRole::Assistant => "assistant",
+			MemberRole::CoMaster => "co_master",
+		}
+	}
+}
+
+#[derive(Deserialize)]
+pub(crate) struct UpdateMemberRoleDto {
+	pub role: MemberRole,
+}
+
+/// Права, проверяемые sql-функцией `has_company_permission`.
+/// Право `apps` проверяется только внутри запросов к заявкам
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CompanyPermission {
+	Events,
+	Company,
+}
+
+impl CompanyPermission {
+	pub const fn as_str(self) -> &'static str {
+		match self {
+			CompanyPermission::Events => "events",
+			CompanyPermission::Company => "company",
+		}
 	}
 }
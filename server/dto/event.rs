@@ -1,8 +1,12 @@
-use chrono::{DateTime, FixedOffset};
-use serde::Deserialize;
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::shared::deserialize_list;
+use crate::{
+	dto::company::Visibility,
+	recurrence::{Frequency, RecurrenceRule},
+	shared::{deserialize_list, deserialize_missed},
+};
 
 #[derive(Debug, Deserialize)]
 pub(crate) struct ReadEventsDto {
@@ -27,25 +31,201 @@ pub(crate) struct ReadEventsDto {
 	pub company: Vec<Uuid>,
 }
 
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum VenueType {
+	#[default]
+	Offline,
+	Online,
+}
+
+impl VenueType {
+	pub const fn as_str(self) -> &'static str {
+		match self {
+			VenueType::Offline => "offline",
+			VenueType::Online => "online",
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum OnlinePlatform {
+	Discord,
+	Foundry,
+	Roll20,
+	OwlbearRodeo,
+	TabletopSimulator,
+	Telegram,
+	Other,
+}
+
+impl OnlinePlatform {
+	pub const fn as_str(self) -> &'static str {
+		match self {
+			OnlinePlatform::Discord => "discord",
+			OnlinePlatform::Foundry => "foundry",
+			OnlinePlatform::Roll20 => "roll20",
+			OnlinePlatform::OwlbearRodeo => "owlbear_rodeo",
+			OnlinePlatform::TabletopSimulator => "tabletop_simulator",
+			OnlinePlatform::Telegram => "telegram",
+			OnlinePlatform::Other => "other",
+		}
+	}
+}
+
+#[derive(Default, Deserialize)]
+pub(crate) struct VenueDto {
+	#[serde(default)]
+	pub venue_type: VenueType,
+	#[serde(default)]
+	pub online_platform: Option<OnlinePlatform>,
+	#[serde(default)]
+	pub join_link: Option<String>,
+}
+
+/// Время начала игры: либо абсолютное `date`, либо местное `local_date`
+/// в поясе `timezone` (по умолчанию - пояс локации, затем пояс ведущего)
+#[derive(Deserialize)]
+pub(crate) struct EventTimeDto {
+	#[serde(default)]
+	pub date: Option<DateTime<FixedOffset>>,
+	#[serde(default)]
+	pub local_date: Option<NaiveDateTime>,
+	#[serde(default)]
+	pub timezone: Option<String>,
+}
+
 #[derive(Deserialize)]
 pub(crate) struct NewEventDto {
 	pub company: Uuid,
 	#[serde(default)]
 	pub location: Option<Uuid>,
-	pub date: DateTime<FixedOffset>,
+	#[serde(flatten)]
+	pub time: EventTimeDto,
+	#[serde(default)]
+	pub max_slots: Option<i16>,
+	#[serde(default)]
+	pub plan_duration: Option<i16>,
+	#[serde(default)]
+	pub visibility: Visibility,
+	#[serde(flatten)]
+	pub venue: VenueDto,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct NewSeriesDto {
+	pub company: Uuid,
+	#[serde(default)]
+	pub location: Option<Uuid>,
+	#[serde(flatten)]
+	pub time: EventTimeDto,
+	#[serde(default)]
+	pub max_slots: Option<i16>,
+	#[serde(default)]
+	pub plan_duration: Option<i16>,
+	#[serde(default)]
+	pub visibility: Visibility,
+	#[serde(flatten)]
+	pub venue: VenueDto,
+	pub frequency: Frequency,
+	#[serde(default)]
+	pub until: Option<NaiveDate>,
+	#[serde(default)]
+	pub count: Option<u16>,
+	#[serde(default)]
+	pub exceptions: Vec<NaiveDate>,
+}
+
+/// Даты, на которые игры серии не проводятся. Уже созданные игры на эти даты отменяются
+#[derive(Deserialize)]
+pub(crate) struct SeriesExceptionsDto {
+	pub dates: Vec<NaiveDate>,
+}
+
+/// Импорт игр из файла iCalendar. Без `confirm` возвращается только предпросмотр
+#[derive(Deserialize)]
+pub(crate) struct ImportCalendarDto {
+	pub calendar: String,
+	#[serde(default)]
+	pub location: Option<Uuid>,
 	#[serde(default)]
 	pub max_slots: Option<i16>,
 	#[serde(default)]
 	pub plan_duration: Option<i16>,
+	#[serde(default)]
+	pub visibility: Visibility,
+	#[serde(flatten)]
+	pub venue: VenueDto,
+	#[serde(default)]
+	pub confirm: bool,
+}
+
+/// Событие из импортируемого календаря и игры, которые будут по нему созданы
+#[derive(Serialize)]
+pub(crate) struct ImportedGameDto {
+	pub uid: Option<String>,
+	pub summary: Option<String>,
+	pub location: Option<String>,
+	pub dates: Vec<DateTime<FixedOffset>>,
+	pub plan_duration: Option<i16>,
+	pub rule: Option<RecurrenceRule>,
+	pub exceptions: Vec<NaiveDate>,
+	/// пояс повторения серии, если время задано в поясе
+	pub timezone: Option<String>,
+	/// правило даёт больше игр, чем создаётся за раз: импортируются только первые
+	pub truncated: bool,
+	/// причина, по которой событие не будет импортировано
+	pub skipped: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum EditScope {
+	#[default]
+	This,
+	Following,
 }
 
 #[derive(Deserialize)]
 pub(crate) struct UpdateEventDto {
 	#[serde(default)]
 	pub location: Option<Uuid>,
-	pub date: DateTime<FixedOffset>,
+	#[serde(flatten)]
+	pub time: EventTimeDto,
 	#[serde(default)]
 	pub max_slots: Option<i16>,
 	#[serde(default)]
 	pub plan_duration: Option<i16>,
+	#[serde(default)]
+	pub visibility: Option<Visibility>,
+	#[serde(default)]
+	pub venue_type: Option<VenueType>,
+	#[serde(default, deserialize_with = "deserialize_missed")]
+	pub online_platform: Option<Option<OnlinePlatform>>,
+	#[serde(default, deserialize_with = "deserialize_missed")]
+	pub join_link: Option<Option<String>>,
+	#[serde(default)]
+	pub scope: EditScope,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct EventInviteDto {
+	pub user: Uuid,
+	#[serde(default)]
+	pub ttl_hours: Option<u16>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct InviteQuery {
+	#[serde(default)]
+	pub invite: Option<String>,
+}
+
+/// Пересечения с другими играми пользователя по умолчанию только
+/// возвращаются как предупреждение, `strict` отклоняет такой запрос
+#[derive(Deserialize)]
+pub(crate) struct ConflictQuery {
+	#[serde(default)]
+	pub strict: bool,
 }
@@ -1,6 +1,12 @@
+use chrono::{DateTime, FixedOffset};
 use serde::Deserialize;
 
-use crate::shared::deser_empty_str_as_none;
+use crate::{dto::schedule::WeeklyWindowDto, shared::deser_empty_str_as_none};
+
+pub(crate) const DEFAULT_RADIUS_KM: f64 = 10.0;
+pub(crate) const MAX_RADIUS_KM: f64 = 300.0;
+pub(crate) const DEFAULT_NEAR_LIMIT: u16 = 50;
+pub(crate) const MAX_NEAR_LIMIT: u16 = 200;
 
 #[derive(Deserialize)]
 pub(crate) struct ReadLocationDto {
@@ -23,4 +29,56 @@ pub(crate) struct NewLocationDto {
 	pub city: Option<String>,
 	#[serde(default, deserialize_with = "deser_empty_str_as_none")]
 	pub map_link: Option<String>,
+	#[serde(default)]
+	pub tables: Option<i16>,
+	#[serde(default)]
+	pub opening_hours: Vec<WeeklyWindowDto>,
+	/// если координаты не указаны, они берутся из `map_link`
+	#[serde(default)]
+	pub latitude: Option<f64>,
+	#[serde(default)]
+	pub longitude: Option<f64>,
+}
+
+/// Вместимость и часы работы локации целиком заменяют прежние
+#[derive(Deserialize)]
+pub(crate) struct LocationCapacityDto {
+	#[serde(default)]
+	pub tables: Option<i16>,
+	#[serde(default)]
+	pub opening_hours: Vec<WeeklyWindowDto>,
+}
+
+/// Поиск рядом с точкой. Период учитывается только для игр,
+/// по умолчанию - ближайшие 30 дней
+#[derive(Deserialize)]
+pub(crate) struct NearDto {
+	pub lat: f64,
+	pub lon: f64,
+	/// радиус в километрах
+	#[serde(default)]
+	pub radius: Option<f64>,
+	#[serde(default)]
+	pub limit: Option<u16>,
+	#[serde(default)]
+	pub date_from: Option<DateTime<FixedOffset>>,
+	#[serde(default)]
+	pub date_to: Option<DateTime<FixedOffset>>,
+}
+
+impl NearDto {
+	pub(crate) fn radius(&self) -> f64 {
+		self
+			.radius
+			.filter(|radius| radius.is_finite() && *radius > 0.0)
+			.unwrap_or(DEFAULT_RADIUS_KM)
+			.min(MAX_RADIUS_KM)
+	}
+
+	pub(crate) fn limit(&self) -> u16 {
+		self
+			.limit
+			.unwrap_or(DEFAULT_NEAR_LIMIT)
+			.clamp(1, MAX_NEAR_LIMIT)
+	}
 }
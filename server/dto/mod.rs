@@ -1,11 +1,17 @@
+pub(crate) mod admin;
 pub(crate) mod auth;
 mod common;
 pub(crate) mod company;
 pub(crate) mod event;
 pub(crate) mod location;
+pub(crate) mod notification;
+pub(crate) mod page;
+pub(crate) mod poll;
 pub(crate) mod region;
+pub(crate) mod schedule;
+pub(crate) mod search;
 
-pub(crate) use common::{Dto, FileLinkDto};
+pub(crate) use common::{Dto, FileLinkDto, QueryDto};
 
 pub(super) fn init_static() {
 	auth::init_static();
@@ -0,0 +1,13 @@
+use serde::Deserialize;
+
+/// Фильтр входящих уведомлений, читается из query вместе с `PageDto`
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct ReadNotificationsDto {
+	#[serde(default)]
+	pub unread: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct TelegramNotificationsDto {
+	pub enabled: bool,
+}
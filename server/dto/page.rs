@@ -0,0 +1,107 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{shared::deser_empty_str_as_none, system_models::AppError};
+
+pub(crate) const DEFAULT_PAGE_LIMIT: u16 = 50;
+pub(crate) const MAX_PAGE_LIMIT: u16 = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum SortField {
+	Date,
+	Name,
+	/// порядок создания: id - это uuid v6, упорядоченный по времени
+	Created,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum SortDirection {
+	#[default]
+	Asc,
+	Desc,
+}
+
+/// Параметры страницы, общие для всех списков. Читаются из query отдельно
+/// от фильтров списка
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct PageDto {
+	#[serde(default)]
+	pub limit: Option<u16>,
+	#[serde(default, deserialize_with = "deser_empty_str_as_none")]
+	pub cursor: Option<String>,
+	#[serde(default)]
+	pub sort: Option<SortField>,
+	#[serde(default)]
+	pub direction: SortDirection,
+}
+
+impl PageDto {
+	pub(crate) fn limit(&self) -> u16 {
+		self
+			.limit
+			.unwrap_or(DEFAULT_PAGE_LIMIT)
+			.clamp(1, MAX_PAGE_LIMIT)
+	}
+}
+
+/// Ключ последнего элемента страницы. Клиент получает его как непрозрачную
+/// строку и передаёт обратно без изменений
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Cursor {
+	pub id: Uuid,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub date: Option<DateTime<Utc>>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub name: Option<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub rank: Option<i32>,
+}
+
+impl Cursor {
+	pub(crate) fn encode(&self) -> String {
+		hex::encode(serde_json::to_vec(self).unwrap_or_default())
+	}
+
+	pub(crate) fn decode(cursor: &str) -> Result<Self, AppError> {
+		hex::decode(cursor)
+			.ok()
+			.and_then(|bytes| serde_json::from_slice(&bytes).ok())
+			.ok_or_else(|| AppError::scenario_error("Некорректный курсор страницы", None::<&str>))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_cursor_roundtrip() {
+		let cursor = Cursor {
+			id: Uuid::from_u128(7),
+			date: None,
+			name: Some(String::from("Антикафе")),
+			rank: Some(1),
+		};
+		let decoded = Cursor::decode(&cursor.encode()).unwrap();
+
+		assert_eq!(decoded.id, cursor.id);
+		assert_eq!(decoded.name, cursor.name);
+		assert_eq!(decoded.rank, cursor.rank);
+		assert!(Cursor::decode("zz").is_err());
+	}
+
+	#[test]
+	fn test_limit_is_clamped() {
+		let page = |limit| PageDto {
+			limit,
+			..PageDto::default()
+		};
+
+		assert_eq!(page(None).limit(), DEFAULT_PAGE_LIMIT);
+		assert_eq!(page(Some(0)).limit(), 1);
+		assert_eq!(page(Some(1000)).limit(), MAX_PAGE_LIMIT);
+	}
+}
@@ -0,0 +1,65 @@
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::dto::{
+	company::Visibility,
+	event::{EventTimeDto, VenueDto},
+};
+
+pub(crate) const MAX_POLL_SLOTS: usize = 20;
+
+/// Опрос по выбору даты. Время каждого варианта задаётся так же,
+/// как время начала игры
+#[derive(Deserialize)]
+pub(crate) struct NewDatePollDto {
+	#[serde(default)]
+	pub title: Option<String>,
+	pub slots: Vec<EventTimeDto>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum PollAnswer {
+	Yes,
+	Maybe,
+	No,
+}
+
+impl PollAnswer {
+	pub const fn as_str(self) -> &'static str {
+		match self {
+			PollAnswer::Yes => "yes",
+			PollAnswer::Maybe => "maybe",
+			PollAnswer::No => "no",
+		}
+	}
+}
+
+#[derive(Deserialize)]
+pub(crate) struct PollVoteDto {
+	pub slot: Uuid,
+	pub answer: PollAnswer,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct PollVotesDto {
+	pub votes: Vec<PollVoteDto>,
+}
+
+/// Создание игры по итогам опроса. Без `slot` выбирается вариант
+/// с наибольшим числом голосов "да"
+#[derive(Deserialize)]
+pub(crate) struct ConvertPollDto {
+	#[serde(default)]
+	pub slot: Option<Uuid>,
+	#[serde(default)]
+	pub location: Option<Uuid>,
+	#[serde(default)]
+	pub max_slots: Option<i16>,
+	#[serde(default)]
+	pub plan_duration: Option<i16>,
+	#[serde(default)]
+	pub visibility: Visibility,
+	#[serde(flatten)]
+	pub venue: VenueDto,
+}
@@ -0,0 +1,61 @@
+use chrono::NaiveTime;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::shared::deserialize_list;
+
+pub(crate) const MAX_AVAILABILITY_WINDOWS: usize = 28;
+pub(crate) const DEFAULT_HORIZON_DAYS: u16 = 14;
+pub(crate) const MAX_HORIZON_DAYS: u16 = 60;
+pub(crate) const DEFAULT_SUGGESTIONS: u16 = 5;
+pub(crate) const MAX_SUGGESTIONS: u16 = 20;
+
+/// Еженедельное окно: день недели (1 - понедельник) и местное время
+/// игрока или локации. `to` раньше `from` - окно до следующего утра
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+pub(crate) struct WeeklyWindowDto {
+	pub weekday: u8,
+	pub from: NaiveTime,
+	pub to: NaiveTime,
+}
+
+impl WeeklyWindowDto {
+	pub(crate) fn is_valid(&self) -> bool {
+		(1..=7).contains(&self.weekday) && self.from != self.to
+	}
+}
+
+#[derive(Deserialize)]
+pub(crate) struct AvailabilityDto {
+	pub windows: Vec<WeeklyWindowDto>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct SuggestionsDto {
+	/// игроки через запятую, по умолчанию - активные участники кампании
+	#[serde(default, deserialize_with = "deserialize_list")]
+	pub players: Vec<Uuid>,
+	#[serde(default)]
+	pub days: Option<u16>,
+	/// длительность игры в часах, как `plan_duration`
+	#[serde(default)]
+	pub duration: Option<i16>,
+	#[serde(default)]
+	pub limit: Option<u16>,
+}
+
+impl SuggestionsDto {
+	pub(crate) fn days(&self) -> u16 {
+		self
+			.days
+			.unwrap_or(DEFAULT_HORIZON_DAYS)
+			.clamp(1, MAX_HORIZON_DAYS)
+	}
+
+	pub(crate) fn limit(&self) -> u16 {
+		self
+			.limit
+			.unwrap_or(DEFAULT_SUGGESTIONS)
+			.clamp(1, MAX_SUGGESTIONS)
+	}
+}
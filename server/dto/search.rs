@@ -0,0 +1,81 @@
+use serde::Deserialize;
+
+use crate::shared::deserialize_list;
+
+pub(crate) const DEFAULT_SEARCH_LIMIT: u16 = 20;
+pub(crate) const MAX_SEARCH_LIMIT: u16 = 50;
+// длинные запросы обрезаются, чтобы не строить огромный tsquery
+const MAX_SEARCH_WORDS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum SearchKind {
+	Company,
+	Location,
+	Master,
+	Event,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct SearchDto {
+	pub q: String,
+	/// типы результатов через запятую, по умолчанию - все
+	#[serde(default, deserialize_with = "deserialize_list")]
+	pub kind: Vec<SearchKind>,
+	#[serde(default)]
+	pub limit: Option<u16>,
+}
+
+impl SearchDto {
+	pub(crate) fn limit(&self) -> u16 {
+		self
+			.limit
+			.unwrap_or(DEFAULT_SEARCH_LIMIT)
+			.clamp(1, MAX_SEARCH_LIMIT)
+	}
+
+	pub(crate) fn includes(&self, kind: SearchKind) -> bool {
+		self.kind.is_empty() || self.kind.contains(&kind)
+	}
+
+	/// Запрос для `to_tsquery`: каждое слово ищется по префиксу, чтобы поиск
+	/// работал по мере набора. Из слов выбрасывается всё, кроме букв и цифр,
+	/// поэтому операторы tsquery из пользовательского ввода не проходят
+	pub(crate) fn prefix_query(&self) -> Option<String> {
+		let words = self
+			.q
+			.split(|ch: char| !ch.is_alphanumeric())
+			.filter(|word| !word.is_empty())
+			.take(MAX_SEARCH_WORDS)
+			.map(|word| format!("{}:*", word.to_lowercase()))
+			.collect::<Vec<_>>();
+
+		(!words.is_empty()).then(|| words.join(" & "))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn search(q: &str) -> SearchDto {
+		SearchDto {
+			q: String::from(q),
+			kind: Vec::new(),
+			limit: None,
+		}
+	}
+
+	#[test]
+	fn test_prefix_query() {
+		assert_eq!(
+			search("Проклятие  Страда!").prefix_query().as_deref(),
+			Some("проклятие:* & страда:*")
+		);
+		assert_eq!(
+			search("dnd5e | !x & y:*").prefix_query().as_deref(),
+			Some("dnd5e:* & x:* & y:*")
+		);
+		assert_eq!(search(" ' & ").prefix_query(), None);
+	}
+}
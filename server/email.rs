@@ -9,7 +9,7 @@ use uuid::Uuid;
 
 use crate::system_models::{AppError, CoreResult};
 
-static EXTERNAL_HOST: LazyLock<String> = LazyLock::new(|| {
+pub(crate) static EXTERNAL_HOST: LazyLock<String> = LazyLock::new(|| {
 	::std::env::var("EXTERNAL_HOST").expect("EXTERNAL_HOST environment variable is not defined")
 });
 static SMTP_RELAY: LazyLock<String> = LazyLock::new(|| {
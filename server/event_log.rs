@@ -0,0 +1,265 @@
+use ::std::{
+	collections::VecDeque,
+	sync::{Mutex, PoisonError},
+};
+use uuid::Uuid;
+
+use crate::subscribers::Subscribers;
+
+/// Сколько последних событий SSE хранится для досылки после переподключения
+const LOG_CAPACITY: usize = 1024;
+
+/// Событие SSE. id выдаёт последовательность в БД, поэтому они общие
+/// для всех экземпляров сервера. Но выдаются они до коммита, и события
+/// приходят не обязательно по возрастанию id
+#[derive(Debug, Clone)]
+pub(crate) struct SseMessage {
+	pub id: u64,
+	/// порядок получения на этом экземпляре, назначается журналом
+	pub seq: u64,
+	/// `None` - для всех подключенных
+	pub user: Option<Uuid>,
+	/// имя события SSE
+	pub event: String,
+	pub data: String,
+}
+
+impl SseMessage {
+	pub(crate) fn is_for(&self, user: Option<Uuid>) -> bool {
+		self.user.is_none_or(|id| Some(id) == user)
+	}
+}
+
+/// `seq` - порядковый номер последнего полученного события: живой поток
+/// отдаёт только то, что получено позже
+#[derive(Debug)]
+pub(crate) enum Replay {
+	/// Всё пропущенное ещё в журнале
+	Missed { messages: Vec<SseMessage>, seq: u64 },
+	/// Часть пропущенного уже вытеснена из журнала или прошла мимо этого
+	/// экземпляра. Клиенту нужно перечитать входящие. `head` - id последнего
+	/// полученного события
+	Reset { head: u64, seq: u64 },
+}
+
+struct Ring {
+	/// id последнего полученного события или последний id на момент подписки
+	head: u64,
+	/// сколько событий получено, из этого счётчика берётся `SseMessage::seq`
+	received: u64,
+	/// последний id на момент подписки в БД, пока журнал помнит всё полученное после неё
+	anchor: Option<u64>,
+	/// в порядке получения. NOTIFY доставляет события в порядке коммитов,
+	/// поэтому он одинаков на всех экземплярах
+	messages: VecDeque<SseMessage>,
+}
+
+/// Журнал последних событий SSE для заголовка `Last-Event-ID`
+pub(crate) struct EventLog {
+	capacity: usize,
+	ring: Mutex<Ring>,
+}
+
+impl Default for EventLog {
+	fn default() -> Self {
+		Self::new(LOG_CAPACITY)
+	}
+}
+
+impl EventLog {
+	pub(crate) fn new(capacity: usize) -> Self {
+		Self {
+			capacity,
+			ring: Mutex::new(Ring {
+				head: 0,
+				received: 0,
+				anchor: None,
+				messages: VecDeque::with_capacity(capacity),
+			}),
+		}
+	}
+
+	/// Подписка на события в БД только что установлена: всё, что выдано до `head`
+	/// включительно, могло пройти мимо журнала, и досылать с событий до неё нельзя
+	pub(crate) fn resync(&self, head: u64) {
+		let mut ring = self.ring.lock().unwrap_or_else(PoisonError::into_inner);
+
+		ring.head = head;
+		ring.anchor = Some(head);
+		ring.messages.clear();
+	}
+
+	/// Запоминает событие и рассылает его подписчикам этого экземпляра. Рассылка
+	/// идёт под блокировкой журнала, чтобы досылка и живой поток не разошлись
+	pub(crate) fn record(&self, subscribers: &Subscribers, mut message: SseMessage) {
+		let mut ring = self.ring.lock().unwrap_or_else(PoisonError::into_inner);
+
+		ring.received += 1;
+		message.seq = ring.received;
+		ring.head = message.id;
+
+		if ring.messages.len() >= self.capacity {
+			ring.messages.pop_front();
+			ring.anchor = None;
+		}
+		ring.messages.push_back(message.clone());
+
+		subscribers.dispatch(message);
+	}
+
+	/// События для `user`, полученные после события `last_id`. Сравнивать id нельзя:
+	/// событие с меньшим id может прийти позже
+	pub(crate) fn replay(&self, last_id: u64, user: Option<Uuid>) -> Replay {
+		let ring = self.ring.lock().unwrap_or_else(PoisonError::into_inner);
+
+		let (head, seq) = (ring.head, ring.received);
+
+		let start = if ring.anchor == Some(last_id) {
+			0
+		} else if let Some(position) = ring.messages.iter().position(|m| m.id == last_id) {
+			position + 1
+		} else {
+			return Replay::Reset { head, seq };
+		};
+
+		let messages = ring
+			.messages
+			.iter()
+			.skip(start)
+			.filter(|message| message.is_for(user))
+			.cloned()
+			.collect();
+
+		Replay::Missed { messages, seq }
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use ::std::sync::Arc;
+
+	use super::*;
+
+	fn message(id: u64, user: Option<Uuid>) -> SseMessage {
+		SseMessage {
+			id,
+			seq: 0,
+			user,
+			event: String::from("test"),
+			data: id.to_string(),
+		}
+	}
+
+	fn ids(replay: Replay) -> Option<Vec<u64>> {
+		match replay {
+			Replay::Missed { messages, .. } => Some(messages.iter().map(|m| m.id).collect()),
+			Replay::Reset { .. } => None,
+		}
+	}
+
+	#[test]
+	fn test_replays_only_missed_events_of_the_user() {
+		let subscribers = Subscribers::default();
+		let log = EventLog::new(8);
+		let (alice, bob) = (Uuid::from_u128(1), Uuid::from_u128(2));
+
+		log.resync(9);
+		log.record(&subscribers, message(10, Some(alice)));
+		log.record(&subscribers, message(11, Some(bob)));
+		log.record(&subscribers, message(12, None));
+		log.record(&subscribers, message(13, Some(alice)));
+
+		assert_eq!(ids(log.replay(10, Some(alice))), Some(vec![12, 13]));
+		assert_eq!(ids(log.replay(13, Some(alice))), Some(vec![]));
+		assert_eq!(ids(log.replay(9, Some(bob))), Some(vec![11, 12]));
+	}
+
+	#[test]
+	fn test_resets_when_gap_is_evicted() {
+		let subscribers = Subscribers::default();
+		let log = EventLog::new(3);
+
+		// пропуски в последовательности не считаются потерей
+		for id in [1, 2, 5, 7] {
+			log.record(&subscribers, message(id, None));
+		}
+
+		assert_eq!(ids(log.replay(2, None)), Some(vec![5, 7]));
+		assert!(matches!(log.replay(1, None), Replay::Reset { head: 7, .. }));
+	}
+
+	#[test]
+	fn test_replays_events_received_out_of_order() {
+		let subscribers = Subscribers::default();
+		let log = EventLog::new(8);
+
+		log.resync(9);
+		// 10 закоммичено позже 11
+		for id in [11, 10, 12] {
+			log.record(&subscribers, message(id, None));
+		}
+
+		assert_eq!(ids(log.replay(9, None)), Some(vec![11, 10, 12]));
+		assert_eq!(ids(log.replay(11, None)), Some(vec![10, 12]));
+		assert_eq!(ids(log.replay(10, None)), Some(vec![12]));
+		assert!(matches!(
+			log.replay(12, None),
+			Replay::Missed { seq: 3, .. }
+		));
+	}
+
+	#[test]
+	fn test_global_events_reach_every_subscriber() {
+		let subscribers = Arc::new(Subscribers::default());
+		let log = EventLog::new(8);
+		let alice = Uuid::from_u128(1);
+
+		let mut personal = subscribers.subscribe(Some(alice));
+		let mut anonymous = subscribers.subscribe(None);
+
+		log.resync(0);
+		log.record(&subscribers, message(1, None));
+
+		for subscription in [&mut personal, &mut anonymous] {
+			let received = subscription.global.try_recv().unwrap();
+			assert_eq!((received.id, received.seq), (1, 1));
+		}
+		assert!(personal.personal.as_mut().unwrap().try_recv().is_err());
+
+		assert_eq!(ids(log.replay(0, Some(alice))), Some(vec![1]));
+		assert_eq!(ids(log.replay(0, None)), Some(vec![1]));
+	}
+
+	#[test]
+	fn test_resets_after_resubscription() {
+		let subscribers = Subscribers::default();
+		let log = EventLog::new(8);
+
+		log.resync(1);
+		log.record(&subscribers, message(2, None));
+		log.resync(5);
+		log.record(&subscribers, message(6, None));
+
+		assert!(matches!(log.replay(2, None), Replay::Reset { head: 6, .. }));
+		assert_eq!(ids(log.replay(5, None)), Some(vec![6]));
+	}
+
+	#[test]
+	fn test_resets_for_events_before_subscription() {
+		let log = EventLog::new(8);
+
+		assert!(matches!(log.replay(5, None), Replay::Reset { head: 0, .. }));
+
+		log.resync(99);
+
+		assert!(matches!(
+			log.replay(500, None),
+			Replay::Reset { head: 99, .. }
+		));
+		assert!(matches!(
+			log.replay(42, None),
+			Replay::Reset { head: 99, .. }
+		));
+		assert_eq!(ids(log.replay(99, None)), Some(vec![]));
+	}
+}
@@ -0,0 +1,186 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Coordinates {
+	pub latitude: f64,
+	pub longitude: f64,
+}
+
+impl Coordinates {
+	pub(crate) fn new(latitude: f64, longitude: f64) -> Option<Self> {
+		let valid = (-90.0..=90.0).contains(&latitude)
+			&& (-180.0..=180.0).contains(&longitude)
+			// нулевые координаты почти всегда означают незаполненное поле
+			&& (latitude != 0.0 || longitude != 0.0);
+
+		valid.then_some(Self {
+			latitude,
+			longitude,
+		})
+	}
+}
+
+/// Извлекает координаты из ссылки 2gis, Яндекс Карт или Google Maps без геокодирования.
+/// Метка места предпочтительнее центра карты, если в ссылке есть и то, и другое
+pub(crate) fn parse_map_link(link: &str) -> Option<Coordinates> {
+	let link = percent_decode(link.trim());
+	let (path, query) = link.split_once('?').unwrap_or((&link, ""));
+
+	if path.contains("yandex.") {
+		return ["pt", "whatshere[point]", "ll"]
+			.iter()
+			.find_map(|key| query_param(query, key))
+			.and_then(|value| pair(value, Order::LonLat));
+	}
+
+	if path.contains("2gis.") {
+		// https://2gis.ru/moscow/geo/70000001/37.617,55.755 или ?m=37.617,55.755/16
+		return path
+			.rsplit('/')
+			.find_map(|segment| pair(segment, Order::LonLat))
+			.or_else(|| {
+				query_param(query, "m")
+					.and_then(|value| value.split('/').next())
+					.and_then(|value| pair(value, Order::LonLat))
+			});
+	}
+
+	if path.contains("google.") || path.contains("goo.gl") {
+		// !3d55.75!4d37.61 - координаты самого места в ссылке на карточку
+		return place_data(path)
+			.or_else(|| {
+				["q", "query", "ll"]
+					.iter()
+					.find_map(|key| query_param(query, key))
+					.and_then(|value| pair(value, Order::LatLon))
+			})
+			.or_else(|| {
+				path
+					.split('/')
+					.find_map(|segment| segment.strip_prefix('@'))
+					.and_then(|value| pair(value, Order::LatLon))
+			});
+	}
+
+	None
+}
+
+#[derive(Clone, Copy)]
+enum Order {
+	LatLon,
+	LonLat,
+}
+
+/// Два первых числа через запятую. Всё после них (масштаб и т.п.) игнорируется
+fn pair(value: &str, order: Order) -> Option<Coordinates> {
+	let mut parts = value.split(',').map(str::trim);
+	let first = parts.next()?.parse::<f64>().ok()?;
+	let second = parts.next()?.parse::<f64>().ok()?;
+
+	match order {
+		Order::LatLon => Coordinates::new(first, second),
+		Order::LonLat => Coordinates::new(second, first),
+	}
+}
+
+fn place_data(path: &str) -> Option<Coordinates> {
+	let (_, after_lat) = path.split_once("!3d")?;
+	let (latitude, after_lon) = after_lat.split_once("!4d")?;
+	let longitude = after_lon.split('!').next()?;
+
+	Coordinates::new(latitude.parse().ok()?, longitude.parse().ok()?)
+}
+
+fn query_param<'a>(query: &'a str, key: &str) -> Option<&'a str> {
+	query
+		.split('&')
+		.filter_map(|pair| pair.split_once('='))
+		.find(|(name, _)| *name == key)
+		.map(|(_, value)| value)
+}
+
+fn percent_decode(value: &str) -> String {
+	let bytes = value.as_bytes();
+	let mut decoded = Vec::with_capacity(bytes.len());
+	let mut i = 0;
+
+	while i < bytes.len() {
+		let escaped = (bytes[i] == b'%')
+			.then(|| bytes.get(i + 1..i + 3))
+			.flatten()
+			.and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+
+		match escaped {
+			Some(byte) => {
+				decoded.push(byte);
+				i += 3;
+			}
+			None => {
+				decoded.push(bytes[i]);
+				i += 1;
+			}
+		}
+	}
+
+	String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn coords(latitude: f64, longitude: f64) -> Option<Coordinates> {
+		Some(Coordinates {
+			latitude,
+			longitude,
+		})
+	}
+
+	#[test]
+	fn test_yandex_prefers_placemark() {
+		assert_eq!(
+			parse_map_link("https://yandex.ru/maps/?ll=37.620393%2C55.753960&pt=37.6,55.7&z=12"),
+			coords(55.7, 37.6)
+		);
+		assert_eq!(
+			parse_map_link("https://yandex.ru/maps/213/moscow/?ll=37.620393,55.753960&z=12"),
+			coords(55.753960, 37.620393)
+		);
+	}
+
+	#[test]
+	fn test_google_formats() {
+		assert_eq!(
+			parse_map_link("https://www.google.com/maps/@55.7539,37.6208,15z"),
+			coords(55.7539, 37.6208)
+		);
+		assert_eq!(
+			parse_map_link(
+				"https://google.ru/maps/place/Kremlin/@55.75,37.61,17z/data=!3m1!4b1!4m6!3m5!3d55.752!4d37.617"
+			),
+			coords(55.752, 37.617)
+		);
+		assert_eq!(
+			parse_map_link("https://google.com/maps/?q=59.93,30.31"),
+			coords(59.93, 30.31)
+		);
+	}
+
+	#[test]
+	fn test_two_gis_formats() {
+		assert_eq!(
+			parse_map_link("https://2gis.ru/moscow/geo/4504235282713960/37.617,55.755"),
+			coords(55.755, 37.617)
+		);
+		assert_eq!(
+			parse_map_link("https://2gis.ru/spb?m=30.315%2C59.939%2F16"),
+			coords(59.939, 30.315)
+		);
+	}
+
+	#[test]
+	fn test_rejects_garbage() {
+		assert_eq!(parse_map_link("https://yandex.ru/maps/213/moscow/"), None);
+		assert_eq!(parse_map_link("https://google.com/maps/@0,0,3z"), None);
+		assert_eq!(parse_map_link("https://yandex.ru/maps/?pt=200,95"), None);
+		assert_eq!(parse_map_link("https://example.com/?ll=37.6,55.7"), None);
+	}
+}
@@ -0,0 +1,170 @@
+use ::std::sync::Arc;
+use axum::extract::{Path, State};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+	dto::{
+		Dto,
+		admin::{AnnouncementDto, MergeCitiesDto, MergeLocationsDto, UpdateRoleDto},
+		location::LocationCapacityDto,
+	},
+	email::EXTERNAL_HOST,
+	geo,
+	handlers::locations::check_capacity,
+	state::AppState,
+	system_models::{AppError, AppResponse, AppResult},
+};
+
+/// имя события SSE для объявлений
+const ANNOUNCEMENT_EVENT: &str = "announcement";
+
+pub(crate) async fn merge_locations(
+	State(state): State<Arc<AppState>>,
+	Dto(body): Dto<MergeLocationsDto>,
+) -> AppResult {
+	if body.duplicate == body.canonical {
+		return AppError::scenario_error("Нельзя объединить локацию саму с собой", None::<&str>)
+			.into();
+	}
+
+	let Some(stats) = state
+		.repo
+		.merge_locations(body.duplicate, body.canonical)
+		.await?
+	else {
+		return Ok(AppResponse::scenario_fail("Локация не найдена", None));
+	};
+
+	let payload = serde_json::to_value(stats)?;
+
+	Ok(AppResponse::scenario_success(
+		"Локации объединены",
+		Some(payload),
+	))
+}
+
+pub(crate) async fn set_location_capacity(
+	State(state): State<Arc<AppState>>,
+	Path(location_id): Path<Uuid>,
+	Dto(body): Dto<LocationCapacityDto>,
+) -> AppResult {
+	check_capacity(body.tables, &body.opening_hours)?;
+
+	match state.repo.set_location_capacity(location_id, &body).await? {
+		false => Ok(AppResponse::scenario_fail("Локация не найдена", None)),
+		true => Ok(AppResponse::scenario_success(
+			"Вместимость и часы работы локации обновлены",
+			None,
+		)),
+	}
+}
+
+/// Заполняет координаты локаций, созданных до их появления, по ссылкам на карты
+pub(crate) async fn fill_location_coordinates(State(state): State<Arc<AppState>>) -> AppResult {
+	let links = state.repo.read_links_without_coordinates().await?;
+
+	let mut filled = 0;
+	for link in &links {
+		if let Some(coordinates) = geo::parse_map_link(&link.map_link) {
+			state
+				.repo
+				.set_location_coordinates(link.id, coordinates)
+				.await?;
+			filled += 1;
+		}
+	}
+
+	Ok(AppResponse::scenario_success(
+		"Координаты локаций заполнены",
+		Some(json!({ "filled": filled, "skipped": links.len() - filled })),
+	))
+}
+
+pub(crate) async fn merge_cities(
+	State(state): State<Arc<AppState>>,
+	Dto(body): Dto<MergeCitiesDto>,
+) -> AppResult {
+	if body.duplicate == body.canonical {
+		return AppError::scenario_error("Нельзя объединить город сам с собой", None::<&str>).into();
+	}
+
+	let Some(stats) = state
+		.repo
+		.merge_cities(&body.duplicate, &body.canonical)
+		.await?
+	else {
+		return Ok(AppResponse::scenario_fail("Город не найден", None));
+	};
+
+	let payload = serde_json::to_value(stats)?;
+
+	Ok(AppResponse::scenario_success(
+		"Города объединены",
+		Some(payload),
+	))
+}
+
+pub(crate) async fn set_user_role(
+	State(state): State<Arc<AppState>>,
+	Path(user_id): Path<Uuid>,
+	Dto(body): Dto<UpdateRoleDto>,
+) -> AppResult {
+	let Some(user) = state
+		.repo
+		.set_user_role(user_id, body.role.as_str())
+		.await?
+	else {
+		return Ok(AppResponse::scenario_fail("Пользователь не найден", None));
+	};
+
+	let payload = serde_json::to_value(user)?;
+
+	Ok(AppResponse::scenario_success(
+		"Роль пользователя обновлена",
+		Some(payload),
+	))
+}
+
+/// Подключения SSE к этому экземпляру сервера
+pub(crate) async fn read_sse_metrics(State(state): State<Arc<AppState>>) -> AppResult {
+	let payload = serde_json::to_value(state.subscribers.metrics())?;
+
+	Ok(AppResponse::scenario_success("Метрики SSE", Some(payload)))
+}
+
+/// Объявление всем подключенным к SSE, включая гостей. Во входящие не сохраняется
+pub(crate) async fn publish_announcement(
+	State(state): State<Arc<AppState>>,
+	Dto(body): Dto<AnnouncementDto>,
+) -> AppResult {
+	let message = body.message.trim();
+
+	if message.is_empty() {
+		return AppError::scenario_error("Текст объявления не может быть пустым", None::<&str>)
+			.into();
+	}
+
+	let data = serde_json::to_string(&json!({ "message": message }))?;
+
+	state
+		.repo
+		.publish_sse_global(ANNOUNCEMENT_EVENT, &data)
+		.await?;
+
+	Ok(AppResponse::scenario_success("Объявление отправлено", None))
+}
+
+/// Подписывает бота Telegram на вебхук этого сервера
+pub(crate) async fn set_telegram_webhook(State(state): State<Arc<AppState>>) -> AppResult {
+	let url = format!("{}/api/telegram/webhook", *EXTERNAL_HOST);
+
+	state.telegram.set_webhook(&url).await.map_err(|err| {
+		AppError::scenario_error("Не удалось подключить вебхук Telegram", Some(err))
+	})?;
+
+	Ok(AppResponse::scenario_success(
+		"Вебхук Telegram подключен",
+		Some(json!({ "url": url })),
+	))
+}
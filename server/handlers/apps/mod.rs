@@ -7,6 +7,8 @@ use chrono::Utc;
 use uuid::Uuid;
 
 use crate::{
+	dto::{QueryDto, page::PageDto},
+	notification::NotificationPayload,
 	state::AppState,
 	system_models::{AppResponse, AppResult},
 };
@@ -14,8 +16,9 @@ use crate::{
 pub(crate) async fn read_player_apps_list(
 	State(state): State<Arc<AppState>>,
 	Extension(user_id): Extension<Uuid>,
+	QueryDto(page): QueryDto<PageDto>,
 ) -> AppResult {
-	let apps = state.repo.read_player_apps_list(user_id).await?;
+	let apps = state.repo.read_player_apps_list(user_id, &page).await?;
 
 	let json_value = serde_json::to_value(apps)?;
 
@@ -84,8 +87,9 @@ pub(crate) async fn read_player_app_company_closest(
 pub(crate) async fn read_master_apps_list(
 	State(state): State<Arc<AppState>>,
 	Extension(user_id): Extension<Uuid>,
+	QueryDto(page): QueryDto<PageDto>,
 ) -> AppResult {
-	let apps = state.repo.read_master_apps_list(user_id).await?;
+	let apps = state.repo.read_master_apps_list(user_id, &page).await?;
 
 	let json_value = serde_json::to_value(apps)?;
 
@@ -189,6 +193,18 @@ pub(crate) async fn approve_app(
 
 	state.repo.approve_app(app_id).await?;
 
+	state
+		.notify(
+			&[app.player_id],
+			NotificationPayload::ApplicationApproved {
+				app_id,
+				event_id: app.event_id,
+				company_name: app.company_name,
+				date: app.event_date,
+			},
+		)
+		.await;
+
 	Ok(AppResponse::scenario_success(
 		"Заявка на событие успешно одобрена",
 		None,
@@ -236,6 +252,18 @@ pub(crate) async fn reject_app(
 
 	state.repo.reject_app(app_id).await?;
 
+	state
+		.notify(
+			&[app.player_id],
+			NotificationPayload::ApplicationRejected {
+				app_id,
+				event_id: app.event_id,
+				company_name: app.company_name,
+				date: app.event_date,
+			},
+		)
+		.await;
+
 	Ok(AppResponse::scenario_success(
 		"Заявка на событие успешно отклонена",
 		None,
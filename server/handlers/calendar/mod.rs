@@ -0,0 +1,449 @@
+use ::std::sync::Arc;
+use axum::{
+	Extension,
+	extract::{Path, State},
+	http::{HeaderMap, StatusCode, header},
+	response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use rand::Rng as _;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+	dto::{
+		Dto, QueryDto,
+		company::CompanyPermission,
+		event::{ConflictQuery, ImportCalendarDto, ImportedGameDto, ReadEventsDto},
+	},
+	handlers::events::{
+		EventStart, check_company, check_location, check_venue, checked_dates, convert_dates,
+		flat_conflicts, flat_location_issues, schedule_rejected,
+	},
+	ical::{self, CachedFeed, IcsTime, ImportedEvent, InvalidEvent},
+	recurrence,
+	repository::{
+		Repository,
+		models::{Checked, ScheduleCheck},
+	},
+	state::AppState,
+	system_models::{AppError, AppResponse, AppResult},
+};
+
+/// Выпускает новый токен календаря, старая ссылка перестаёт работать
+pub(crate) async fn issue_calendar_token(
+	State(state): State<Arc<AppState>>,
+	Extension(user_id): Extension<Uuid>,
+) -> AppResult {
+	let token = hex::encode(rand::rng().random::<[u8; 32]>());
+	state.repo.set_calendar_token(user_id, Some(&token)).await?;
+
+	let payload = json!({
+		"url": format!("/calendar/{token}.ics"),
+		"token": token,
+	});
+
+	Ok(AppResponse::scenario_success(
+		"Ссылка на календарь создана",
+		Some(payload),
+	))
+}
+
+pub(crate) async fn revoke_calendar_token(
+	State(state): State<Arc<AppState>>,
+	Extension(user_id): Extension<Uuid>,
+) -> AppResult {
+	state.repo.set_calendar_token(user_id, None).await?;
+
+	Ok(AppResponse::scenario_success(
+		"Ссылка на календарь отозвана",
+		None,
+	))
+}
+
+pub(crate) async fn read_personal_feed(
+	State(state): State<Arc<AppState>>,
+	Path(file): Path<String>,
+) -> Response {
+	let Some(token) = file.strip_suffix(".ics") else {
+		return StatusCode::NOT_FOUND.into_response();
+	};
+
+	match state.repo.read_calendar_events(token).await {
+		Ok(Some(events)) => (
+			[(header::CONTENT_TYPE, ical::CONTENT_TYPE)],
+			ical::render("НРИ: мои игры", &events, Utc::now()),
+		)
+			.into_response(),
+		Ok(None) => StatusCode::NOT_FOUND.into_response(),
+		Err(err) => err.into_response(),
+	}
+}
+
+// ограничение на количество игр, создаваемых одним импортом
+const IMPORT_LIMIT: usize = 500;
+const FEED_PAST_DAYS: i64 = 30;
+const FEED_FUTURE_DAYS: i64 = 365;
+const HTTP_DATE_TEMPLATE: &str = "%a, %d %b %Y %H:%M:%S GMT";
+
+#[derive(Clone, Copy)]
+enum FeedScope {
+	Company,
+	Location,
+	Master,
+}
+
+impl FeedScope {
+	const fn as_str(self) -> &'static str {
+		match self {
+			Self::Company => "company",
+			Self::Location => "location",
+			Self::Master => "master",
+		}
+	}
+}
+
+pub(crate) async fn read_company_feed(
+	State(state): State<Arc<AppState>>,
+	Path(file): Path<String>,
+	headers: HeaderMap,
+) -> Response {
+	serve_public_feed(&state, FeedScope::Company, &file, &headers).await
+}
+
+pub(crate) async fn read_location_feed(
+	State(state): State<Arc<AppState>>,
+	Path(file): Path<String>,
+	headers: HeaderMap,
+) -> Response {
+	serve_public_feed(&state, FeedScope::Location, &file, &headers).await
+}
+
+pub(crate) async fn read_master_feed(
+	State(state): State<Arc<AppState>>,
+	Path(file): Path<String>,
+	headers: HeaderMap,
+) -> Response {
+	serve_public_feed(&state, FeedScope::Master, &file, &headers).await
+}
+
+async fn serve_public_feed(
+	state: &AppState,
+	scope: FeedScope,
+	file: &str,
+	headers: &HeaderMap,
+) -> Response {
+	let Some(id) = file
+		.strip_suffix(".ics")
+		.and_then(|id| Uuid::parse_str(id).ok())
+	else {
+		return StatusCode::NOT_FOUND.into_response();
+	};
+
+	let key = format!("{}:{id}", scope.as_str());
+	let feed = match state.calendar_cache.get(&key) {
+		Some(feed) => feed,
+		None => {
+			let now = Utc::now();
+			// в публичную ленту попадают только публичные события, как для гостя
+			let query = ReadEventsDto {
+				date_from: (now - Duration::days(FEED_PAST_DAYS)).fixed_offset(),
+				date_to: (now + Duration::days(FEED_FUTURE_DAYS)).fixed_offset(),
+				master: matches!(scope, FeedScope::Master).then_some(id),
+				location: matches!(scope, FeedScope::Location).then_some(id),
+				region: None,
+				city: None,
+				applied: None,
+				not_rejected: None,
+				imamaster: None,
+				company: match scope {
+					FeedScope::Company => vec![id],
+					_ => Vec::new(),
+				},
+			};
+
+			let events = match state.repo.read_public_calendar(query).await {
+				Ok(events) => events,
+				Err(err) => return err.into_response(),
+			};
+
+			let name = events
+				.first()
+				.and_then(|event| match scope {
+					FeedScope::Company => Some(event.company.as_str()),
+					FeedScope::Location => event.location.as_deref(),
+					FeedScope::Master => Some(event.master.as_str()),
+				})
+				.map_or_else(|| String::from("НРИ"), |name| format!("НРИ: {name}"));
+
+			state.calendar_cache.put(&key, &name, &events)
+		}
+	};
+
+	feed_response(&feed, headers)
+}
+
+fn feed_response(feed: &CachedFeed, headers: &HeaderMap) -> Response {
+	let last_modified = feed.last_modified.format(HTTP_DATE_TEMPLATE).to_string();
+	let cache_control = format!("public, max-age={}", ical::FEED_TTL.as_secs());
+
+	let not_modified = match headers.get(header::IF_NONE_MATCH) {
+		Some(if_none_match) => if_none_match
+			.to_str()
+			.is_ok_and(|tags| tags.split(',').any(|tag| tag.trim() == feed.etag)),
+		None => headers
+			.get(header::IF_MODIFIED_SINCE)
+			.and_then(|since| since.to_str().ok())
+			.and_then(|since| DateTime::parse_from_rfc2822(since).ok())
+			.is_some_and(|since| feed.last_modified.timestamp() <= since.timestamp()),
+	};
+
+	let cache_headers = [
+		(header::ETAG, feed.etag.clone()),
+		(header::LAST_MODIFIED, last_modified),
+		(header::CACHE_CONTROL, cache_control),
+	];
+
+	if not_modified {
+		return (StatusCode::NOT_MODIFIED, cache_headers).into_response();
+	}
+
+	(
+		cache_headers,
+		[(header::CONTENT_TYPE, ical::CONTENT_TYPE)],
+		feed.body.clone(),
+	)
+		.into_response()
+}
+
+pub(crate) async fn import_calendar(
+	State(state): State<Arc<AppState>>,
+	Extension(user_id): Extension<Uuid>,
+	Path(company_id): Path<Uuid>,
+	QueryDto(conflict): QueryDto<ConflictQuery>,
+	Dto(body): Dto<ImportCalendarDto>,
+) -> AppResult {
+	check_venue(&body.venue, body.location)?;
+	check_company(company_id, user_id, CompanyPermission::Events, &state.repo).await?;
+	check_location(body.location, &state.repo).await?;
+
+	let imported = ical::parse(&body.calendar)
+		.map_err(|err| AppError::scenario_error("Не удалось разобрать файл календаря", Some(err)))?;
+
+	// пояс по умолчанию нужен только событиям без пояса, он запрашивается один раз
+	let default_timezone = match imported.iter().any(|event| {
+		matches!(
+			event,
+			Ok(ImportedEvent {
+				start: IcsTime::Floating(_),
+				..
+			})
+		)
+	}) {
+		true => state.repo.default_timezone(body.location, user_id).await?,
+		false => None,
+	};
+
+	let mut games = Vec::with_capacity(imported.len());
+	let mut starts = Vec::with_capacity(imported.len());
+
+	for event in imported {
+		let (game, start) = preview_game(event, &body, default_timezone.as_deref());
+		games.push(game);
+		starts.push(start);
+	}
+
+	convert_games(&mut games, starts, Utc::now(), &state.repo).await?;
+
+	let total = games.iter().map(|game| game.dates.len()).sum::<usize>();
+
+	if total > IMPORT_LIMIT {
+		return Ok(AppResponse::scenario_fail(
+			"Слишком много игр для одного импорта",
+			Some(json!({ "total": total, "limit": IMPORT_LIMIT })),
+		));
+	}
+
+	if !body.confirm {
+		let payload = json!({ "games": games, "total": total });
+		return Ok(AppResponse::scenario_success(
+			"Предпросмотр импорта",
+			Some(payload),
+		));
+	}
+
+	if total == 0 {
+		return Ok(AppResponse::scenario_fail(
+			"В календаре нет игр для импорта",
+			None,
+		));
+	}
+
+	let check = ScheduleCheck {
+		user_id,
+		strict: conflict.strict,
+	};
+
+	let (ids, checks) = match state
+		.repo
+		.import_events(company_id, &body, &games, check)
+		.await?
+	{
+		Checked::Saved(ids, checks) => (ids, checks),
+		Checked::Rejected(checks) => return schedule_rejected(&checks),
+	};
+
+	let payload = json!({
+		"events": ids,
+		"conflicts": flat_conflicts(&checks),
+		"location_issues": flat_location_issues(&checks),
+	});
+
+	Ok(AppResponse::scenario_success(
+		"Игры импортированы",
+		Some(payload),
+	))
+}
+
+/// Начало события и местные даты его игр
+type PlannedGames = (EventStart, Vec<NaiveDateTime>);
+
+fn preview_game(
+	event: Result<ImportedEvent, InvalidEvent>,
+	body: &ImportCalendarDto,
+	default_timezone: Option<&str>,
+) -> (ImportedGameDto, Option<PlannedGames>) {
+	let event = match event {
+		Ok(event) => event,
+		Err(invalid) => {
+			let game = ImportedGameDto {
+				uid: invalid.uid,
+				summary: invalid.summary,
+				location: None,
+				dates: Vec::new(),
+				plan_duration: None,
+				rule: None,
+				exceptions: Vec::new(),
+				timezone: None,
+				truncated: false,
+				skipped: Some(invalid.reason),
+			};
+			return (game, None);
+		}
+	};
+
+	let plan_duration = event
+		.duration
+		.filter(|duration| *duration > Duration::zero())
+		.map(|duration| i16::try_from((duration.num_minutes() + 59) / 60).unwrap_or(i16::MAX))
+		.or(body.plan_duration);
+
+	let mut game = ImportedGameDto {
+		uid: event.uid,
+		summary: event.summary,
+		location: event.location,
+		dates: Vec::new(),
+		plan_duration,
+		rule: None,
+		exceptions: event.exceptions,
+		timezone: None,
+		truncated: false,
+		skipped: None,
+	};
+
+	if event.cancelled {
+		game.skipped = Some(String::from("Событие отменено"));
+		return (game, None);
+	}
+
+	let start = match event.start {
+		IcsTime::Utc(date) => EventStart::Fixed(date.fixed_offset()),
+		IcsTime::Zoned(local, timezone) => EventStart::Zoned(local, timezone),
+		// для времени без пояса берём пояс локации или ведущего, как в add_event
+		IcsTime::Floating(local) => match default_timezone {
+			Some(timezone) => EventStart::Zoned(local, timezone.to_owned()),
+			None => {
+				game.skipped = Some(String::from("Не удалось определить часовой пояс игры"));
+				return (game, None);
+			}
+		},
+		IcsTime::AllDay(_) => {
+			game.skipped = Some(String::from("События на весь день не импортируются"));
+			return (game, None);
+		}
+	};
+
+	game.timezone = start.timezone().map(str::to_owned);
+
+	let locals = match event.rule {
+		None => vec![start.local()],
+		Some(Ok(rule)) => {
+			let locals = recurrence::expand(start.local(), &rule, &game.exceptions);
+			game.truncated = recurrence::is_truncated(start.local().date(), &rule);
+			game.rule = Some(rule);
+			locals
+		}
+		Some(Err(reason)) => {
+			game.skipped = Some(reason);
+			return (game, None);
+		}
+	};
+
+	(game, Some((start, locals)))
+}
+
+/// Переводит даты всех событий календаря одним запросом. Событие с неизвестным
+/// поясом или временем на переводе часов пропускается, остальные импортируются
+async fn convert_games(
+	games: &mut [ImportedGameDto],
+	starts: Vec<Option<PlannedGames>>,
+	now: DateTime<Utc>,
+	repo: &Repository,
+) -> Result<(), AppError> {
+	let locals = starts
+		.iter()
+		.flatten()
+		.flat_map(|(start, locals)| {
+			let timezone = start.timezone();
+			locals
+				.iter()
+				.filter_map(move |local| timezone.map(|timezone| (*local, timezone)))
+		})
+		.collect::<Vec<_>>();
+
+	let mut zoned = match locals.is_empty() {
+		true => Vec::new(),
+		false => repo.local_to_utc(&locals).await?,
+	}
+	.into_iter();
+
+	for (game, start) in games.iter_mut().zip(starts) {
+		let Some((start, locals)) = start else {
+			continue;
+		};
+
+		let dates = match start {
+			EventStart::Fixed(_) => convert_dates(&start, &locals, repo).await,
+			EventStart::Zoned(..) => checked_dates(zoned.by_ref().take(locals.len()).collect()),
+		};
+
+		game.dates = match dates {
+			Ok(dates) => dates,
+			Err(AppError::ScenarioError(reason, context)) => {
+				game.skipped = Some(match context {
+					Some(context) => format!("{reason}: {context}"),
+					None => reason,
+				});
+				continue;
+			}
+			Err(err) => return Err(err),
+		};
+		game.dates.retain(|date| *date > now);
+
+		if game.dates.is_empty() {
+			game.skipped = Some(String::from("Все игры события уже прошли"));
+		}
+	}
+
+	Ok(())
+}
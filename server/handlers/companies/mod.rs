@@ -4,14 +4,25 @@ use axum::{
 	extract::{Path, State},
 	response::Response,
 };
+use serde_json::json;
 use uuid::Uuid;
 
 use crate::{
 	dto::{
-		Dto, FileLinkDto,
-		company::{ApiCompanyDto, ApiUpdateCompanyDto, ReadCompaniesDto},
+		Dto, FileLinkDto, QueryDto,
+		company::{
+			ApiCompanyDto, ApiUpdateCompanyDto, NewOneShotDto, ReadCompaniesDto, TransferCompanyDto,
+		},
+		event::ConflictQuery,
+		page::PageDto,
+	},
+	handlers::events::{
+		check_location, check_venue, flat_conflicts, flat_location_issues, resolve_date,
+		schedule_rejected,
 	},
 	image,
+	notification::NotificationPayload,
+	repository::models::{Checked, ScheduleCheck},
 	state::AppState,
 	system_models::{AppError, AppResponse, AppResult},
 };
@@ -42,9 +53,10 @@ pub(crate) async fn read_company_cover(
 pub(crate) async fn get_my_companies(
 	State(state): State<Arc<AppState>>,
 	Extension(master_id): Extension<Uuid>,
+	QueryDto(page): QueryDto<PageDto>,
 	Dto(query): Dto<ReadCompaniesDto>,
 ) -> AppResult {
-	let my = state.repo.get_my_companies(query, master_id).await?;
+	let my = state.repo.get_my_companies(query, master_id, &page).await?;
 
 	let json_value = serde_json::to_value(my)?;
 
@@ -63,17 +75,7 @@ pub(crate) async fn add_company(
 		image::check_remote_file(cover_link).await?;
 	}
 
-	let new_comp_id = state
-		.repo
-		.add_company(
-			master_id,
-			&body.name,
-			&body.system,
-			&body.description,
-			&body.cover_link,
-			&body.event_style,
-		)
-		.await?;
+	let new_comp_id = state.repo.add_company(master_id, &body).await?;
 
 	return Ok(AppResponse::scenario_success(
 		"Кампания успешно создана",
@@ -81,6 +83,48 @@ pub(crate) async fn add_company(
 	));
 }
 
+pub(crate) async fn add_one_shot(
+	State(state): State<Arc<AppState>>,
+	Extension(master_id): Extension<Uuid>,
+	QueryDto(conflict): QueryDto<ConflictQuery>,
+	Dto(body): Dto<NewOneShotDto>,
+) -> AppResult {
+	if let Some(ref cover_link) = body.cover_link {
+		image::check_remote_file(cover_link).await?;
+	}
+
+	check_venue(&body.venue, body.location)?;
+	check_location(body.location, &state.repo).await?;
+
+	let date = resolve_date(&body.time, body.location, master_id, &state.repo).await?;
+
+	let check = ScheduleCheck {
+		user_id: master_id,
+		strict: conflict.strict,
+	};
+
+	let (ids, checks) = match state
+		.repo
+		.add_one_shot(master_id, &body, date, check)
+		.await?
+	{
+		Checked::Saved(ids, checks) => (ids, checks),
+		Checked::Rejected(checks) => return schedule_rejected(&checks),
+	};
+
+	let payload = json!({
+		"company_id": ids.company_id,
+		"event_id": ids.event_id,
+		"conflicts": flat_conflicts(&checks),
+		"location_issues": flat_location_issues(&checks),
+	});
+
+	return Ok(AppResponse::scenario_success(
+		"Ваншот успешно создан",
+		Some(payload),
+	));
+}
+
 pub(crate) async fn update_company(
 	State(state): State<Arc<AppState>>,
 	Extension(master_id): Extension<Uuid>,
@@ -122,3 +166,104 @@ pub(crate) async fn set_cover(
 		)),
 	}
 }
+
+pub(crate) async fn propose_transfer(
+	State(state): State<Arc<AppState>>,
+	Extension(master_id): Extension<Uuid>,
+	Path(company_id): Path<Uuid>,
+	Dto(body): Dto<TransferCompanyDto>,
+) -> AppResult {
+	if body.user == master_id {
+		return AppError::scenario_error("Вы уже являетесь мастером кампании", None::<&str>).into();
+	}
+
+	let Some(company) = state
+		.repo
+		.get_company_by_id(company_id, Some(master_id))
+		.await?
+	else {
+		return AppError::scenario_error("Кампания не найдена", Some(company_id.to_string())).into();
+	};
+
+	if !state
+		.repo
+		.propose_transfer(company_id, master_id, body.user, body.stay_as)
+		.await?
+	{
+		return AppError::scenario_error("Передать кампанию может только её мастер", None::<&str>)
+			.into();
+	}
+
+	state
+		.notify(
+			&[body.user],
+			NotificationPayload::TransferProposed {
+				company_id,
+				company_name: company.name,
+				master_name: company.master_name,
+			},
+		)
+		.await;
+
+	Ok(AppResponse::scenario_success(
+		"Предложение о передаче кампании отправлено",
+		None,
+	))
+}
+
+pub(crate) async fn accept_transfer(
+	State(state): State<Arc<AppState>>,
+	Extension(user_id): Extension<Uuid>,
+	Path(company_id): Path<Uuid>,
+) -> AppResult {
+	let Some(transfer) = state.repo.accept_transfer(company_id, user_id).await? else {
+		return AppError::scenario_error("Предложение о передаче не найдено", None::<&str>).into();
+	};
+
+	state
+		.notify(
+			&[transfer.master, transfer.new_master],
+			NotificationPayload::TransferAccepted {
+				company_id,
+				company_name: transfer.company_name,
+			},
+		)
+		.await;
+
+	Ok(AppResponse::scenario_success(
+		"Вы стали мастером кампании",
+		None,
+	))
+}
+
+pub(crate) async fn cancel_transfer(
+	State(state): State<Arc<AppState>>,
+	Extension(user_id): Extension<Uuid>,
+	Path(company_id): Path<Uuid>,
+) -> AppResult {
+	let Some(transfer) = state.repo.cancel_transfer(company_id, user_id).await? else {
+		return AppError::scenario_error("Предложение о передаче не найдено", None::<&str>).into();
+	};
+
+	// уведомляем вторую сторону
+	let other = if transfer.master == user_id {
+		transfer.new_master
+	} else {
+		transfer.master
+	};
+
+	state
+		.notify(
+			&[other],
+			NotificationPayload::TransferCancelled {
+				company_id,
+				company_name: transfer.company_name,
+			},
+		)
+		.await;
+
+	Ok(AppResponse::scenario_success(
+		"Передача кампании отменена",
+		None,
+	))
+}
@@ -3,27 +3,48 @@ use axum::{
 	Extension,
 	extract::{Path, State},
 };
+use chrono::{DateTime, Duration, FixedOffset, NaiveDateTime, TimeZone as _, Utc};
 use futures::try_join;
+use reqwest::Url;
+use serde::Serialize;
+use serde_json::json;
 use uuid::Uuid;
 
 use crate::{
 	dto::{
-		Dto,
-		event::{NewEventDto, ReadEventsDto, UpdateEventDto},
+		Dto, QueryDto,
+		company::CompanyPermission,
+		event::{
+			ConflictQuery, EditScope, EventInviteDto, EventTimeDto, InviteQuery, NewEventDto,
+			ReadEventsDto, UpdateEventDto, VenueDto, VenueType,
+		},
+		location::NearDto,
+		page::PageDto,
+	},
+	geo::Coordinates,
+	invite,
+	notification::NotificationPayload,
+	repository::{
+		Repository,
+		models::{
+			Checked, CompanyInfo, EventCheck, LocationIssue, ScheduleCheck, ScheduleConflict,
+			ZonedDate,
+		},
 	},
-	repository::Repository,
 	state::AppState,
 	system_models::{AppError, AppResponse, AppResult},
 };
 
-const FULL_UTC_TEMPLATE: &str = "%Y-%m-%dT%H:%M:%SZ";
+const NEAR_EVENTS_DAYS: i64 = 30;
+const LOCAL_TIME_FORMAT: &str = "%d.%m.%Y %H:%M";
 
 pub(crate) async fn read_events_list(
 	State(state): State<Arc<AppState>>,
 	Extension(user_id): Extension<Option<Uuid>>,
+	QueryDto(page): QueryDto<PageDto>,
 	Dto(query): Dto<ReadEventsDto>,
 ) -> AppResult {
-	let events = state.repo.read_events_list(query, user_id).await?;
+	let events = state.repo.read_events_list(query, user_id, &page).await?;
 
 	let json_value = serde_json::to_value(events)?;
 
@@ -33,12 +54,47 @@ pub(crate) async fn read_events_list(
 	));
 }
 
+pub(crate) async fn read_nearby_events(
+	State(state): State<Arc<AppState>>,
+	Dto(query): Dto<NearDto>,
+) -> AppResult {
+	if Coordinates::new(query.lat, query.lon).is_none() {
+		return Ok(AppResponse::scenario_fail("Некорректные координаты", None));
+	}
+
+	let from = query.date_from.map_or_else(Utc::now, |date| date.to_utc());
+	let to = query.date_to.map_or_else(
+		|| from + Duration::days(NEAR_EVENTS_DAYS),
+		|date| date.to_utc(),
+	);
+
+	let events = state.repo.read_nearby_events(&query, from, to).await?;
+
+	let json_value = serde_json::to_value(events)?;
+
+	return Ok(AppResponse::scenario_success(
+		"Игры рядом",
+		Some(json_value),
+	));
+}
+
 pub(crate) async fn read_event(
 	State(state): State<Arc<AppState>>,
 	Extension(user_id): Extension<Option<Uuid>>,
 	Path(event_id): Path<Uuid>,
+	Dto(query): Dto<InviteQuery>,
 ) -> AppResult {
-	let event = state.repo.read_event(event_id, user_id).await?;
+	let event = state
+		.repo
+		.read_event(event_id, user_id)
+		.await?
+		.filter(|ev| {
+			ev.visibility != "private"
+				|| ev.you_are_master
+				|| ev.you_are_member
+				|| ev.you_applied
+				|| has_invite(query.invite.as_deref(), event_id, user_id)
+		});
 
 	let res = match event {
 		None => {
@@ -57,27 +113,41 @@ pub(crate) async fn read_event(
 pub(crate) async fn add_event(
 	State(state): State<Arc<AppState>>,
 	Extension(user_id): Extension<Uuid>,
+	QueryDto(conflict): QueryDto<ConflictQuery>,
 	Dto(body): Dto<NewEventDto>,
 ) -> AppResult {
-	try_join!(
-		check_company(body.company, user_id, &state.repo),
+	check_venue(&body.venue, body.location)?;
+
+	let (date, ..) = try_join!(
+		resolve_date(&body.time, body.location, user_id, &state.repo),
+		check_company(
+			body.company,
+			user_id,
+			CompanyPermission::Events,
+			&state.repo
+		),
 		check_location(body.location, &state.repo),
 	)?;
 
-	let new_evt_id = state
-		.repo
-		.add_event(
-			body.company,
-			&body.location,
-			body.date,
-			body.max_slots,
-			body.plan_duration,
-		)
-		.await?;
+	let check = ScheduleCheck {
+		user_id,
+		strict: conflict.strict,
+	};
+
+	let (new_evt_id, checks) = match state.repo.add_event(&body, date, check).await? {
+		Checked::Saved(id, checks) => (id, checks),
+		Checked::Rejected(checks) => return schedule_rejected(&checks),
+	};
+
+	let payload = json!({
+		"id": new_evt_id.uuid(),
+		"conflicts": flat_conflicts(&checks),
+		"location_issues": flat_location_issues(&checks),
+	});
 
 	return Ok(AppResponse::scenario_success(
 		"Событие успешно создано",
-		new_evt_id.into_api(),
+		Some(payload),
 	));
 }
 
@@ -85,8 +155,20 @@ pub(crate) async fn apply_event(
 	State(state): State<Arc<AppState>>,
 	Extension(user_id): Extension<Uuid>,
 	Path(event_id): Path<Uuid>,
+	QueryDto(query): QueryDto<InviteQuery>,
+	QueryDto(conflict): QueryDto<ConflictQuery>,
 ) -> AppResult {
-	let event = state.repo.get_event_for_applying(event_id, user_id).await?;
+	let event = state
+		.repo
+		.get_event_for_applying(event_id, user_id)
+		.await?
+		.filter(|ev| {
+			ev.visibility != "private"
+				|| ev.you_are_master
+				|| ev.you_are_member
+				|| ev.already_applied
+				|| has_invite(query.invite.as_deref(), event_id, Some(user_id))
+		});
 
 	let Some(event) = event else {
 		let payload = serde_json::to_value(event_id)?;
@@ -120,26 +202,79 @@ pub(crate) async fn apply_event(
 		));
 	}
 
+	let conflicts = state.repo.read_conflicts(user_id, event_id).await?;
+	if conflict.strict && !conflicts.is_empty() {
+		return conflicts_fail(&conflicts);
+	}
+
 	let new_app_id = state
 		.repo
 		.apply_event(event_id, user_id, event.can_auto_approve)
 		.await?;
 
 	state
-		.message_sender
-		.send((
-			Some(event.master_id),
-			format!(
-				r#"На игру по кампании "{}" на {} записался игрок"#,
-				event.company_name,
-				event.event_date.format(FULL_UTC_TEMPLATE)
-			),
-		))
-		.ok();
+		.notify(
+			&[event.master_id],
+			NotificationPayload::ApplicationCreated {
+				app_id: new_app_id.uuid(),
+				event_id,
+				company_name: event.company_name,
+				date: event.event_date,
+				player_id: user_id,
+			},
+		)
+		.await;
 
 	Ok(AppResponse::scenario_success(
 		"Заявка на событие успешно создана",
-		new_app_id.into_api(),
+		Some(json!({ "id": new_app_id.uuid(), "conflicts": conflicts })),
+	))
+}
+
+pub(crate) async fn create_invite(
+	State(state): State<Arc<AppState>>,
+	Extension(master_id): Extension<Uuid>,
+	Path(event_id): Path<Uuid>,
+	Dto(body): Dto<EventInviteDto>,
+) -> AppResult {
+	let Some(event) = state.repo.read_event(event_id, Some(master_id)).await? else {
+		let payload = serde_json::to_value(event_id)?;
+		return Ok(AppResponse::scenario_fail(
+			"Событие не найдено",
+			Some(payload),
+		));
+	};
+
+	if !event.you_are_master {
+		let payload = serde_json::to_value(event_id)?;
+		return Ok(AppResponse::scenario_fail(
+			"Вы не являетесь мастером на данном событии",
+			Some(payload),
+		));
+	}
+
+	let invite_link = invite::create(
+		event_id,
+		body.user,
+		body.ttl_hours.unwrap_or(invite::DEFAULT_TTL_HOURS),
+	);
+
+	state
+		.notify(
+			&[body.user],
+			NotificationPayload::EventInvite {
+				event_id,
+				company_name: event.company,
+				link: invite_link.link.clone(),
+			},
+		)
+		.await;
+
+	let payload = serde_json::to_value(invite_link)?;
+
+	Ok(AppResponse::scenario_success(
+		"Приглашение создано",
+		Some(payload),
 	))
 }
 
@@ -147,12 +282,76 @@ pub(crate) async fn update_event(
 	State(state): State<Arc<AppState>>,
 	Extension(master_id): Extension<Uuid>,
 	Path(event_id): Path<Uuid>,
+	QueryDto(conflict): QueryDto<ConflictQuery>,
 	Dto(body): Dto<UpdateEventDto>,
 ) -> AppResult {
-	match state.repo.update_event(event_id, master_id, body).await? {
-		false => Err(AppError::scenario_error("Игра не найдена", None::<&str>)),
-		true => Ok(AppResponse::scenario_success("Данные игры обновлены", None)),
+	if !validate_join_link(body.join_link.as_ref().and_then(Option::as_deref)) {
+		return AppError::scenario_error("Некорректная ссылка на подключение", None::<&str>).into();
+	}
+
+	let Some(previous) = state.repo.read_event(event_id, Some(master_id)).await? else {
+		return Err(AppError::scenario_error("Игра не найдена", None::<&str>));
+	};
+
+	// расписание и загрузку локации видит только тот, кто может менять игру
+	if !previous.you_are_master {
+		return Err(AppError::scenario_error(
+			"Вы не являетесь мастером на данном событии",
+			None::<&str>,
+		));
 	}
+
+	if body.scope == EditScope::Following && previous.series.is_none() {
+		return Err(AppError::scenario_error(
+			"Игра не входит в серию",
+			None::<&str>,
+		));
+	}
+
+	let date = resolve_date(&body.time, body.location, master_id, &state.repo).await?;
+
+	let check = ScheduleCheck {
+		user_id: master_id,
+		strict: conflict.strict,
+	};
+
+	// пересечения и загрузка локации проверяются у каждой перенесённой игры серии
+	let (moved, checks) = match state
+		.repo
+		.update_event(event_id, master_id, body, date, check)
+		.await?
+	{
+		None => return Err(AppError::scenario_error("Игра не найдена", None::<&str>)),
+		Some(Checked::Rejected(checks)) => return schedule_rejected(&checks),
+		Some(Checked::Saved(moved, checks)) => (moved, checks),
+	};
+
+	// при переносе части серии уведомляются игроки каждой перенесённой игры
+	for event in moved {
+		if event.cancelled || event.previous_date == event.date {
+			continue;
+		}
+
+		state
+			.notify(
+				&event.players,
+				NotificationPayload::EventRescheduled {
+					event_id: event.event_id,
+					company_name: previous.company.clone(),
+					previous_date: event.previous_date,
+					date: event.date,
+				},
+			)
+			.await;
+	}
+
+	Ok(AppResponse::scenario_success(
+		"Данные игры обновлены",
+		Some(json!({
+			"conflicts": flat_conflicts(&checks),
+			"location_issues": flat_location_issues(&checks),
+		})),
+	))
 }
 
 pub(crate) async fn cancel_event(
@@ -180,6 +379,20 @@ pub(crate) async fn cancel_event(
 
 	state.repo.cancel_event(event_id).await?;
 
+	if !event.cancelled {
+		let players = state.repo.read_event_players(event_id).await?;
+		state
+			.notify(
+				&players,
+				NotificationPayload::EventCancelled {
+					event_id,
+					company_name: event.company,
+					date: event.date,
+				},
+			)
+			.await;
+	}
+
 	Ok(AppResponse::scenario_success("Событие отменено", None))
 }
 
@@ -211,20 +424,240 @@ pub(crate) async fn reopen_event(
 	Ok(AppResponse::scenario_success("Событие отменено", None))
 }
 
-async fn check_company(company_id: Uuid, user_id: Uuid, repo: &Repository) -> Result<(), AppError> {
+fn conflicts_fail(conflicts: &[impl Serialize]) -> AppResult {
+	let payload = serde_json::to_value(conflicts)?;
+
+	Ok(AppResponse::scenario_fail(
+		"Время игры пересекается с другими вашими играми",
+		Some(payload),
+	))
+}
+
+/// Пересекающиеся игры по всем записанным играм, каждая один раз
+pub(super) fn flat_conflicts(checks: &[EventCheck]) -> Vec<&ScheduleConflict> {
+	let mut conflicts: Vec<&ScheduleConflict> = Vec::new();
+
+	for conflict in checks.iter().flat_map(|check| &check.conflicts) {
+		if !conflicts
+			.iter()
+			.any(|known| known.event_id == conflict.event_id)
+		{
+			conflicts.push(conflict);
+		}
+	}
+
+	conflicts
+}
+
+/// Что мешает записанным играм на их локациях
+pub(super) fn flat_location_issues(checks: &[EventCheck]) -> Vec<&LocationIssue> {
+	checks
+		.iter()
+		.flat_map(|check| &check.location_issues)
+		.collect()
+}
+
+/// Ответ на запись, отклонённую в строгом режиме
+pub(super) fn schedule_rejected(checks: &[EventCheck]) -> AppResult {
+	let conflicts = flat_conflicts(checks);
+	if !conflicts.is_empty() {
+		return conflicts_fail(&conflicts);
+	}
+
+	location_issues_fail(&flat_location_issues(checks))
+}
+
+fn location_issues_fail(issues: &[&LocationIssue]) -> AppResult {
+	let payload = serde_json::to_value(issues)?;
+
+	Ok(AppResponse::scenario_fail(
+		"Локация не может принять игру в это время",
+		Some(payload),
+	))
+}
+
+pub(super) async fn check_company(
+	company_id: Uuid,
+	user_id: Uuid,
+	permission: CompanyPermission,
+	repo: &Repository,
+) -> Result<CompanyInfo, AppError> {
 	let Some(company) = repo.get_company_by_id(company_id, Some(user_id)).await? else {
 		return AppError::scenario_error("Кампания не найдена", Some(company_id.to_string())).into();
 	};
 
-	if company.master != user_id {
+	if !repo
+		.has_company_permission(company_id, user_id, permission)
+		.await?
+	{
 		return AppError::scenario_error("Вы не можете управлять данной кампанией", None::<&str>)
 			.into();
 	}
 
-	Ok(())
+	Ok(company)
+}
+
+fn has_invite(token: Option<&str>, event_id: Uuid, user_id: Option<Uuid>) -> bool {
+	match (token, user_id) {
+		(Some(token), Some(user_id)) => invite::verify(token, event_id, user_id),
+		_ => false,
+	}
+}
+
+/// Время начала игры: абсолютное от клиента либо местное в конкретном поясе
+pub(super) enum EventStart {
+	Fixed(DateTime<FixedOffset>),
+	Zoned(NaiveDateTime, String),
+}
+
+impl EventStart {
+	pub(super) fn local(&self) -> NaiveDateTime {
+		match self {
+			EventStart::Fixed(date) => date.naive_local(),
+			EventStart::Zoned(local, _) => *local,
+		}
+	}
+
+	pub(super) fn timezone(&self) -> Option<&str> {
+		match self {
+			EventStart::Fixed(_) => None,
+			EventStart::Zoned(_, timezone) => Some(timezone),
+		}
+	}
+}
+
+pub(super) async fn resolve_start(
+	time: &EventTimeDto,
+	location_id: Option<Uuid>,
+	user_id: Uuid,
+	repo: &Repository,
+) -> Result<EventStart, AppError> {
+	match (time.date, time.local_date) {
+		(Some(date), None) => Ok(EventStart::Fixed(date)),
+		(None, Some(local)) => {
+			let timezone = match time.timezone {
+				Some(ref timezone) => Some(timezone.clone()),
+				// для офлайн-игры берём пояс локации, для онлайн - пояс ведущего
+				None => repo.default_timezone(location_id, user_id).await?,
+			};
+
+			let Some(timezone) = timezone else {
+				return AppError::scenario_error(
+					"Не удалось определить часовой пояс игры, укажите его явно",
+					None::<&str>,
+				)
+				.into();
+			};
+
+			Ok(EventStart::Zoned(local, timezone))
+		}
+		(Some(_), Some(_)) => AppError::scenario_error(
+			"Укажите либо точное время игры, либо местное время",
+			None::<&str>,
+		)
+		.into(),
+		(None, None) => AppError::scenario_error("Не указано время начала игры", None::<&str>).into(),
+	}
+}
+
+/// Переводит местные даты в абсолютные по правилам часового пояса начала игры
+pub(super) async fn convert_dates(
+	start: &EventStart,
+	locals: &[NaiveDateTime],
+	repo: &Repository,
+) -> Result<Vec<DateTime<FixedOffset>>, AppError> {
+	match start {
+		EventStart::Fixed(date) => {
+			let offset = *date.offset();
+			Ok(locals
+				.iter()
+				.filter_map(|local| offset.from_local_datetime(local).single())
+				.collect())
+		}
+		EventStart::Zoned(_, timezone) => {
+			let locals = locals
+				.iter()
+				.map(|local| (*local, timezone.as_str()))
+				.collect::<Vec<_>>();
+
+			checked_dates(repo.local_to_utc(&locals).await?)
+		}
+	}
+}
+
+/// Результат перевода местного времени: пояс должен быть известен, а время -
+/// однозначным. Время на переводе часов не угадываем, его нужно указать явно
+pub(super) fn checked_dates(zoned: Vec<ZonedDate>) -> Result<Vec<DateTime<FixedOffset>>, AppError> {
+	if let Some(unknown) = zoned.iter().find(|date| date.date.is_none()) {
+		return AppError::scenario_error("Неизвестный часовой пояс", Some(&unknown.timezone)).into();
+	}
+
+	let unclear = zoned
+		.iter()
+		.filter(|date| date.in_gap || date.ambiguous)
+		.map(|date| date.local.format(LOCAL_TIME_FORMAT).to_string())
+		.collect::<Vec<_>>();
+
+	if !unclear.is_empty() {
+		return AppError::scenario_error(
+			"Время игры приходится на перевод часов, укажите другое время или точное смещение",
+			Some(unclear.join(", ")),
+		)
+		.into();
+	}
+
+	Ok(zoned
+		.into_iter()
+		.filter_map(|date| date.date)
+		.map(|date| date.fixed_offset())
+		.collect())
+}
+
+pub(super) async fn resolve_date(
+	time: &EventTimeDto,
+	location_id: Option<Uuid>,
+	user_id: Uuid,
+	repo: &Repository,
+) -> Result<DateTime<FixedOffset>, AppError> {
+	let start = resolve_start(time, location_id, user_id, repo).await?;
+
+	convert_dates(&start, &[start.local()], repo)
+		.await?
+		.pop()
+		.ok_or_else(|| AppError::scenario_error("Некорректное время начала игры", None::<&str>))
+}
+
+pub(super) fn check_venue(venue: &VenueDto, location_id: Option<Uuid>) -> Result<(), AppError> {
+	match venue.venue_type {
+		VenueType::Online if location_id.is_some() => {
+			AppError::scenario_error("У онлайн-игры не может быть локации", None::<&str>).into()
+		}
+		VenueType::Offline if venue.online_platform.is_some() || venue.join_link.is_some() => {
+			AppError::scenario_error(
+				"Платформа и ссылка на подключение указываются только для онлайн-игр",
+				None::<&str>,
+			)
+			.into()
+		}
+		_ if !validate_join_link(venue.join_link.as_deref()) => {
+			AppError::scenario_error("Некорректная ссылка на подключение", None::<&str>).into()
+		}
+		_ => Ok(()),
+	}
+}
+
+/// Ссылка на подключение открывается у игроков, поэтому принимаются только http(s)-адреса
+fn validate_join_link(join_link: Option<&str>) -> bool {
+	join_link.is_none_or(|link| {
+		Url::parse(link)
+			.is_ok_and(|url| matches!(url.scheme(), "http" | "https") && url.host().is_some())
+	})
 }
 
-async fn check_location(location_id: Option<Uuid>, repo: &Repository) -> Result<(), AppError> {
+pub(super) async fn check_location(
+	location_id: Option<Uuid>,
+	repo: &Repository,
+) -> Result<(), AppError> {
 	if let Some(l_id) = location_id {
 		let may_be_location = repo.get_location_by_id(l_id).await?;
 		if may_be_location.is_none() {
@@ -4,18 +4,25 @@ use uuid::Uuid;
 
 use crate::{
 	dto::{
-		Dto,
-		location::{NewLocationDto, ReadLocationDto},
+		Dto, QueryDto,
+		location::{NearDto, NewLocationDto, ReadLocationDto},
+		page::PageDto,
+		schedule::WeeklyWindowDto,
 	},
+	geo::{self, Coordinates},
 	state::AppState,
-	system_models::{AppResponse, AppResult},
+	system_models::{AppError, AppResponse, AppResult},
 };
 
+// по два интервала на каждый день недели
+const MAX_OPENING_WINDOWS: usize = 14;
+
 pub(crate) async fn get_locations_list(
 	State(state): State<Arc<AppState>>,
+	QueryDto(page): QueryDto<PageDto>,
 	Dto(query): Dto<ReadLocationDto>,
 ) -> AppResult {
-	let locations = state.repo.get_locations_list(query).await?;
+	let locations = state.repo.get_locations_list(query, &page).await?;
 
 	let json_value = serde_json::to_value(locations)?;
 
@@ -40,9 +47,27 @@ pub(crate) async fn get_location_by_id(
 	})
 }
 
+pub(crate) async fn get_nearby_locations(
+	State(state): State<Arc<AppState>>,
+	Dto(query): Dto<NearDto>,
+) -> AppResult {
+	if Coordinates::new(query.lat, query.lon).is_none() {
+		return Ok(AppResponse::scenario_fail("Некорректные координаты", None));
+	}
+
+	let locations = state.repo.read_nearby_locations(&query).await?;
+
+	let json_value = serde_json::to_value(locations)?;
+
+	return Ok(AppResponse::scenario_success(
+		"Локации рядом",
+		Some(json_value),
+	));
+}
+
 pub(crate) async fn add_location(
 	State(state): State<Arc<AppState>>,
-	Dto(body): Dto<NewLocationDto>,
+	Dto(mut body): Dto<NewLocationDto>,
 ) -> AppResult {
 	if !validate_map_link(&body.map_link) {
 		return Ok(AppResponse::scenario_fail(
@@ -51,16 +76,27 @@ pub(crate) async fn add_location(
 		));
 	}
 
-	let new_loc_id = state
-		.repo
-		.add_location(
-			&body.name,
-			&body.address,
-			&body.description,
-			&body.city,
-			&body.map_link,
-		)
-		.await?;
+	check_capacity(body.tables, &body.opening_hours)?;
+
+	let coordinates = match (body.latitude, body.longitude) {
+		(Some(latitude), Some(longitude)) => {
+			let Some(coordinates) = Coordinates::new(latitude, longitude) else {
+				return Ok(AppResponse::scenario_fail("Некорректные координаты", None));
+			};
+			Some(coordinates)
+		}
+		(None, None) => body.map_link.as_deref().and_then(geo::parse_map_link),
+		_ => {
+			return Ok(AppResponse::scenario_fail(
+				"Необходимо указать и широту, и долготу",
+				None,
+			));
+		}
+	};
+	body.latitude = coordinates.map(|c| c.latitude);
+	body.longitude = coordinates.map(|c| c.longitude);
+
+	let new_loc_id = state.repo.add_location(&body).await?;
 
 	return Ok(AppResponse::scenario_success(
 		"Локация успешно добавлена",
@@ -68,6 +104,22 @@ pub(crate) async fn add_location(
 	));
 }
 
+pub(super) fn check_capacity(
+	tables: Option<i16>,
+	hours: &[WeeklyWindowDto],
+) -> Result<(), AppError> {
+	if tables.is_some_and(|tables| tables <= 0) {
+		return AppError::scenario_error("Количество столов должно быть положительным", None::<&str>)
+			.into();
+	}
+
+	if hours.len() > MAX_OPENING_WINDOWS || !hours.iter().all(WeeklyWindowDto::is_valid) {
+		return AppError::scenario_error("Некорректные часы работы", None::<&str>).into();
+	}
+
+	Ok(())
+}
+
 fn validate_map_link(map_link: &Option<String>) -> bool {
 	map_link.as_ref().is_none_or(|link| {
 		link.starts_with("https://2gis.ru/")
@@ -0,0 +1,185 @@
+use ::std::sync::Arc;
+use axum::{
+	Extension,
+	extract::{Path, State},
+};
+use uuid::Uuid;
+
+use crate::{
+	dto::{
+		Dto,
+		company::{CompanyPermission, InviteMemberDto, UpdateMemberRoleDto},
+	},
+	handlers::events::check_company,
+	notification::NotificationPayload,
+	state::AppState,
+	system_models::{AppError, AppResponse, AppResult},
+};
+
+pub(crate) async fn read_company_members(
+	State(state): State<Arc<AppState>>,
+	Extension(user_id): Extension<Option<Uuid>>,
+	Path(company_id): Path<Uuid>,
+) -> AppResult {
+	// состав приватной кампании видят только её мастер и участники
+	if state
+		.repo
+		.get_company_by_id(company_id, user_id)
+		.await?
+		.is_none()
+	{
+		return Ok(AppResponse::scenario_fail("Кампания не найдена", None));
+	}
+
+	let members = state.repo.read_company_members(company_id).await?;
+
+	let payload = serde_json::to_value(members)?;
+
+	return Ok(AppResponse::scenario_success(
+		"Участники кампании",
+		Some(payload),
+	));
+}
+
+pub(crate) async fn invite_member(
+	State(state): State<Arc<AppState>>,
+	Extension(master_id): Extension<Uuid>,
+	Path(company_id): Path<Uuid>,
+	Dto(body): Dto<InviteMemberDto>,
+) -> AppResult {
+	let company = check_company(
+		company_id,
+		master_id,
+		CompanyPermission::Company,
+		&state.repo,
+	)
+	.await?;
+
+	if body.user == company.master {
+		return AppError::scenario_error(
+			"Мастер не может пригласить в кампанию сам себя",
+			None::<&str>,
+		)
+		.into();
+	}
+
+	if !state.repo.invite_member(company_id, body.user).await? {
+		return Ok(AppResponse::scenario_fail(
+			"Пользователь уже состоит в кампании или приглашен",
+			None,
+		));
+	}
+
+	state
+		.notify(
+			&[body.user],
+			NotificationPayload::MemberInvited {
+				company_id,
+				company_name: company.name,
+			},
+		)
+		.await;
+
+	Ok(AppResponse::scenario_success(
+		"Приглашение отправлено",
+		None,
+	))
+}
+
+pub(crate) async fn accept_membership(
+	State(state): State<Arc<AppState>>,
+	Extension(user_id): Extension<Uuid>,
+	Path(company_id): Path<Uuid>,
+) -> AppResult {
+	match state.repo.accept_membership(company_id, user_id).await? {
+		false => Err(AppError::scenario_error(
+			"Приглашение не найдено",
+			None::<&str>,
+		)),
+		true => Ok(AppResponse::scenario_success(
+			"Вы вступили в кампанию",
+			None,
+		)),
+	}
+}
+
+pub(crate) async fn leave_company(
+	State(state): State<Arc<AppState>>,
+	Extension(user_id): Extension<Uuid>,
+	Path(company_id): Path<Uuid>,
+) -> AppResult {
+	match state.repo.remove_member(company_id, user_id).await? {
+		false => Err(AppError::scenario_error(
+			"Вы не состоите в кампании",
+			None::<&str>,
+		)),
+		true => Ok(AppResponse::scenario_success("Вы покинули кампанию", None)),
+	}
+}
+
+pub(crate) async fn remove_member(
+	State(state): State<Arc<AppState>>,
+	Extension(master_id): Extension<Uuid>,
+	Path((company_id, member_id)): Path<(Uuid, Uuid)>,
+) -> AppResult {
+	let company = check_company(
+		company_id,
+		master_id,
+		CompanyPermission::Company,
+		&state.repo,
+	)
+	.await?;
+
+	if !state.repo.remove_member(company_id, member_id).await? {
+		return Err(AppError::scenario_error("Участник не найден", None::<&str>));
+	}
+
+	state
+		.notify(
+			&[member_id],
+			NotificationPayload::MemberRemoved {
+				company_id,
+				company_name: company.name,
+			},
+		)
+		.await;
+
+	Ok(AppResponse::scenario_success(
+		"Участник исключен из кампании",
+		None,
+	))
+}
+
+pub(crate) async fn set_member_role(
+	State(state): State<Arc<AppState>>,
+	Extension(master_id): Extension<Uuid>,
+	Path((company_id, member_id)): Path<(Uuid, Uuid)>,
+	Dto(body): Dto<UpdateMemberRoleDto>,
+) -> AppResult {
+	let Some(company) = state
+		.repo
+		.get_company_by_id(company_id, Some(master_id))
+		.await?
+	else {
+		return AppError::scenario_error("Кампания не найдена", Some(company_id.to_string())).into();
+	};
+
+	// раздавать роли может только владелец кампании
+	if company.master != master_id {
+		return AppError::scenario_error("Вы не можете управлять данной кампанией", None::<&str>)
+			.into();
+	}
+
+	if !state
+		.repo
+		.set_member_role(company_id, member_id, body.role)
+		.await?
+	{
+		return Err(AppError::scenario_error("Участник не найден", None::<&str>));
+	}
+
+	Ok(AppResponse::scenario_success(
+		"Роль участника обновлена",
+		None,
+	))
+}
@@ -1,9 +1,18 @@
+pub(super) mod admin;
 pub(super) mod apps;
+pub(super) mod calendar;
 pub(super) mod companies;
 pub(super) mod events;
 pub(super) mod locations;
+pub(super) mod members;
+pub(super) mod notifications;
+pub(super) mod polls;
 pub(super) mod regions;
+pub(super) mod schedule;
+pub(super) mod search;
+pub(super) mod series;
 pub(super) mod sse;
+pub(super) mod telegram;
 pub(super) mod verify;
 
 use ::std::{str::FromStr as _, sync::Arc};
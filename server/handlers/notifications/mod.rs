@@ -0,0 +1,72 @@
+use ::std::sync::Arc;
+use axum::{
+	Extension,
+	extract::{Path, State},
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+	dto::{Dto, QueryDto, notification::ReadNotificationsDto, page::PageDto},
+	state::AppState,
+	system_models::{AppError, AppResponse, AppResult},
+};
+
+pub(crate) async fn read_notifications(
+	State(state): State<Arc<AppState>>,
+	Extension(user_id): Extension<Uuid>,
+	QueryDto(page): QueryDto<PageDto>,
+	Dto(query): Dto<ReadNotificationsDto>,
+) -> AppResult {
+	let notifications = state
+		.repo
+		.read_notifications(user_id, &query, &page)
+		.await?;
+
+	let payload = serde_json::to_value(notifications)?;
+
+	return Ok(AppResponse::scenario_success(
+		"Список уведомлений",
+		Some(payload),
+	));
+}
+
+pub(crate) async fn count_unread_notifications(
+	State(state): State<Arc<AppState>>,
+	Extension(user_id): Extension<Uuid>,
+) -> AppResult {
+	let count = state.repo.count_unread_notifications(user_id).await?;
+
+	return Ok(AppResponse::scenario_success(
+		"Количество непрочитанных уведомлений",
+		Some(json!({ "count": count })),
+	));
+}
+
+pub(crate) async fn mark_notification_read(
+	State(state): State<Arc<AppState>>,
+	Extension(user_id): Extension<Uuid>,
+	Path(notification_id): Path<Uuid>,
+) -> AppResult {
+	if !state
+		.repo
+		.mark_notification_read(user_id, notification_id)
+		.await?
+	{
+		return AppError::scenario_error("Уведомление не найдено", None::<&str>).into();
+	}
+
+	return Ok(AppResponse::scenario_success("Уведомление прочитано", None));
+}
+
+pub(crate) async fn mark_all_notifications_read(
+	State(state): State<Arc<AppState>>,
+	Extension(user_id): Extension<Uuid>,
+) -> AppResult {
+	let updated = state.repo.mark_all_notifications_read(user_id).await?;
+
+	return Ok(AppResponse::scenario_success(
+		"Все уведомления прочитаны",
+		Some(json!({ "updated": updated })),
+	));
+}
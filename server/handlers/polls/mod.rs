@@ -0,0 +1,276 @@
+use ::std::{collections::HashSet, sync::Arc};
+use axum::{
+	Extension,
+	extract::{Path, State},
+};
+use chrono::{DateTime, Utc};
+use futures::future::try_join_all;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+	dto::{
+		Dto, QueryDto,
+		company::CompanyPermission,
+		event::{ConflictQuery, EventTimeDto, NewEventDto},
+		poll::{ConvertPollDto, MAX_POLL_SLOTS, NewDatePollDto, PollVotesDto},
+	},
+	handlers::events::{
+		check_company, check_location, check_venue, flat_conflicts, flat_location_issues,
+		resolve_date, schedule_rejected,
+	},
+	notification::NotificationPayload,
+	repository::models::{Checked, DatePoll, PollSlot, ScheduleCheck},
+	state::AppState,
+	system_models::{AppError, AppResponse, AppResult},
+};
+
+pub(crate) async fn add_date_poll(
+	State(state): State<Arc<AppState>>,
+	Extension(user_id): Extension<Uuid>,
+	Path(company_id): Path<Uuid>,
+	Dto(body): Dto<NewDatePollDto>,
+) -> AppResult {
+	if body.slots.len() < 2 {
+		return Ok(AppResponse::scenario_fail(
+			"Укажите хотя бы два варианта времени",
+			None,
+		));
+	}
+
+	if body.slots.len() > MAX_POLL_SLOTS {
+		return Ok(AppResponse::scenario_fail(
+			"Слишком много вариантов времени",
+			Some(MAX_POLL_SLOTS.into()),
+		));
+	}
+
+	check_company(company_id, user_id, CompanyPermission::Events, &state.repo).await?;
+
+	// варианты без пояса трактуются в поясе ведущего, как у онлайн-игры
+	let dates = try_join_all(
+		body
+			.slots
+			.iter()
+			.map(|slot| resolve_date(slot, None, user_id, &state.repo)),
+	)
+	.await?;
+
+	let now = Utc::now();
+	if dates.iter().any(|date| *date <= now) {
+		return Ok(AppResponse::scenario_fail(
+			"Варианты времени должны быть в будущем",
+			None,
+		));
+	}
+
+	let title = body.title.as_deref().filter(|title| !title.is_empty());
+	let poll_id = state.repo.add_date_poll(company_id, title, &dates).await?;
+
+	let payload = serde_json::to_value(poll_id)?;
+
+	return Ok(AppResponse::scenario_success("Опрос создан", Some(payload)));
+}
+
+pub(crate) async fn read_company_polls(
+	State(state): State<Arc<AppState>>,
+	Extension(user_id): Extension<Uuid>,
+	Path(company_id): Path<Uuid>,
+) -> AppResult {
+	let (can_manage, members) = futures::try_join!(
+		state
+			.repo
+			.has_company_permission(company_id, user_id, CompanyPermission::Events),
+		state.repo.read_company_members(company_id),
+	)?;
+
+	if !can_manage && !members.iter().any(|member| member.user_id == user_id) {
+		return Ok(AppResponse::scenario_fail(
+			"Опросы доступны только участникам кампании",
+			None,
+		));
+	}
+
+	let polls = state.repo.read_company_polls(company_id).await?;
+	let payload = serde_json::to_value(polls)?;
+
+	return Ok(AppResponse::scenario_success(
+		"Опросы кампании",
+		Some(payload),
+	));
+}
+
+pub(crate) async fn read_date_poll(
+	State(state): State<Arc<AppState>>,
+	Extension(user_id): Extension<Uuid>,
+	Path(poll_id): Path<Uuid>,
+) -> AppResult {
+	let poll = find_poll(poll_id, user_id, &state).await?;
+	let payload = serde_json::to_value(poll)?;
+
+	return Ok(AppResponse::scenario_success("Опрос", Some(payload)));
+}
+
+pub(crate) async fn vote_date_poll(
+	State(state): State<Arc<AppState>>,
+	Extension(user_id): Extension<Uuid>,
+	Path(poll_id): Path<Uuid>,
+	Dto(body): Dto<PollVotesDto>,
+) -> AppResult {
+	let poll = find_poll(poll_id, user_id, &state).await?;
+
+	if !poll.you_can_vote {
+		return Ok(AppResponse::scenario_fail(
+			"Голосовать могут только участники кампании",
+			None,
+		));
+	}
+
+	if poll.event_id.is_some() {
+		return Ok(AppResponse::scenario_fail("Голосование завершено", None));
+	}
+
+	let mut slots = HashSet::with_capacity(body.votes.len());
+	if !body.votes.iter().all(|vote| slots.insert(vote.slot)) {
+		return Ok(AppResponse::scenario_fail(
+			"Вариант времени указан несколько раз",
+			None,
+		));
+	}
+
+	let counted = state
+		.repo
+		.vote_date_poll(poll_id, user_id, &body.votes)
+		.await?;
+
+	return Ok(AppResponse::scenario_success(
+		"Голос учтён",
+		Some(counted.into()),
+	));
+}
+
+pub(crate) async fn convert_date_poll(
+	State(state): State<Arc<AppState>>,
+	Extension(user_id): Extension<Uuid>,
+	Path(poll_id): Path<Uuid>,
+	QueryDto(conflict): QueryDto<ConflictQuery>,
+	Dto(body): Dto<ConvertPollDto>,
+) -> AppResult {
+	let poll = find_poll(poll_id, user_id, &state).await?;
+
+	if !poll.you_can_manage {
+		return Ok(AppResponse::scenario_fail(
+			"Недостаточно прав для создания игры",
+			None,
+		));
+	}
+
+	if let Some(event_id) = poll.event_id {
+		let payload = serde_json::to_value(event_id)?;
+		return Ok(AppResponse::scenario_fail(
+			"Игра по этому опросу уже создана",
+			Some(payload),
+		));
+	}
+
+	let slot = match body.slot {
+		Some(slot_id) => poll.slots.iter().find(|slot| slot.id == slot_id),
+		None => pick_winner(&poll.slots, Utc::now()),
+	};
+
+	let Some(slot) = slot else {
+		return Ok(AppResponse::scenario_fail(
+			"Вариант времени не найден",
+			None,
+		));
+	};
+
+	if slot.date <= Utc::now() {
+		return Ok(AppResponse::scenario_fail(
+			"Выбранный вариант уже в прошлом",
+			None,
+		));
+	}
+
+	check_venue(&body.venue, body.location)?;
+	check_location(body.location, &state.repo).await?;
+
+	let date = slot.date.fixed_offset();
+
+	let event = NewEventDto {
+		company: poll.company_id,
+		location: body.location,
+		time: EventTimeDto {
+			date: Some(date),
+			local_date: None,
+			timezone: None,
+		},
+		max_slots: body.max_slots,
+		plan_duration: body.plan_duration,
+		visibility: body.visibility,
+		venue: body.venue,
+	};
+
+	let check = ScheduleCheck {
+		user_id,
+		strict: conflict.strict,
+	};
+
+	let (converted, checks) = match state
+		.repo
+		.convert_date_poll(poll_id, &event, date, check)
+		.await?
+	{
+		None => {
+			return Ok(AppResponse::scenario_fail(
+				"Игра по этому опросу уже создана",
+				None,
+			));
+		}
+		Some(Checked::Rejected(checks)) => return schedule_rejected(&checks),
+		Some(Checked::Saved(converted, checks)) => (converted, checks),
+	};
+
+	state
+		.notify(
+			&converted.voters,
+			NotificationPayload::EventScheduled {
+				poll_id,
+				event_id: converted.event_id,
+				company_name: poll.company_name,
+				date: slot.date,
+			},
+		)
+		.await;
+
+	let payload = json!({
+		"id": converted.event_id,
+		"conflicts": flat_conflicts(&checks),
+		"location_issues": flat_location_issues(&checks),
+	});
+
+	return Ok(AppResponse::scenario_success(
+		"Игра создана по итогам опроса",
+		Some(payload),
+	));
+}
+
+async fn find_poll(poll_id: Uuid, user_id: Uuid, state: &AppState) -> Result<DatePoll, AppError> {
+	state
+		.repo
+		.read_date_poll(poll_id, user_id)
+		.await?
+		.filter(|poll| poll.you_can_manage || poll.you_can_vote)
+		.ok_or_else(|| AppError::scenario_error("Опрос не найден", Some(poll_id.to_string())))
+}
+
+/// Больше всего "да", при равенстве - больше "может быть", затем более ранний вариант.
+/// Прошедшие варианты не рассматриваются
+fn pick_winner(slots: &[PollSlot], now: DateTime<Utc>) -> Option<&PollSlot> {
+	slots.iter().filter(|slot| slot.date > now).max_by(|a, b| {
+		a.yes
+			.cmp(&b.yes)
+			.then(a.maybe.cmp(&b.maybe))
+			.then(b.date.cmp(&a.date))
+	})
+}
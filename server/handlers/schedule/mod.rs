@@ -0,0 +1,154 @@
+use ::std::sync::Arc;
+use axum::{
+	Extension,
+	extract::{Path, State},
+};
+use chrono::{Duration, Utc};
+use futures::try_join;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+	dto::{
+		Dto,
+		company::CompanyPermission,
+		schedule::{AvailabilityDto, MAX_AVAILABILITY_WINDOWS, SuggestionsDto, WeeklyWindowDto},
+	},
+	handlers::events::check_company,
+	scheduling::{self, DEFAULT_DURATION_HOURS, Interval, PlayerSchedule},
+	state::AppState,
+	system_models::{AppResponse, AppResult},
+};
+
+pub(crate) async fn read_my_availability(
+	State(state): State<Arc<AppState>>,
+	Extension(user_id): Extension<Uuid>,
+) -> AppResult {
+	let windows = state.repo.read_availability(user_id).await?;
+	let payload = serde_json::to_value(windows)?;
+
+	return Ok(AppResponse::scenario_success(
+		"Свободное время",
+		Some(payload),
+	));
+}
+
+pub(crate) async fn set_my_availability(
+	State(state): State<Arc<AppState>>,
+	Extension(user_id): Extension<Uuid>,
+	Dto(body): Dto<AvailabilityDto>,
+) -> AppResult {
+	if body.windows.len() > MAX_AVAILABILITY_WINDOWS {
+		return Ok(AppResponse::scenario_fail(
+			"Слишком много интервалов",
+			Some(MAX_AVAILABILITY_WINDOWS.into()),
+		));
+	}
+
+	if !body.windows.iter().all(WeeklyWindowDto::is_valid) {
+		return Ok(AppResponse::scenario_fail(
+			"Некорректный интервал свободного времени",
+			None,
+		));
+	}
+
+	let windows = scheduling::merge_weekly(&body.windows);
+	state.repo.set_availability(user_id, &windows).await?;
+
+	return Ok(AppResponse::scenario_success(
+		"Свободное время обновлено",
+		None,
+	));
+}
+
+pub(crate) async fn suggest_times(
+	State(state): State<Arc<AppState>>,
+	Extension(user_id): Extension<Uuid>,
+	Path(company_id): Path<Uuid>,
+	Dto(query): Dto<SuggestionsDto>,
+) -> AppResult {
+	let (_, members) = try_join!(
+		check_company(company_id, user_id, CompanyPermission::Events, &state.repo),
+		state.repo.read_company_members(company_id),
+	)?;
+
+	// по умолчанию - постоянные игроки, иначе - указанные участники кампании
+	let players = members
+		.into_iter()
+		.filter(|member| match query.players.is_empty() {
+			true => member.status == "active" && member.role == "player",
+			false => query.players.contains(&member.user_id),
+		})
+		.map(|member| member.user_id)
+		.collect::<Vec<_>>();
+
+	if players.is_empty() {
+		return Ok(AppResponse::scenario_fail(
+			"В кампании нет подходящих игроков",
+			None,
+		));
+	}
+
+	let from = Utc::now();
+	let to = from + Duration::days(query.days().into());
+
+	let (free, busy) = try_join!(
+		state.repo.read_free_intervals(&players, from, to),
+		state.repo.read_busy_intervals(&players, from, to),
+	)?;
+
+	let schedules = players
+		.iter()
+		.map(|player_id| PlayerSchedule {
+			user_id: *player_id,
+			// окна соседних дней стыкуются, игра может идти через полночь
+			free: scheduling::merge(
+				free
+					.iter()
+					.filter(|interval| interval.user_id == *player_id)
+					.map(|interval| Interval {
+						start: interval.start,
+						end: interval.end,
+					})
+					.collect(),
+			),
+			busy: busy
+				.iter()
+				.filter(|interval| interval.user_id == *player_id)
+				.map(|interval| Interval {
+					start: interval.start,
+					end: interval.end,
+				})
+				.collect(),
+		})
+		.collect::<Vec<_>>();
+
+	let without_availability = schedules
+		.iter()
+		.filter(|schedule| schedule.free.is_empty())
+		.map(|schedule| schedule.user_id)
+		.collect::<Vec<_>>();
+
+	let duration = query
+		.duration
+		.filter(|hours| *hours > 0)
+		.unwrap_or(DEFAULT_DURATION_HOURS);
+
+	let suggestions = scheduling::suggest(
+		&schedules,
+		from,
+		Duration::hours(duration.into()),
+		query.limit().into(),
+	);
+
+	let payload = json!({
+		"players": players,
+		"without_availability": without_availability,
+		"suggestions": suggestions,
+	});
+
+	return Ok(AppResponse::scenario_success(
+		"Подходящее время",
+		Some(payload),
+	));
+}
@@ -0,0 +1,28 @@
+use ::std::sync::Arc;
+use axum::{Extension, extract::State};
+use uuid::Uuid;
+
+use crate::{
+	dto::{Dto, search::SearchDto},
+	state::AppState,
+	system_models::{AppResponse, AppResult},
+};
+
+pub(crate) async fn search(
+	State(state): State<Arc<AppState>>,
+	Extension(user_id): Extension<Option<Uuid>>,
+	Dto(query): Dto<SearchDto>,
+) -> AppResult {
+	let Some(tsquery) = query.prefix_query() else {
+		return Ok(AppResponse::scenario_fail("Введите поисковый запрос", None));
+	};
+
+	let hits = state.repo.search(&query, &tsquery, user_id).await?;
+
+	let json_value = serde_json::to_value(hits)?;
+
+	return Ok(AppResponse::scenario_success(
+		"Результаты поиска",
+		Some(json_value),
+	));
+}
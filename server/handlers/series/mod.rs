@@ -0,0 +1,173 @@
+use ::std::sync::Arc;
+use axum::{
+	Extension,
+	extract::{Path, State},
+};
+use futures::try_join;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+	dto::{
+		Dto, QueryDto,
+		company::CompanyPermission,
+		event::{ConflictQuery, NewSeriesDto, SeriesExceptionsDto},
+	},
+	handlers::events::{
+		check_company, check_location, check_venue, convert_dates, flat_conflicts,
+		flat_location_issues, resolve_start, schedule_rejected,
+	},
+	notification::NotificationPayload,
+	recurrence::{self, RecurrenceRule},
+	repository::models::{Checked, ScheduleCheck},
+	state::AppState,
+	system_models::{AppResponse, AppResult},
+};
+
+pub(crate) async fn add_series(
+	State(state): State<Arc<AppState>>,
+	Extension(user_id): Extension<Uuid>,
+	QueryDto(conflict): QueryDto<ConflictQuery>,
+	Dto(body): Dto<NewSeriesDto>,
+) -> AppResult {
+	if body.until.is_none() && body.count.is_none() {
+		return Ok(AppResponse::scenario_fail(
+			"Необходимо указать дату окончания или количество игр серии",
+			None,
+		));
+	}
+
+	check_venue(&body.venue, body.location)?;
+
+	let (start, ..) = try_join!(
+		resolve_start(&body.time, body.location, user_id, &state.repo),
+		check_company(
+			body.company,
+			user_id,
+			CompanyPermission::Events,
+			&state.repo
+		),
+		check_location(body.location, &state.repo),
+	)?;
+
+	let rule = RecurrenceRule {
+		frequency: body.frequency,
+		until: body.until,
+		count: body.count,
+	};
+
+	if body.until.is_some_and(|until| until < start.local().date()) {
+		return Ok(AppResponse::scenario_fail(
+			"Дата окончания серии раньше даты первой игры",
+			None,
+		));
+	}
+
+	// каждая игра переводится по правилам пояса на свою дату, с учётом летнего времени
+	let locals = recurrence::expand(start.local(), &rule, &body.exceptions);
+	let dates = convert_dates(&start, &locals, &state.repo).await?;
+
+	if dates.is_empty() {
+		return Ok(AppResponse::scenario_fail(
+			"По заданному правилу не получилось ни одной игры",
+			None,
+		));
+	}
+
+	let check = ScheduleCheck {
+		user_id,
+		strict: conflict.strict,
+	};
+
+	let (ids, checks) = match state
+		.repo
+		.add_series(&body, &dates, start.timezone(), check)
+		.await?
+	{
+		Checked::Saved(ids, checks) => (ids, checks),
+		Checked::Rejected(checks) => return schedule_rejected(&checks),
+	};
+
+	let payload = json!({
+		"series_id": ids.series_id,
+		"events": ids.events,
+		"conflicts": flat_conflicts(&checks),
+		"location_issues": flat_location_issues(&checks),
+	});
+
+	Ok(AppResponse::scenario_success(
+		"Серия событий успешно создана",
+		Some(payload),
+	))
+}
+
+pub(crate) async fn read_series(
+	State(state): State<Arc<AppState>>,
+	Extension(user_id): Extension<Option<Uuid>>,
+	Path(series_id): Path<Uuid>,
+) -> AppResult {
+	let Some(series) = state.repo.read_series(series_id, user_id).await? else {
+		return Ok(AppResponse::scenario_fail("Серия событий не найдена", None));
+	};
+
+	// серию приватной кампании видят только её мастер и участники
+	if state
+		.repo
+		.get_company_by_id(series.company_id, user_id)
+		.await?
+		.is_none()
+	{
+		return Ok(AppResponse::scenario_fail("Серия событий не найдена", None));
+	}
+
+	let payload = serde_json::to_value(series)?;
+
+	Ok(AppResponse::scenario_success(
+		"Серия событий",
+		Some(payload),
+	))
+}
+
+/// Добавляет даты исключений в уже созданную серию. Игры на эти даты отменяются,
+/// их игроки получают уведомление
+pub(crate) async fn add_series_exceptions(
+	State(state): State<Arc<AppState>>,
+	Extension(user_id): Extension<Uuid>,
+	Path(series_id): Path<Uuid>,
+	Dto(body): Dto<SeriesExceptionsDto>,
+) -> AppResult {
+	if body.dates.is_empty() {
+		return Ok(AppResponse::scenario_fail(
+			"Не указаны даты исключений",
+			None,
+		));
+	}
+
+	let Some(cancelled) = state
+		.repo
+		.add_series_exceptions(series_id, user_id, &body.dates)
+		.await?
+	else {
+		return Ok(AppResponse::scenario_fail("Серия событий не найдена", None));
+	};
+
+	for event in &cancelled {
+		state
+			.notify(
+				&event.players,
+				NotificationPayload::EventCancelled {
+					event_id: event.id,
+					company_name: event.company_name.clone(),
+					date: event.date,
+				},
+			)
+			.await;
+	}
+
+	let cancelled = cancelled.iter().map(|event| event.id).collect::<Vec<_>>();
+
+	Ok(AppResponse::scenario_success(
+		"Исключения серии сохранены",
+		Some(json!({ "cancelled": cancelled })),
+	))
+}
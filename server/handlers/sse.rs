@@ -2,39 +2,137 @@ use std::{convert::Infallible, sync::Arc};
 
 use axum::{
 	extract::{Extension, State},
+	http::HeaderMap,
 	response::sse::{Event, KeepAlive, Sse},
 };
-use futures_util::{StreamExt as _FuturesStreamExt, stream::Stream};
-use tokio_stream::{StreamExt as TokioStreamExt, wrappers::BroadcastStream};
+use futures_util::{
+	StreamExt as _FuturesStreamExt,
+	stream::{self, Stream},
+};
+use tokio_stream::{
+	StreamExt as TokioStreamExt,
+	wrappers::{BroadcastStream, ReceiverStream, errors::BroadcastStreamRecvError},
+};
+use utoipa::OpenApi;
 use uuid::Uuid;
 
-use crate::state::AppState;
+use crate::{
+	event_log::{Replay, SseMessage},
+	notification::{NotificationEvent, NotificationPayload},
+	state::AppState,
+	subscribers::Subscription,
+};
+
+const LAST_EVENT_ID: &str = "last-event-id";
+
+/// Описание потока SSE для спецификации: обычные маршруты её не описывают,
+/// а формат событий нужен клиентам
+#[derive(OpenApi)]
+#[openapi(
+	paths(sse_handler),
+	components(schemas(NotificationEvent, NotificationPayload))
+)]
+pub(crate) struct SseApi;
 
+/// Уведомления приходят событиями SSE с именем из поля `type` и JSON `NotificationEvent`
+/// в `data`. Событие `reset` означает, что часть уведомлений пропущена и входящие
+/// нужно перечитать; `announcement` с JSON `{ "message": ... }` - объявление для всех
+/// подключенных; `heartbeat` поддерживает соединение
+#[utoipa::path(
+	get,
+	path = "/api/sse",
+	tag = "notifications",
+	params(
+		("Last-Event-ID" = Option<u64>, Header, description = "id последнего полученного события, браузер присылает его сам при переподключении"),
+	),
+	responses(
+		(status = 200, description = "Поток событий", content_type = "text/event-stream", body = NotificationEvent),
+	),
+)]
 pub(crate) async fn sse_handler(
 	State(state): State<Arc<AppState>>,
 	Extension(user_id): Extension<Option<Uuid>>,
+	headers: HeaderMap,
 ) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
-	// Создаем подписки с явным временем жизни
-	let message_receiver = state.message_sender.subscribe();
+	// Создаем подписки с явным временем жизни. Подписка на сообщения
+	// оформляется до чтения журнала, чтобы между ними ничего не потерялось
+	let Subscription {
+		personal,
+		global,
+		guard,
+	} = state.subscribers.subscribe(user_id);
 	let heartbeat_receiver = state.heartbeat_sender.subscribe();
 	let mut shutdown_receiver = state.shutdown_sender.subscribe();
 
-	let event_stream = TokioStreamExt::filter_map(
-		BroadcastStream::new(message_receiver),
-		move |event_result| {
-			let (for_user, msg) = event_result.ok()?;
-			for_user
-				.is_none_or(|id| Some(id) == user_id)
-				.then_some(Ok(Event::default().data(msg)))
-		},
-	);
+	// браузер сам присылает id последнего полученного события при переподключении
+	let last_event_id = headers
+		.get(LAST_EVENT_ID)
+		.and_then(|value| value.to_str().ok())
+		.and_then(|value| value.trim().parse::<u64>().ok());
+
+	let (replayed, replayed_seq) = match last_event_id.map(|id| state.event_log.replay(id, user_id))
+	{
+		None => (Vec::new(), 0),
+		Some(Replay::Missed { messages, seq, .. }) => {
+			(messages.into_iter().map(to_event).collect(), seq)
+		}
+		Some(Replay::Reset { head, seq }) => (vec![reset_event().id(head.to_string())], seq),
+	};
+
+	// Элемент `None` закрывает соединение: личная очередь закрывается,
+	// когда клиент не успевает её разбирать
+	let personal_stream = stream::iter(personal).flat_map(move |receiver| {
+		TokioStreamExt::chain(
+			TokioStreamExt::filter_map(ReceiverStream::new(receiver), move |message| {
+				// уже отправленное из журнала не дублируем
+				(message.seq > replayed_seq).then(|| Some(to_event(message)))
+			}),
+			stream::iter([None]),
+		)
+	});
+
+	let subscribers = state.subscribers.clone();
+	let global_stream =
+		TokioStreamExt::filter_map(BroadcastStream::new(global), move |event_result| {
+			match event_result {
+				Ok(message) => (message.seq > replayed_seq).then(|| Some(to_event(message))),
+				// часть сообщений пропущена - клиенту стоит перечитать входящие
+				Err(BroadcastStreamRecvError::Lagged(missed)) => {
+					subscribers.lagged(missed);
+					user_id.map(|_| Some(reset_event()))
+				}
+			}
+		});
 
 	let heartbeat_stream = TokioStreamExt::map(BroadcastStream::new(heartbeat_receiver), |_| {
-		Ok(Event::default().event("heartbeat"))
+		Some(Event::default().event("heartbeat"))
 	});
 
-	let stream = TokioStreamExt::take_while(event_stream.merge(heartbeat_stream), Result::is_ok)
+	let live_stream = TokioStreamExt::map_while(
+		TokioStreamExt::merge(
+			TokioStreamExt::merge(personal_stream, global_stream),
+			heartbeat_stream,
+		),
+		// подписка снимается вместе с потоком
+		move |event| {
+			let _guard = &guard;
+			event.map(Ok)
+		},
+	);
+
+	let stream = TokioStreamExt::chain(stream::iter(replayed.into_iter().map(Ok)), live_stream)
 		.take_until(async move { shutdown_receiver.recv().await.ok() });
 
 	Sse::new(stream).keep_alive(KeepAlive::default())
 }
+
+fn to_event(message: SseMessage) -> Event {
+	Event::default()
+		.id(message.id.to_string())
+		.event(message.event)
+		.data(message.data)
+}
+
+fn reset_event() -> Event {
+	Event::default().event("reset")
+}
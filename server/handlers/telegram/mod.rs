@@ -0,0 +1,133 @@
+use ::std::sync::Arc;
+use axum::{
+	Extension, Json,
+	extract::State,
+	http::{HeaderMap, StatusCode},
+};
+use chrono::{Duration, Utc};
+use rand::Rng as _;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+	dto::{Dto, notification::TelegramNotificationsDto},
+	state::AppState,
+	system_models::{AppError, AppResponse, AppResult},
+	telegram::bot::{
+		Command, LINK_TTL_MINUTES, Notifier, Update, WEBHOOK_SECRET_HEADER, parse_command,
+		verify_webhook_secret,
+	},
+};
+
+/// Ссылка на бота с одноразовым кодом. Нажатие Start в боте привязывает чат
+/// к аккаунту и включает уведомления
+pub(crate) async fn create_telegram_link(
+	State(state): State<Arc<AppState>>,
+	Extension(user_id): Extension<Uuid>,
+) -> AppResult {
+	let username = state
+		.telegram
+		.username()
+		.await
+		.map_err(|err| AppError::scenario_error("Бот Telegram недоступен", Some(err)))?;
+
+	let code = hex::encode(rand::rng().random::<[u8; 16]>());
+	let expires_at = Utc::now() + Duration::minutes(LINK_TTL_MINUTES);
+
+	state
+		.repo
+		.set_telegram_link_code(user_id, &code, expires_at)
+		.await?;
+
+	let payload = json!({
+		"link": format!("https://t.me/{username}?start={code}"),
+		"expires_at": expires_at,
+	});
+
+	Ok(AppResponse::scenario_success(
+		"Ссылка на бота создана",
+		Some(payload),
+	))
+}
+
+pub(crate) async fn set_telegram_notifications(
+	State(state): State<Arc<AppState>>,
+	Extension(user_id): Extension<Uuid>,
+	Dto(body): Dto<TelegramNotificationsDto>,
+) -> AppResult {
+	let updated = state
+		.repo
+		.set_telegram_notifications(user_id, body.enabled)
+		.await?;
+
+	if !updated {
+		return Ok(AppResponse::scenario_fail(
+			"Сначала подключите бота Telegram",
+			None,
+		));
+	}
+
+	return Ok(AppResponse::scenario_success(
+		"Настройки уведомлений в Telegram обновлены",
+		None,
+	));
+}
+
+/// Обновления бота. Telegram повторяет запрос, пока не получит 200,
+/// поэтому ошибки обработки только пишутся в лог
+pub(crate) async fn telegram_webhook(
+	State(state): State<Arc<AppState>>,
+	headers: HeaderMap,
+	Json(update): Json<Update>,
+) -> StatusCode {
+	let secret = headers
+		.get(WEBHOOK_SECRET_HEADER)
+		.and_then(|value| value.to_str().ok());
+
+	if !verify_webhook_secret(secret) {
+		return StatusCode::UNAUTHORIZED;
+	}
+
+	let Some(message) = update.message else {
+		return StatusCode::OK;
+	};
+
+	// привязываются только личные чаты
+	if message.chat.kind != "private" {
+		return StatusCode::OK;
+	}
+
+	let chat_id = message.chat.id;
+
+	let reply = match message.text.as_deref().and_then(parse_command) {
+		Some(Command::Start(Some(code))) => {
+			match state.repo.link_telegram_chat(code, chat_id).await {
+				Ok(Some(_)) => {
+					"Уведомления о заявках и играх будут приходить сюда. Отключить их можно командой /stop или в профиле"
+				}
+				Ok(None) => "Ссылка устарела. Получите новую в профиле на сайте",
+				Err(err) => {
+					eprintln!("Не удалось привязать чат Telegram: {err}");
+					return StatusCode::OK;
+				}
+			}
+		}
+		Some(Command::Start(None)) => {
+			"Чтобы получать уведомления, откройте ссылку на бота из профиля на сайте"
+		}
+		Some(Command::Stop) => match state.repo.disable_telegram_chats(&[chat_id]).await {
+			Ok(_) => "Уведомления отключены. Включить их снова можно в профиле",
+			Err(err) => {
+				eprintln!("Не удалось отключить уведомления в Telegram: {err}");
+				return StatusCode::OK;
+			}
+		},
+		None => return StatusCode::OK,
+	};
+
+	if let Err(err) = state.telegram.send_message(chat_id, reply).await {
+		eprintln!("Не удалось ответить в Telegram: {err}");
+	}
+
+	StatusCode::OK
+}
@@ -0,0 +1,753 @@
+use ::std::{
+	collections::HashMap,
+	sync::{Arc, Mutex},
+	time::{Duration as StdDuration, Instant},
+};
+use chrono::{
+	DateTime, Datelike as _, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc, Weekday,
+};
+use sha2::{Digest as _, Sha256};
+
+use crate::{
+	recurrence::{Frequency, RecurrenceRule},
+	repository::models::CalendarEvent,
+	scheduling::DEFAULT_DURATION_HOURS,
+};
+
+pub(crate) const CONTENT_TYPE: &str = "text/calendar; charset=utf-8";
+/// Сколько публичная лента отдаётся из памяти, не обращаясь к базе
+pub(crate) const FEED_TTL: StdDuration = StdDuration::from_secs(300);
+/// Лента запрашивается по любому uuid, поэтому число лент в памяти ограничено
+const FEED_CACHE_CAPACITY: usize = 1024;
+
+const ICS_UTC_TEMPLATE: &str = "%Y%m%dT%H%M%SZ";
+const ICS_LOCAL_TEMPLATE: &str = "%Y%m%dT%H%M%S";
+const ICS_DATE_TEMPLATE: &str = "%Y%m%d";
+const ICS_DATE_LEN: usize = 8;
+// RFC 5545, 3.1: строки длиннее 75 октетов переносятся
+const MAX_LINE_OCTETS: usize = 75;
+
+pub(crate) fn render(name: &str, events: &[CalendarEvent], now: DateTime<Utc>) -> String {
+	let mut out = String::new();
+
+	push_line(&mut out, "BEGIN:VCALENDAR");
+	push_line(&mut out, "VERSION:2.0");
+	push_line(&mut out, "PRODID:-//nri_scheduler//RU");
+	push_line(&mut out, "CALSCALE:GREGORIAN");
+	push_line(&mut out, "METHOD:PUBLISH");
+	push_line(&mut out, &format!("X-WR-CALNAME:{}", escape(name)));
+
+	for event in events {
+		push_event(&mut out, event, now);
+	}
+
+	push_line(&mut out, "END:VCALENDAR");
+
+	out
+}
+
+fn push_event(out: &mut String, event: &CalendarEvent, now: DateTime<Utc>) {
+	let duration = event.plan_duration.unwrap_or(DEFAULT_DURATION_HOURS);
+	let end = event.date + Duration::hours(i64::from(duration));
+
+	push_line(out, "BEGIN:VEVENT");
+	push_line(out, &format!("UID:{}@nri_scheduler", event.id));
+	push_line(out, &format!("DTSTAMP:{}", now.format(ICS_UTC_TEMPLATE)));
+	push_line(
+		out,
+		&format!("DTSTART:{}", event.date.format(ICS_UTC_TEMPLATE)),
+	);
+	push_line(out, &format!("DTEND:{}", end.format(ICS_UTC_TEMPLATE)));
+	push_line(out, &format!("SUMMARY:{}", escape(&event.company)));
+
+	let location = match (&event.location, &event.address) {
+		(Some(name), Some(address)) => Some(format!("{name}, {address}")),
+		(Some(name), None) => Some(name.clone()),
+		(None, _) => event
+			.online_platform
+			.as_ref()
+			.map(|p| format!("Онлайн: {p}")),
+	};
+	if let Some(location) = location {
+		push_line(out, &format!("LOCATION:{}", escape(&location)));
+	}
+
+	let mut description = vec![format!("Мастер: {}", event.master)];
+	if let Some(ref descr) = event.description {
+		description.push(descr.clone());
+	}
+	if let Some(ref map_link) = event.map_link {
+		description.push(format!("Карта: {map_link}"));
+	}
+	if let Some(ref join_link) = event.join_link {
+		description.push(format!("Подключение: {join_link}"));
+	}
+	push_line(
+		out,
+		&format!("DESCRIPTION:{}", escape(&description.join("\n"))),
+	);
+
+	if let Some(url) = event.join_link.as_ref().or(event.map_link.as_ref()) {
+		push_line(out, &format!("URL:{url}"));
+	}
+
+	push_line(
+		out,
+		match event.cancelled {
+			true => "STATUS:CANCELLED",
+			false => "STATUS:CONFIRMED",
+		},
+	);
+	push_line(out, "END:VEVENT");
+}
+
+pub(crate) struct CachedFeed {
+	pub body: String,
+	pub etag: String,
+	pub last_modified: DateTime<Utc>,
+	fetched_at: Instant,
+}
+
+/// Кэш публичных лент. Ключ — область ленты, например `company:<uuid>`
+#[derive(Default)]
+pub(crate) struct FeedCache {
+	feeds: Mutex<HashMap<String, Arc<CachedFeed>>>,
+}
+
+impl FeedCache {
+	pub(crate) fn get(&self, key: &str) -> Option<Arc<CachedFeed>> {
+		let feeds = self.feeds.lock().ok()?;
+		feeds
+			.get(key)
+			.filter(|feed| feed.fetched_at.elapsed() < FEED_TTL)
+			.cloned()
+	}
+
+	/// Сохраняет свежую выборку. Если события не изменились, ETag и
+	/// Last-Modified остаются прежними, и клиент продолжит получать 304
+	pub(crate) fn put(&self, key: &str, name: &str, events: &[CalendarEvent]) -> Arc<CachedFeed> {
+		let now = Utc::now();
+		let etag = etag(name, events);
+
+		let Ok(mut feeds) = self.feeds.lock() else {
+			return Arc::new(CachedFeed {
+				body: render(name, events, now),
+				etag,
+				last_modified: now,
+				fetched_at: Instant::now(),
+			});
+		};
+
+		feeds.retain(|_, feed| feed.fetched_at.elapsed() < FEED_TTL);
+
+		if feeds.len() >= FEED_CACHE_CAPACITY
+			&& !feeds.contains_key(key)
+			&& let Some(oldest) = feeds
+				.iter()
+				.min_by_key(|(_, feed)| feed.fetched_at)
+				.map(|(key, _)| key.clone())
+		{
+			feeds.remove(&oldest);
+		}
+
+		let feed = match feeds.get(key) {
+			Some(previous) if previous.etag == etag => CachedFeed {
+				body: previous.body.clone(),
+				etag,
+				last_modified: previous.last_modified,
+				fetched_at: Instant::now(),
+			},
+			_ => CachedFeed {
+				body: render(name, events, now),
+				etag,
+				last_modified: now,
+				fetched_at: Instant::now(),
+			},
+		};
+
+		let feed = Arc::new(feed);
+		feeds.insert(key.to_owned(), feed.clone());
+
+		feed
+	}
+}
+
+/// ETag считается по данным событий, а не по телу ответа: DTSTAMP в теле
+/// меняется при каждой отрисовке
+fn etag(name: &str, events: &[CalendarEvent]) -> String {
+	let mut hasher = Sha256::new();
+	hasher.update(name.as_bytes());
+	hasher.update(serde_json::to_vec(events).unwrap_or_default());
+
+	format!("\"{}\"", hex::encode(&hasher.finalize()[..16]))
+}
+
+/// Экранирование значения типа TEXT (RFC 5545, 3.3.11)
+fn escape(value: &str) -> String {
+	let mut escaped = String::with_capacity(value.len());
+
+	for ch in value.chars() {
+		match ch {
+			'\\' => escaped.push_str("\\\\"),
+			';' => escaped.push_str("\\;"),
+			',' => escaped.push_str("\\,"),
+			'\n' => escaped.push_str("\\n"),
+			'\r' => {}
+			_ => escaped.push(ch),
+		}
+	}
+
+	escaped
+}
+
+/// Дописывает строку с переносом по 75 октетов, не разрывая символы UTF-8
+fn push_line(out: &mut String, line: &str) {
+	let mut octets = 0;
+
+	for ch in line.chars() {
+		if octets + ch.len_utf8() > MAX_LINE_OCTETS {
+			out.push_str("\r\n ");
+			// пробел в начале строки продолжения тоже считается
+			octets = 1;
+		}
+		out.push(ch);
+		octets += ch.len_utf8();
+	}
+
+	out.push_str("\r\n");
+}
+
+/// Время из файла календаря в том виде, в каком оно записано
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum IcsTime {
+	Utc(DateTime<Utc>),
+	/// местное время с `TZID`
+	Zoned(NaiveDateTime, String),
+	/// местное время без пояса
+	Floating(NaiveDateTime),
+	/// событие на весь день
+	AllDay(NaiveDate),
+}
+
+impl IcsTime {
+	fn parse(value: &str, timezone: Option<&str>) -> Option<Self> {
+		if let Some(utc) = value.strip_suffix('Z') {
+			return NaiveDateTime::parse_from_str(utc, ICS_LOCAL_TEMPLATE)
+				.ok()
+				.map(|date| Self::Utc(date.and_utc()));
+		}
+
+		if value.len() == ICS_DATE_LEN {
+			return NaiveDate::parse_from_str(value, ICS_DATE_TEMPLATE)
+				.ok()
+				.map(Self::AllDay);
+		}
+
+		let local = NaiveDateTime::parse_from_str(value, ICS_LOCAL_TEMPLATE).ok()?;
+
+		Some(match timezone {
+			Some(timezone) => Self::Zoned(local, timezone.to_owned()),
+			None => Self::Floating(local),
+		})
+	}
+
+	pub(crate) const fn naive(&self) -> NaiveDateTime {
+		match self {
+			Self::Utc(date) => date.naive_utc(),
+			Self::Zoned(local, _) | Self::Floating(local) => *local,
+			Self::AllDay(date) => date.and_time(NaiveTime::MIN),
+		}
+	}
+
+	/// Продолжительность до `end`, если оба значения заданы в одной системе отсчёта
+	fn until(&self, end: &Self) -> Option<Duration> {
+		match (self, end) {
+			(Self::Utc(start), Self::Utc(end)) => Some(*end - *start),
+			(Self::Zoned(start, start_tz), Self::Zoned(end, end_tz)) if start_tz == end_tz => {
+				Some(*end - *start)
+			}
+			(Self::Floating(start), Self::Floating(end)) => Some(*end - *start),
+			_ => None,
+		}
+	}
+}
+
+#[derive(Debug)]
+pub(crate) struct ImportedEvent {
+	pub uid: Option<String>,
+	pub summary: Option<String>,
+	pub location: Option<String>,
+	pub start: IcsTime,
+	pub duration: Option<Duration>,
+	/// `Err` - правило есть, но его нельзя выразить через серии событий
+	pub rule: Option<Result<RecurrenceRule, String>>,
+	pub exceptions: Vec<NaiveDate>,
+	pub cancelled: bool,
+}
+
+/// VEVENT, из которого не получилось собрать событие
+#[derive(Debug)]
+pub(crate) struct InvalidEvent {
+	pub uid: Option<String>,
+	pub summary: Option<String>,
+	pub reason: String,
+}
+
+#[derive(Default)]
+struct EventBuilder {
+	uid: Option<String>,
+	summary: Option<String>,
+	location: Option<String>,
+	start: Option<IcsTime>,
+	end: Option<IcsTime>,
+	duration: Option<Duration>,
+	rule: Option<String>,
+	exceptions: Vec<NaiveDate>,
+	cancelled: bool,
+	/// первая ошибка в свойствах события
+	error: Option<String>,
+}
+
+impl EventBuilder {
+	fn set(&mut self, name: &str, params: &[(String, String)], value: &str) -> Result<(), String> {
+		let timezone = params
+			.iter()
+			.find(|(key, _)| key == "TZID")
+			.map(|(_, tz)| tz.as_str());
+
+		match name {
+			"UID" => self.uid = Some(unescape(value)),
+			"SUMMARY" => self.summary = Some(unescape(value)),
+			"LOCATION" => self.location = Some(unescape(value)),
+			"DTSTART" => {
+				let start = IcsTime::parse(value, timezone)
+					.ok_or_else(|| format!("Некорректная дата начала: {value}"))?;
+				self.start = Some(start);
+			}
+			"DTEND" => self.end = IcsTime::parse(value, timezone),
+			"DURATION" => self.duration = parse_duration(value),
+			"RRULE" => self.rule = Some(value.to_owned()),
+			"EXDATE" => self.exceptions.extend(
+				value
+					.split(',')
+					.filter_map(|date| IcsTime::parse(date, timezone))
+					.map(|date| date.naive().date()),
+			),
+			"STATUS" => self.cancelled = value.eq_ignore_ascii_case("CANCELLED"),
+			_ => {}
+		}
+
+		Ok(())
+	}
+
+	fn build(self) -> Result<ImportedEvent, InvalidEvent> {
+		let start = match (self.error, self.start) {
+			(None, Some(start)) => start,
+			(error, _) => {
+				return Err(InvalidEvent {
+					uid: self.uid,
+					summary: self.summary,
+					reason: error.unwrap_or_else(|| String::from("У события нет даты начала")),
+				});
+			}
+		};
+
+		let duration = self
+			.duration
+			.or_else(|| self.end.and_then(|end| start.until(&end)));
+		let rule = self
+			.rule
+			.map(|rule| parse_rule(&rule, start.naive().date()));
+
+		Ok(ImportedEvent {
+			uid: self.uid,
+			summary: self.summary,
+			location: self.location,
+			start,
+			duration,
+			rule,
+			exceptions: self.exceptions,
+			cancelled: self.cancelled,
+		})
+	}
+}
+
+/// Разбирает VEVENT из файла iCalendar. Вложенные компоненты (VALARM)
+/// пропускаются, часовые пояса VTIMEZONE берутся по имени из `TZID`.
+/// Ошибка в одном событии не мешает разобрать остальные
+pub(crate) fn parse(input: &str) -> Result<Vec<Result<ImportedEvent, InvalidEvent>>, String> {
+	let mut lines: Vec<String> = Vec::new();
+
+	for raw in input.lines() {
+		if let Some(continuation) = raw.strip_prefix([' ', '\t'])
+			&& let Some(last) = lines.last_mut()
+		{
+			last.push_str(continuation);
+		} else {
+			lines.push(raw.to_owned());
+		}
+	}
+
+	if !lines.iter().any(|line| {
+		line
+			.trim_start_matches('\u{feff}')
+			.eq_ignore_ascii_case("BEGIN:VCALENDAR")
+	}) {
+		return Err(String::from("Файл не является календарём iCalendar"));
+	}
+
+	let mut events = Vec::new();
+	let mut current: Option<EventBuilder> = None;
+	let mut nested = 0_usize;
+
+	for line in &lines {
+		let Some((name, params, value)) = split_property(line) else {
+			continue;
+		};
+
+		match (name.as_str(), &mut current) {
+			("BEGIN", None) if value.eq_ignore_ascii_case("VEVENT") => {
+				current = Some(EventBuilder::default());
+			}
+			("BEGIN", Some(_)) => nested += 1,
+			("END", Some(_)) if nested > 0 => nested -= 1,
+			("END", Some(_)) if value.eq_ignore_ascii_case("VEVENT") => {
+				if let Some(event) = current.take() {
+					events.push(event.build());
+				}
+			}
+			(_, Some(event)) if nested == 0 => {
+				if let Err(err) = event.set(&name, &params, value) {
+					event.error.get_or_insert(err);
+				}
+			}
+			_ => {}
+		}
+	}
+
+	Ok(events)
+}
+
+type Property<'a> = (String, Vec<(String, String)>, &'a str);
+
+fn split_property(line: &str) -> Option<Property<'_>> {
+	let mut in_quotes = false;
+	let (colon, _) = line.char_indices().find(|&(_, ch)| {
+		if ch == '"' {
+			in_quotes = !in_quotes;
+		}
+		ch == ':' && !in_quotes
+	})?;
+
+	let mut head = line[..colon].split(';');
+	let name = head.next()?.trim().to_ascii_uppercase();
+	let params = head
+		.filter_map(|param| param.split_once('='))
+		.map(|(key, value)| (key.to_ascii_uppercase(), value.trim_matches('"').to_owned()))
+		.collect();
+
+	Some((name, params, &line[colon + 1..]))
+}
+
+fn unescape(value: &str) -> String {
+	let mut unescaped = String::with_capacity(value.len());
+	let mut chars = value.chars();
+
+	while let Some(ch) = chars.next() {
+		if ch != '\\' {
+			unescaped.push(ch);
+			continue;
+		}
+
+		match chars.next() {
+			Some('n' | 'N') => unescaped.push('\n'),
+			Some(escaped) => unescaped.push(escaped),
+			None => {}
+		}
+	}
+
+	unescaped
+}
+
+/// Продолжительность вида `PT3H30M` или `P1D`
+fn parse_duration(value: &str) -> Option<Duration> {
+	let value = value.strip_prefix('+').unwrap_or(value).strip_prefix('P')?;
+	let mut total = Duration::zero();
+	let mut number = String::new();
+
+	for ch in value.chars() {
+		match ch {
+			'0'..='9' => number.push(ch),
+			'T' => {}
+			unit => {
+				let n = number.parse::<i64>().ok()?;
+				number.clear();
+				total += match unit {
+					'W' => Duration::weeks(n),
+					'D' => Duration::days(n),
+					'H' => Duration::hours(n),
+					'M' => Duration::minutes(n),
+					'S' => Duration::seconds(n),
+					_ => return None,
+				};
+			}
+		}
+	}
+
+	Some(total)
+}
+
+/// Переводит RRULE в правило серии. Поддерживаются только те правила,
+/// которые умеют серии: еженедельно, раз в две недели и N-й день недели месяца
+fn parse_rule(value: &str, start: NaiveDate) -> Result<RecurrenceRule, String> {
+	let unsupported = || format!("Неподдерживаемое правило повторения: {value}");
+
+	let parts: HashMap<String, &str> = value
+		.split(';')
+		.filter_map(|part| part.split_once('='))
+		.map(|(key, value)| (key.to_ascii_uppercase(), value))
+		.collect();
+
+	let interval = match parts.get("INTERVAL") {
+		Some(interval) => interval.parse::<u32>().map_err(|_| unsupported())?,
+		None => 1,
+	};
+	let by_day = parts.get("BYDAY").copied();
+
+	let frequency = match (parts.get("FREQ").copied(), interval) {
+		(Some("WEEKLY"), 1 | 2)
+			if by_day.is_none_or(|day| parse_weekday(day) == Some(start.weekday())) =>
+		{
+			match interval {
+				1 => Frequency::Weekly,
+				_ => Frequency::Biweekly,
+			}
+		}
+		(Some("MONTHLY"), 1)
+			if by_day.is_some_and(|day| {
+				is_start_weekday_of_month(day, parts.get("BYSETPOS").copied(), start)
+			}) =>
+		{
+			Frequency::MonthlyWeekday
+		}
+		_ => return Err(unsupported()),
+	};
+
+	let count = match parts.get("COUNT") {
+		Some(count) => Some(count.parse::<u16>().map_err(|_| unsupported())?),
+		None => None,
+	};
+
+	let until = match parts.get("UNTIL") {
+		Some(until) => Some(
+			until
+				.get(..ICS_DATE_LEN)
+				.and_then(|date| NaiveDate::parse_from_str(date, ICS_DATE_TEMPLATE).ok())
+				.ok_or_else(unsupported)?,
+		),
+		None => None,
+	};
+
+	Ok(RecurrenceRule {
+		frequency,
+		until,
+		count,
+	})
+}
+
+// BYDAY=2SA или BYDAY=SA;BYSETPOS=2 должны совпадать с датой начала
+fn is_start_weekday_of_month(by_day: &str, set_pos: Option<&str>, start: NaiveDate) -> bool {
+	let Some((position, day)) = by_day
+		.len()
+		.checked_sub(2)
+		.and_then(|split| Some((by_day.get(..split)?, by_day.get(split..)?)))
+	else {
+		return false;
+	};
+
+	let position = match (position, set_pos) {
+		("", Some(set_pos)) => set_pos,
+		(position, None) => position,
+		_ => return false,
+	};
+
+	let week = (start.day() - 1) / 7 + 1;
+
+	parse_weekday(day) == Some(start.weekday())
+		&& position.trim_start_matches('+').parse::<u32>() == Ok(week)
+}
+
+fn parse_weekday(code: &str) -> Option<Weekday> {
+	match code.to_ascii_uppercase().as_str() {
+		"MO" => Some(Weekday::Mon),
+		"TU" => Some(Weekday::Tue),
+		"WE" => Some(Weekday::Wed),
+		"TH" => Some(Weekday::Thu),
+		"FR" => Some(Weekday::Fri),
+		"SA" => Some(Weekday::Sat),
+		"SU" => Some(Weekday::Sun),
+		_ => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use uuid::Uuid;
+
+	use super::*;
+
+	fn event(cancelled: bool, plan_duration: Option<i16>) -> CalendarEvent {
+		CalendarEvent {
+			id: Uuid::from_u128(1),
+			company: String::from("Проклятие Страда"),
+			description: None,
+			date: DateTime::parse_from_rfc3339("2026-12-01T15:00:00Z")
+				.unwrap()
+				.to_utc(),
+			plan_duration,
+			cancelled,
+			location: Some(String::from("Антикафе")),
+			address: Some(String::from("ул. Ленина, 1")),
+			map_link: None,
+			online_platform: None,
+			join_link: None,
+			master: String::from("master"),
+		}
+	}
+
+	#[test]
+	fn test_escape_text() {
+		assert_eq!(escape("a,b;c\\d\r\ne"), "a\\,b\\;c\\\\d\\ne");
+	}
+
+	#[test]
+	fn test_long_lines_are_folded_by_octets() {
+		let mut out = String::new();
+		let line = format!("SUMMARY:{}", "Ж".repeat(100));
+		push_line(&mut out, &line);
+
+		assert!(
+			out.split("\r\n")
+				.all(|folded| folded.len() <= MAX_LINE_OCTETS)
+		);
+		assert_eq!(out.replace("\r\n ", "").trim_end(), line);
+	}
+
+	#[test]
+	fn test_cached_feed_keeps_etag_for_same_events() {
+		let cache = FeedCache::default();
+		let first = cache.put("company:1", "Игры", &[event(false, None)]);
+		let same = cache.put("company:1", "Игры", &[event(false, None)]);
+		let changed = cache.put("company:1", "Игры", &[event(true, None)]);
+
+		assert_eq!(first.etag, same.etag);
+		assert_eq!(first.last_modified, same.last_modified);
+		assert_ne!(first.etag, changed.etag);
+		assert_eq!(cache.get("company:1").unwrap().etag, changed.etag);
+	}
+
+	#[test]
+	fn test_feed_cache_evicts_oldest_when_full() {
+		let cache = FeedCache::default();
+
+		for n in 0..=FEED_CACHE_CAPACITY {
+			cache.put(&format!("company:{n}"), "Игры", &[]);
+		}
+
+		assert!(cache.get("company:0").is_none());
+		assert!(
+			cache
+				.get(&format!("company:{FEED_CACHE_CAPACITY}"))
+				.is_some()
+		);
+		assert_eq!(cache.feeds.lock().unwrap().len(), FEED_CACHE_CAPACITY);
+	}
+
+	#[test]
+	fn test_parse_weekly_event_with_timezone() {
+		let ics = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nUID:abc@example\r\n\
+			SUMMARY:Проклятие\\, Страда\r\nDTSTART;TZID=Europe/Moscow:20261105T190000\r\n\
+			DTEND;TZID=Europe/Moscow:20261105T223000\r\nRRULE:FREQ=WEEKLY;COUNT=\r\n 4\r\n\
+			EXDATE;TZID=Europe/Moscow:20261112T190000\r\nBEGIN:VALARM\r\n\
+			DESCRIPTION:Скоро игра\r\nEND:VALARM\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+
+		let events = parse(ics).unwrap();
+		let event = events[0].as_ref().unwrap();
+		let rule = event.rule.as_ref().unwrap().as_ref().unwrap();
+
+		assert_eq!(events.len(), 1);
+		assert_eq!(event.summary.as_deref(), Some("Проклятие, Страда"));
+		assert_eq!(
+			event.start,
+			IcsTime::Zoned(
+				NaiveDateTime::parse_from_str("2026-11-05 19:00", "%Y-%m-%d %H:%M").unwrap(),
+				String::from("Europe/Moscow")
+			)
+		);
+		assert_eq!(event.duration, Some(Duration::minutes(210)));
+		assert_eq!(rule.frequency, Frequency::Weekly);
+		assert_eq!(rule.count, Some(4));
+		assert_eq!(
+			event.exceptions,
+			vec![NaiveDate::from_ymd_opt(2026, 11, 12).unwrap()]
+		);
+	}
+
+	#[test]
+	fn test_parse_rules() {
+		// 2026-11-14 - вторая суббота месяца
+		let start = NaiveDate::from_ymd_opt(2026, 11, 14).unwrap();
+
+		let monthly = parse_rule("FREQ=MONTHLY;BYDAY=2SA;UNTIL=20270301T000000Z", start).unwrap();
+		assert_eq!(monthly.frequency, Frequency::MonthlyWeekday);
+		assert_eq!(monthly.until, NaiveDate::from_ymd_opt(2027, 3, 1));
+
+		let biweekly = parse_rule("FREQ=WEEKLY;INTERVAL=2;BYDAY=SA", start).unwrap();
+		assert_eq!(biweekly.frequency, Frequency::Biweekly);
+
+		assert!(parse_rule("FREQ=MONTHLY;BYDAY=-1SA", start).is_err());
+		assert!(parse_rule("FREQ=WEEKLY;BYDAY=MO,TH", start).is_err());
+		assert!(parse_rule("FREQ=DAILY", start).is_err());
+	}
+
+	#[test]
+	fn test_parse_rejects_non_calendar() {
+		assert!(parse("hello").is_err());
+	}
+
+	#[test]
+	fn test_parse_keeps_events_after_invalid_one() {
+		let ics = "BEGIN:VCALENDAR\nBEGIN:VEVENT\nSUMMARY:x\nEND:VEVENT\n\
+			BEGIN:VEVENT\nSUMMARY:y\nDTSTART:2026-11-05\nEND:VEVENT\n\
+			BEGIN:VEVENT\nSUMMARY:z\nDTSTART:20261105T190000Z\nEND:VEVENT\nEND:VCALENDAR";
+
+		let events = parse(ics).unwrap();
+
+		assert_eq!(events.len(), 3);
+		let missing = events[0].as_ref().unwrap_err();
+		assert_eq!(missing.summary.as_deref(), Some("x"));
+		assert_eq!(missing.reason, "У события нет даты начала");
+		assert!(
+			events[1]
+				.as_ref()
+				.unwrap_err()
+				.reason
+				.contains("2026-11-05")
+		);
+		assert_eq!(events[2].as_ref().unwrap().summary.as_deref(), Some("z"));
+	}
+
+	#[test]
+	fn test_event_times_and_status() {
+		let now = DateTime::parse_from_rfc3339("2026-10-19T00:00:00Z")
+			.unwrap()
+			.to_utc();
+		let ics = render("Игры", &[event(true, Some(3)), event(false, None)], now);
+
+		assert!(ics.contains("DTSTART:20261201T150000Z\r\nDTEND:20261201T180000Z\r\n"));
+		assert!(ics.contains("DTEND:20261201T190000Z\r\n"));
+		assert!(ics.contains("STATUS:CANCELLED\r\n"));
+		assert!(ics.contains("LOCATION:Антикафе\\, ул. Ленина\\, 1\r\n"));
+		assert!(ics.contains("UID:00000000-0000-0000-0000-000000000001@nri_scheduler\r\n"));
+	}
+}
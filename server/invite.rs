@@ -0,0 +1,122 @@
+use ::std::sync::LazyLock;
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use uuid::Uuid;
+
+use crate::auth::derive_secret;
+
+pub(crate) const DEFAULT_TTL_HOURS: u16 = 72;
+
+static INVITE_SECRET_KEY: LazyLock<[u8; 32]> = LazyLock::new(|| derive_secret("event-invite"));
+
+pub(super) fn init_static() {
+	let _ = *INVITE_SECRET_KEY;
+	println!("+ an invite secret key is ok");
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct InviteLink {
+	pub token: String,
+	pub link: String,
+	pub expires_at: DateTime<Utc>,
+}
+
+/// Приглашение на событие, привязанное к конкретному пользователю.
+/// Токен имеет вид `<unix-время истечения>.<hex подписи>`
+pub(crate) fn create(event_id: Uuid, user_id: Uuid, ttl_hours: u16) -> InviteLink {
+	let expires_at = Utc::now() + Duration::hours(i64::from(ttl_hours));
+	let token = sign(
+		&*INVITE_SECRET_KEY,
+		event_id,
+		user_id,
+		expires_at.timestamp(),
+	);
+
+	InviteLink {
+		link: format!("/event/{event_id}?invite={token}"),
+		token,
+		expires_at,
+	}
+}
+
+pub(crate) fn verify(token: &str, event_id: Uuid, user_id: Uuid) -> bool {
+	check(
+		&*INVITE_SECRET_KEY,
+		token,
+		event_id,
+		user_id,
+		Utc::now().timestamp(),
+	)
+}
+
+fn mac(key: &[u8], event_id: Uuid, user_id: Uuid, expires_at: i64) -> Option<Hmac<Sha256>> {
+	let mut mac = Hmac::<Sha256>::new_from_slice(key).ok()?;
+	mac.update(event_id.as_bytes());
+	mac.update(user_id.as_bytes());
+	mac.update(&expires_at.to_be_bytes());
+	Some(mac)
+}
+
+fn sign(key: &[u8], event_id: Uuid, user_id: Uuid, expires_at: i64) -> String {
+	let signature = mac(key, event_id, user_id, expires_at)
+		.map(|mac| hex::encode(mac.finalize().into_bytes()))
+		.unwrap_or_default();
+
+	format!("{expires_at}.{signature}")
+}
+
+fn check(key: &[u8], token: &str, event_id: Uuid, user_id: Uuid, now: i64) -> bool {
+	let Some((expires_at, signature)) = token.split_once('.') else {
+		return false;
+	};
+
+	let Ok(expires_at) = expires_at.parse::<i64>() else {
+		return false;
+	};
+
+	if expires_at <= now {
+		return false;
+	}
+
+	let Ok(signature) = hex::decode(signature) else {
+		return false;
+	};
+
+	mac(key, event_id, user_id, expires_at).is_some_and(|mac| mac.verify_slice(&signature).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const KEY: &[u8] = b"test-key";
+
+	#[test]
+	fn test_token_is_bound_to_event_and_user() {
+		let (event, user, other) = (Uuid::from_u128(1), Uuid::from_u128(2), Uuid::from_u128(3));
+		let token = sign(KEY, event, user, 2_000);
+
+		assert!(check(KEY, &token, event, user, 1_000));
+		assert!(!check(KEY, &token, event, other, 1_000));
+		assert!(!check(KEY, &token, other, user, 1_000));
+		assert!(!check(b"another-key", &token, event, user, 1_000));
+	}
+
+	#[test]
+	fn test_expired_or_tampered_token_is_rejected() {
+		let (event, user) = (Uuid::from_u128(1), Uuid::from_u128(2));
+		let token = sign(KEY, event, user, 2_000);
+
+		assert!(!check(KEY, &token, event, user, 2_000));
+		assert!(!check(
+			KEY,
+			&token.replacen("2000", "9000", 1),
+			event,
+			user,
+			1_000
+		));
+		assert!(!check(KEY, "garbage", event, user, 1_000));
+	}
+}
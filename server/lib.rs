@@ -5,14 +5,22 @@ pub(crate) mod cookie;
 pub(crate) mod cors;
 pub(crate) mod dto;
 pub(crate) mod email;
+pub(crate) mod event_log;
+pub(crate) mod geo;
 pub mod graceful_shutdown;
 pub(crate) mod handlers;
+pub(crate) mod ical;
 pub(crate) mod image;
+pub(crate) mod invite;
+pub(crate) mod notification;
 // pub(crate) mod log;
+pub(crate) mod recurrence;
 pub mod repository;
 pub mod router;
+pub(crate) mod scheduling;
 pub(crate) mod shared;
 pub mod state;
+pub(crate) mod subscribers;
 pub mod system_models;
 pub(crate) mod telegram;
 #[cfg(feature = "vite")]
@@ -0,0 +1,290 @@
+use chrono::{DateTime, FixedOffset, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+// клиент находит дату в тексте по этому формату и переводит её в часовой пояс пользователя
+const FULL_UTC_TEMPLATE: &str = "%Y-%m-%dT%H:%M:%SZ";
+const LOCAL_TEMPLATE: &str = "%d.%m.%Y %H:%M";
+
+/// Уведомление пользователю. Тег `type` совпадает с именем события SSE
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum NotificationPayload {
+	/// Игрок записался на игру - уведомление мастеру
+	ApplicationCreated {
+		app_id: Uuid,
+		event_id: Uuid,
+		company_name: String,
+		date: DateTime<Utc>,
+		player_id: Uuid,
+	},
+	ApplicationApproved {
+		app_id: Uuid,
+		event_id: Uuid,
+		company_name: String,
+		date: DateTime<Utc>,
+	},
+	ApplicationRejected {
+		app_id: Uuid,
+		event_id: Uuid,
+		company_name: String,
+		date: DateTime<Utc>,
+	},
+	/// Личное приглашение на игру со ссылкой, подписанной для получателя
+	EventInvite {
+		event_id: Uuid,
+		company_name: String,
+		link: String,
+	},
+	/// Игра назначена по итогам опроса о дате
+	EventScheduled {
+		poll_id: Uuid,
+		event_id: Uuid,
+		company_name: String,
+		date: DateTime<Utc>,
+	},
+	EventRescheduled {
+		event_id: Uuid,
+		company_name: String,
+		previous_date: DateTime<Utc>,
+		date: DateTime<Utc>,
+	},
+	EventCancelled {
+		event_id: Uuid,
+		company_name: String,
+		date: DateTime<Utc>,
+	},
+	MemberInvited {
+		company_id: Uuid,
+		company_name: String,
+	},
+	MemberRemoved {
+		company_id: Uuid,
+		company_name: String,
+	},
+	TransferProposed {
+		company_id: Uuid,
+		company_name: String,
+		master_name: String,
+	},
+	TransferAccepted {
+		company_id: Uuid,
+		company_name: String,
+	},
+	TransferCancelled {
+		company_id: Uuid,
+		company_name: String,
+	},
+}
+
+impl NotificationPayload {
+	pub(crate) const fn event_name(&self) -> &'static str {
+		match self {
+			Self::ApplicationCreated { .. } => "application_created",
+			Self::ApplicationApproved { .. } => "application_approved",
+			Self::ApplicationRejected { .. } => "application_rejected",
+			Self::EventInvite { .. } => "event_invite",
+			Self::EventScheduled { .. } => "event_scheduled",
+			Self::EventRescheduled { .. } => "event_rescheduled",
+			Self::EventCancelled { .. } => "event_cancelled",
+			Self::MemberInvited { .. } => "member_invited",
+			Self::MemberRemoved { .. } => "member_removed",
+			Self::TransferProposed { .. } => "transfer_proposed",
+			Self::TransferAccepted { .. } => "transfer_accepted",
+			Self::TransferCancelled { .. } => "transfer_cancelled",
+		}
+	}
+
+	/// Уведомления, которые дублируются в Telegram
+	pub(crate) const fn is_for_telegram(&self) -> bool {
+		matches!(
+			self,
+			Self::ApplicationCreated { .. }
+				| Self::ApplicationApproved { .. }
+				| Self::ApplicationRejected { .. }
+				| Self::EventRescheduled { .. }
+				| Self::EventCancelled { .. }
+		)
+	}
+
+	/// Дата игры в тексте уведомления
+	pub(crate) const fn date(&self) -> Option<DateTime<Utc>> {
+		match self {
+			Self::ApplicationCreated { date, .. }
+			| Self::ApplicationApproved { date, .. }
+			| Self::ApplicationRejected { date, .. }
+			| Self::EventScheduled { date, .. }
+			| Self::EventRescheduled { date, .. }
+			| Self::EventCancelled { date, .. } => Some(*date),
+			_ => None,
+		}
+	}
+
+	/// Готовый текст для входящих и для клиентов, не знающих нового типа
+	pub(crate) fn message(&self) -> String {
+		self.render(|date| date.format(FULL_UTC_TEMPLATE).to_string())
+	}
+
+	/// Текст с датой в часовом поясе получателя - для мессенджеров, где её
+	/// некому перевести. `utc_offset` - смещение пояса на саму дату, с учётом
+	/// перевода часов. Если пояс неизвестен, дата остаётся в UTC
+	pub(crate) fn local_message(&self, utc_offset: Option<FixedOffset>) -> String {
+		self.render(|date| match utc_offset {
+			Some(offset) => date
+				.with_timezone(&offset)
+				.format(LOCAL_TEMPLATE)
+				.to_string(),
+			None => format!("{} UTC", date.format(LOCAL_TEMPLATE)),
+		})
+	}
+
+	fn render(&self, format_date: impl Fn(&DateTime<Utc>) -> String) -> String {
+		match self {
+			Self::ApplicationCreated {
+				company_name, date, ..
+			} => format!(
+				r#"На игру по кампании "{company_name}" на {} записался игрок"#,
+				format_date(date)
+			),
+			Self::ApplicationApproved {
+				company_name, date, ..
+			} => format!(
+				r#"Ваша заявка на игру по кампании "{company_name}" на {} одобрена"#,
+				format_date(date)
+			),
+			Self::ApplicationRejected {
+				company_name, date, ..
+			} => format!(
+				r#"Ваша заявка на игру по кампании "{company_name}" на {} отклонена"#,
+				format_date(date)
+			),
+			Self::EventInvite {
+				company_name, link, ..
+			} => format!(r#"Вас пригласили на игру по кампании "{company_name}": {link}"#),
+			Self::EventScheduled {
+				company_name, date, ..
+			} => format!(
+				r#"Игра по кампании "{company_name}" назначена на {}"#,
+				format_date(date)
+			),
+			Self::EventRescheduled {
+				company_name, date, ..
+			} => format!(
+				r#"Игра по кампании "{company_name}" перенесена на {}"#,
+				format_date(date)
+			),
+			Self::EventCancelled {
+				company_name, date, ..
+			} => format!(
+				r#"Игра по кампании "{company_name}" на {} отменена"#,
+				format_date(date)
+			),
+			Self::MemberInvited { company_name, .. } => {
+				format!(r#"Вас пригласили в кампанию "{company_name}""#)
+			}
+			Self::MemberRemoved { company_name, .. } => {
+				format!(r#"Вы исключены из кампании "{company_name}""#)
+			}
+			Self::TransferProposed {
+				company_name,
+				master_name,
+				..
+			} => format!(
+				r#"Мастер {master_name} предлагает вам стать мастером кампании "{company_name}""#
+			),
+			Self::TransferAccepted { company_name, .. } => {
+				format!(r#"Кампания "{company_name}" передана новому мастеру"#)
+			}
+			Self::TransferCancelled { company_name, .. } => {
+				format!(r#"Передача кампании "{company_name}" отменена"#)
+			}
+		}
+	}
+}
+
+/// Данные события SSE. `id` - запись во входящих, по нему уведомление отмечается
+/// прочитанным; отсутствует, если сохранить уведомление не удалось
+#[derive(Debug, Serialize, ToSchema)]
+pub(crate) struct NotificationEvent<'a> {
+	pub id: Option<Uuid>,
+	pub message: &'a str,
+	#[serde(flatten)]
+	pub payload: &'a NotificationPayload,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_event_name_matches_serde_tag() {
+		let company_id = Uuid::from_u128(1);
+		let company_name = String::from("Проклятие Страда");
+
+		let payloads = [
+			NotificationPayload::MemberInvited {
+				company_id,
+				company_name: company_name.clone(),
+			},
+			NotificationPayload::TransferProposed {
+				company_id,
+				company_name: company_name.clone(),
+				master_name: String::from("Мастер"),
+			},
+			NotificationPayload::EventCancelled {
+				event_id: Uuid::from_u128(2),
+				company_name,
+				date: DateTime::default(),
+			},
+		];
+
+		for payload in payloads {
+			let json = serde_json::to_value(&payload).unwrap();
+			assert_eq!(json["type"], payload.event_name());
+		}
+	}
+
+	#[test]
+	fn test_sse_data_is_flat() {
+		let payload = NotificationPayload::MemberRemoved {
+			company_id: Uuid::from_u128(1),
+			company_name: String::from("C"),
+		};
+		let message = payload.message();
+		let event = NotificationEvent {
+			id: None,
+			message: &message,
+			payload: &payload,
+		};
+
+		let json = serde_json::to_value(&event).unwrap();
+
+		assert_eq!(json["type"], "member_removed");
+		assert_eq!(json["company_name"], "C");
+		assert_eq!(json["message"], r#"Вы исключены из кампании "C""#);
+		assert!(json["id"].is_null());
+	}
+
+	#[test]
+	fn test_local_message_uses_recipient_timezone() {
+		let payload = NotificationPayload::EventCancelled {
+			event_id: Uuid::from_u128(1),
+			company_name: String::from("C"),
+			date: "2026-10-19T17:30:00Z".parse().unwrap(),
+		};
+
+		assert_eq!(
+			payload.local_message(FixedOffset::east_opt(3 * 3600)),
+			r#"Игра по кампании "C" на 19.10.2026 20:30 отменена"#
+		);
+		assert_eq!(
+			payload.local_message(None),
+			r#"Игра по кампании "C" на 19.10.2026 17:30 UTC отменена"#
+		);
+		assert_eq!(
+			payload.message(),
+			r#"Игра по кампании "C" на 2026-10-19T17:30:00Z отменена"#
+		);
+	}
+}
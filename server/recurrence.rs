@@ -0,0 +1,204 @@
+use chrono::{Datelike as _, Days, Months, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+// ограничение на количество событий, создаваемых одним правилом
+pub(crate) const MAX_OCCURRENCES: usize = 104;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Frequency {
+	Weekly,
+	Biweekly,
+	MonthlyWeekday,
+}
+
+impl Frequency {
+	pub const fn as_str(self) -> &'static str {
+		match self {
+			Frequency::Weekly => "weekly",
+			Frequency::Biweekly => "biweekly",
+			Frequency::MonthlyWeekday => "monthly_weekday",
+		}
+	}
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct RecurrenceRule {
+	pub frequency: Frequency,
+	pub until: Option<NaiveDate>,
+	pub count: Option<u16>,
+}
+
+/// Разворачивает правило в список дат начала (по местному времени).
+/// `count` учитывает и даты-исключения, как `COUNT` + `EXDATE` в iCalendar
+pub(crate) fn expand(
+	start: NaiveDateTime,
+	rule: &RecurrenceRule,
+	exceptions: &[NaiveDate],
+) -> Vec<NaiveDateTime> {
+	let limit = rule
+		.count
+		.map_or(MAX_OCCURRENCES, |c| usize::from(c).min(MAX_OCCURRENCES));
+
+	dates(start.date(), rule)
+		.take(limit)
+		.filter(|date| !exceptions.contains(date))
+		.map(|date| date.and_time(start.time()))
+		.collect()
+}
+
+/// Правило даёт больше игр, чем `expand` создаёт за раз
+pub(crate) fn is_truncated(start: NaiveDate, rule: &RecurrenceRule) -> bool {
+	rule
+		.count
+		.is_none_or(|count| usize::from(count) > MAX_OCCURRENCES)
+		&& dates(start, rule).nth(MAX_OCCURRENCES).is_some()
+}
+
+/// Даты правила без учёта `count`: до `until` или до конца календаря
+fn dates(start: NaiveDate, rule: &RecurrenceRule) -> impl Iterator<Item = NaiveDate> {
+	let (frequency, until) = (rule.frequency, rule.until);
+	let mut step = 0;
+
+	::std::iter::from_fn(move || {
+		loop {
+			let date = nth_date(start, frequency, step)?;
+			step += 1;
+
+			let Some(date) = date else {
+				// в этом месяце нет нужного дня недели (например, пятой субботы)
+				if step > MAX_OCCURRENCES * 2 {
+					return None;
+				}
+				continue;
+			};
+
+			if until.is_some_and(|until| date > until) {
+				return None;
+			}
+
+			return Some(date);
+		}
+	})
+}
+
+// внешний None - выход за пределы календаря, внутренний - пропуск месяца
+fn nth_date(start: NaiveDate, frequency: Frequency, step: usize) -> Option<Option<NaiveDate>> {
+	let step = u32::try_from(step).ok()?;
+
+	match frequency {
+		Frequency::Weekly => start
+			.checked_add_days(Days::new(u64::from(step) * 7))
+			.map(Some),
+		Frequency::Biweekly => start
+			.checked_add_days(Days::new(u64::from(step) * 14))
+			.map(Some),
+		Frequency::MonthlyWeekday => {
+			let week = u8::try_from((start.day() - 1) / 7 + 1).ok()?;
+			let month = start.with_day(1)?.checked_add_months(Months::new(step))?;
+
+			Some(NaiveDate::from_weekday_of_month_opt(
+				month.year(),
+				month.month(),
+				start.weekday(),
+				week,
+			))
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn dt(s: &str) -> NaiveDateTime {
+		NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+	}
+
+	fn d(s: &str) -> NaiveDate {
+		NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+	}
+
+	#[test]
+	fn test_weekly_with_count_and_exceptions() {
+		let rule = RecurrenceRule {
+			frequency: Frequency::Weekly,
+			until: None,
+			count: Some(4),
+		};
+		let dates = expand(dt("2026-12-24 19:00"), &rule, &[d("2026-12-31")]);
+
+		assert_eq!(
+			dates,
+			vec![
+				dt("2026-12-24 19:00"),
+				dt("2027-01-07 19:00"),
+				dt("2027-01-14 19:00"),
+			]
+		);
+	}
+
+	#[test]
+	fn test_biweekly_until() {
+		let rule = RecurrenceRule {
+			frequency: Frequency::Biweekly,
+			until: Some(d("2026-11-30")),
+			count: None,
+		};
+		let dates = expand(dt("2026-11-01 12:00"), &rule, &[]);
+
+		assert_eq!(
+			dates,
+			vec![
+				dt("2026-11-01 12:00"),
+				dt("2026-11-15 12:00"),
+				dt("2026-11-29 12:00"),
+			]
+		);
+	}
+
+	#[test]
+	fn test_monthly_weekday_skips_missing_weeks() {
+		// 31.10.2026 - пятая суббота месяца
+		let rule = RecurrenceRule {
+			frequency: Frequency::MonthlyWeekday,
+			until: Some(d("2027-03-01")),
+			count: None,
+		};
+		let dates = expand(dt("2026-10-31 18:00"), &rule, &[]);
+
+		assert_eq!(dates, vec![dt("2026-10-31 18:00"), dt("2027-01-30 18:00")]);
+	}
+
+	#[test]
+	fn test_occurrences_are_limited() {
+		let rule = RecurrenceRule {
+			frequency: Frequency::Weekly,
+			until: None,
+			count: None,
+		};
+
+		assert_eq!(
+			expand(dt("2026-01-01 10:00"), &rule, &[]).len(),
+			MAX_OCCURRENCES
+		);
+		assert!(is_truncated(d("2026-01-01"), &rule));
+	}
+
+	#[test]
+	fn test_short_rules_are_not_truncated() {
+		let counted = RecurrenceRule {
+			frequency: Frequency::Weekly,
+			until: None,
+			count: Some(10),
+		};
+		let until = RecurrenceRule {
+			frequency: Frequency::Weekly,
+			until: Some(d("2026-12-31")),
+			count: None,
+		};
+
+		assert!(!is_truncated(d("2026-01-01"), &counted));
+		assert!(!is_truncated(d("2026-01-01"), &until));
+	}
+}
@@ -1,3 +1,4 @@
 mod postgr;
 
 pub(super) use postgr::PostgresStore;
+pub(crate) use postgr::{SseFeed, SseListener};
@@ -0,0 +1,90 @@
+use sqlx::{PgPool, postgres::PgListener};
+use uuid::Uuid;
+
+use crate::{event_log::SseMessage, system_models::CoreResult};
+
+pub(super) const SSE_CHANNEL: &str = "sse_events";
+
+pub(crate) enum SseFeed {
+	Message(SseMessage),
+	/// Подписка (пере)установлена. Всё, что выдано до этого id включительно,
+	/// могло пройти мимо
+	Resync(u64),
+}
+
+/// Подписка экземпляра сервера на события SSE, опубликованные любым экземпляром
+pub(crate) struct SseListener {
+	pool: PgPool,
+	listener: Option<PgListener>,
+}
+
+impl SseListener {
+	pub(super) const fn new(pool: PgPool) -> Self {
+		Self {
+			pool,
+			listener: None,
+		}
+	}
+
+	pub(crate) async fn recv(&mut self) -> CoreResult<SseFeed> {
+		loop {
+			let Some(listener) = self.listener.as_mut() else {
+				let mut listener = PgListener::connect_with(&self.pool).await?;
+				listener.listen(SSE_CHANNEL).await?;
+				self.listener = Some(listener);
+
+				// последнее значение читается уже после LISTEN, чтобы между ними ничего не потерять
+				let head = sqlx::query_scalar::<_, i64>(
+					"select case when is_called then last_value else 0 end from sse_event_id;",
+				)
+				.fetch_one(&self.pool)
+				.await?;
+
+				return Ok(SseFeed::Resync(u64::try_from(head).unwrap_or_default()));
+			};
+
+			let received = listener.try_recv().await;
+
+			match received {
+				Ok(Some(notification)) => {
+					let Ok(id) = notification.payload().parse::<i64>() else {
+						eprintln!("Некорректный id события SSE: {}", notification.payload());
+						continue;
+					};
+
+					// уведомление приходит после коммита, так что строка уже видна
+					let row = sqlx::query_as::<_, (Option<Uuid>, String, String)>(
+						"select user_id, event, data from sse_events where id = $1;",
+					)
+					.bind(id)
+					.fetch_optional(&self.pool)
+					.await;
+
+					match row {
+						Ok(Some((user, event, data))) => {
+							return Ok(SseFeed::Message(SseMessage {
+								id: u64::try_from(id).unwrap_or_default(),
+								seq: 0,
+								user,
+								event,
+								data,
+							}));
+						}
+						Ok(None) => eprintln!("Событие SSE {id} уже удалено"),
+						// событие не прочитано: переподключаемся, чтобы клиенты получили reset
+						Err(err) => {
+							self.listener = None;
+							return Err(err.into());
+						}
+					}
+				}
+				// соединение потеряно: подключаемся заново и сообщаем о возможном пропуске
+				Ok(None) => self.listener = None,
+				Err(err) => {
+					self.listener = None;
+					return Err(err.into());
+				}
+			}
+		}
+	}
+}
@@ -1,22 +1,46 @@
+mod listener;
 mod pool;
 
 use ::std::error::Error;
-use chrono::{DateTime, FixedOffset};
-use sqlx::{Error as SqlxError, PgPool, Postgres, QueryBuilder};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, Utc};
+pub(crate) use listener::{SseFeed, SseListener};
+use sqlx::{
+	Error as SqlxError, PgConnection, PgExecutor, PgPool, Postgres, QueryBuilder,
+	types::Json as SqlxJson,
+};
 use uuid::Uuid;
 
 use super::super::Store;
 use crate::{
 	dto::{
 		auth::{TouchSearch, UpdateProfileDto},
-		company::{ApiUpdateCompanyDto, ReadCompaniesDto},
-		event::{ReadEventsDto, UpdateEventDto},
-		location::ReadLocationDto,
+		company::{
+			ApiCompanyDto, ApiUpdateCompanyDto, CompanyPermission, MemberRole, NewOneShotDto,
+			ReadCompaniesDto, Visibility,
+		},
+		event::{
+			EditScope, ImportCalendarDto, ImportedGameDto, NewEventDto, NewSeriesDto, OnlinePlatform,
+			ReadEventsDto, UpdateEventDto, VenueDto, VenueType,
+		},
+		location::{LocationCapacityDto, NearDto, NewLocationDto, ReadLocationDto},
+		notification::ReadNotificationsDto,
+		page::{Cursor, PageDto, SortDirection, SortField},
+		poll::PollVoteDto,
+		schedule::WeeklyWindowDto,
+		search::{SearchDto, SearchKind},
 	},
+	geo::Coordinates,
+	notification::NotificationPayload,
 	repository::models::{
-		AppForApproval, City, Company, CompanyInfo, Event, EventForApplying, Location, MasterApp,
-		PlayerApp, Profile, Region, ShortEvent, ShortProfile, UserForAuthEmail, UserPair,
+		AppForApproval, CalendarEvent, CancelledSeriesEvent, Checked, City, Company, CompanyInfo,
+		CompanyMember, ConvertedPoll, DatePoll, Event, EventCheck, EventForApplying, Location,
+		LocationLink, MasterApp, MergeStats, MovedEvent, NearbyEvent, NearbyLocation, Notification,
+		OneShotIds, Page, PlayerApp, Profile, Region, ScheduleCheck, ScheduleConflict, SearchHit,
+		Series, SeriesIds, ShortDatePoll, ShortEvent, ShortProfile, TargetConflict, TargetLoad,
+		TelegramChat, TransferParties, User, UserForAuthEmail, UserInterval, UserPair, WeeklyWindow,
+		ZonedDate,
 	},
+	scheduling::{self, DEFAULT_DURATION_HOURS},
 	shared::RecordId,
 	system_models::{AppError, CoreResult},
 };
@@ -135,6 +159,8 @@ impl Store for PostgresStore {
 				, sq.email
 				, sq.email_verified
 				, sq.tg_id
+				, sq.tg_linked
+				, sq.tg_notifications
 				, sq.about_me
 				, sq.city
 				, sq.region
@@ -149,6 +175,8 @@ impl Store for PostgresStore {
 					, u.email
 					, u.email_verified
 					, u.tg_id
+					, (u.tg_chat_id is not null) as tg_linked
+					, u.tg_notifications
 					, u.about_me
 					, u.city
 					, r.name as region
@@ -317,6 +345,196 @@ where a1.player = ",
 		Ok(())
 	}
 
+	async fn read_availability(&self, user_id: Uuid) -> CoreResult<Vec<WeeklyWindow>> {
+		sqlx::query_as::<_, WeeklyWindow>(
+			"select weekday, start_time as from, end_time as to
+			from user_availability
+			where user_id = $1
+			order by weekday, start_time;",
+		)
+		.bind(user_id)
+		.fetch_all(&self.pool)
+		.await
+		.map_err(AppError::from)
+	}
+
+	async fn set_availability(&self, user_id: Uuid, windows: &[WeeklyWindowDto]) -> CoreResult {
+		let weekdays = windows
+			.iter()
+			.map(|window| i16::from(window.weekday))
+			.collect::<Vec<_>>();
+		let starts = windows.iter().map(|window| window.from).collect::<Vec<_>>();
+		let ends = windows.iter().map(|window| window.to).collect::<Vec<_>>();
+
+		let mut tx = self.pool.begin().await?;
+
+		sqlx::query("delete from user_availability where user_id = $1;")
+			.bind(user_id)
+			.execute(&mut *tx)
+			.await?;
+
+		sqlx::query(
+			"INSERT INTO user_availability (user_id, weekday, start_time, end_time)
+			select $1, w.weekday, w.start_time, w.end_time
+			from unnest($2::smallint[], $3::time[], $4::time[]) as w(weekday, start_time, end_time)
+			on conflict do nothing;",
+		)
+		.bind(user_id)
+		.bind(weekdays)
+		.bind(starts)
+		.bind(ends)
+		.execute(&mut *tx)
+		.await?;
+
+		tx.commit().await?;
+
+		Ok(())
+	}
+
+	async fn read_free_intervals(
+		&self,
+		users: &[Uuid],
+		from: DateTime<Utc>,
+		to: DateTime<Utc>,
+	) -> CoreResult<Vec<UserInterval>> {
+		// окна разворачиваются по дням с запасом в сутки с обеих сторон:
+		// местная дата может не совпадать с датой в UTC.
+		// если пояс пользователя неизвестен, окна считаются в UTC
+		sqlx::query_as::<_, UserInterval>(
+			"select t.user_id, t.start, t.end
+from (
+	select
+		a.user_id
+		, (d.day + a.start_time) at time zone tz.name as start
+		, (
+			d.day + a.end_time
+			+ case when a.end_time < a.start_time then interval '1 day' else interval '0' end
+		) at time zone tz.name as end
+	from user_availability a
+	cross join lateral (
+		select COALESCE(
+			(select z.name from pg_timezone_names z where z.name = user_timezone(a.user_id)),
+			'UTC'
+		) as name
+	) tz
+	inner join generate_series(
+		($2 at time zone 'UTC')::date - 1,
+		($3 at time zone 'UTC')::date + 1,
+		interval '1 day'
+	) as g(day)
+		on extract(isodow from g.day) = a.weekday
+	cross join lateral (select g.day::date as day) d
+	where a.user_id = any($1)
+) t
+where t.end > $2 and t.start < $3
+order by t.user_id, t.start;",
+		)
+		.bind(users)
+		.bind(from)
+		.bind(to)
+		.fetch_all(&self.pool)
+		.await
+		.map_err(AppError::from)
+	}
+
+	async fn read_busy_intervals(
+		&self,
+		users: &[Uuid],
+		from: DateTime<Utc>,
+		to: DateTime<Utc>,
+	) -> CoreResult<Vec<UserInterval>> {
+		// занятость - одобренные заявки игрока и игры, которые он ведёт сам
+		sqlx::query_as::<_, UserInterval>(
+			"select t.user_id, t.start, t.end
+from (
+	select
+		a.player as user_id
+		, e.date as start
+		, e.date + make_interval(hours => COALESCE(e.plan_duration, $4)) as end
+	from applications a
+	inner join events e
+		on e.id = a.event
+	where a.player = any($1)
+		and a.approval is true
+		and not e.cancelled
+	union all
+	select
+		c.master as user_id
+		, e.date as start
+		, e.date + make_interval(hours => COALESCE(e.plan_duration, $4)) as end
+	from events e
+	inner join companies c
+		on c.id = e.company
+	where c.master = any($1)
+		and not e.cancelled
+) t
+where t.end > $2 and t.start < $3
+order by t.user_id, t.start;",
+		)
+		.bind(users)
+		.bind(from)
+		.bind(to)
+		.bind(i32::from(DEFAULT_DURATION_HOURS))
+		.fetch_all(&self.pool)
+		.await
+		.map_err(AppError::from)
+	}
+
+	async fn set_calendar_token(&self, user_id: Uuid, token: Option<&str>) -> CoreResult {
+		sqlx::query("update users set calendar_token = $1 where id = $2;")
+			.bind(token)
+			.bind(user_id)
+			.execute(&self.pool)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn read_calendar_events(&self, token: &str) -> CoreResult<Option<Vec<CalendarEvent>>> {
+		let Some(user_id) =
+			sqlx::query_scalar::<_, Uuid>("select id from users where calendar_token = $1;")
+				.bind(token)
+				.fetch_optional(&self.pool)
+				.await?
+		else {
+			return Ok(None);
+		};
+
+		let events = sqlx::query_as::<_, CalendarEvent>(
+			"select
+				e.id,
+				c.name as company,
+				c.description,
+				e.date,
+				e.plan_duration,
+				e.cancelled,
+				l.name as location,
+				l.address,
+				l.map_link,
+				e.online_platform,
+				e.join_link,
+				m.nickname as master
+			from events e
+			inner join companies c on c.id = e.company
+			inner join users m on m.id = c.master
+			left join locations l on l.id = e.location
+			where e.date > CURRENT_TIMESTAMP - interval '90 days'
+			and (
+				exists (
+					select 1 from applications a
+					where a.event = e.id and a.player = $1 and a.approval is true
+				)
+				or has_company_permission(c.id, $1, 'events')
+			)
+			order by e.date;",
+		)
+		.bind(user_id)
+		.fetch_all(&self.pool)
+		.await?;
+
+		Ok(Some(events))
+	}
+
 	async fn verify_email(&self, verification_id: Uuid) -> CoreResult<Option<(bool, bool)>> {
 		sqlx::query_as::<_, (bool, bool)>(
 			"WITH existing_verification AS (
@@ -377,8 +595,242 @@ where a1.player = ",
 		.map_err(AppError::from)
 	}
 
-	async fn get_locations_list(&self, query_args: ReadLocationDto) -> CoreResult<Vec<Location>> {
-		let mut qb: QueryBuilder<'_, Postgres> = QueryBuilder::new("SELECT l.*, r.name as region");
+	async fn add_notifications(
+		&self,
+		users: &[Uuid],
+		message: &str,
+		payload: &NotificationPayload,
+	) -> CoreResult<Vec<(Uuid, Uuid)>> {
+		sqlx::query_as::<_, (Uuid, Uuid)>(
+			"INSERT INTO notifications (user_id, message, payload)
+			select u.id, $2, $3
+			from unnest($1::uuid[]) as u(id)
+			returning user_id, id;",
+		)
+		.bind(users)
+		.bind(message)
+		.bind(SqlxJson(payload))
+		.fetch_all(&self.pool)
+		.await
+		.map_err(AppError::from)
+	}
+
+	fn sse_listener(&self) -> SseListener {
+		SseListener::new(self.pool.clone())
+	}
+
+	async fn publish_sse(&self, users: &[Uuid], event: &str, data: &[String]) -> CoreResult {
+		let users = users.iter().copied().map(Some).collect::<Vec<_>>();
+
+		return insert_sse_events(&self.pool, &users, event, data).await;
+	}
+
+	async fn publish_sse_global(&self, event: &str, data: &str) -> CoreResult {
+		return insert_sse_events(&self.pool, &[None], event, &[data.to_owned()]).await;
+	}
+
+	async fn read_notifications(
+		&self,
+		user_id: Uuid,
+		query: &ReadNotificationsDto,
+		page: &PageDto,
+	) -> CoreResult<Page<Notification>> {
+		let mut qb: QueryBuilder<'_, Postgres> = QueryBuilder::new(
+			"select
+	n.id
+	, n.message
+	, n.payload
+	, n.created_at
+	, n.read_at
+from notifications n
+where n.user_id = ",
+		);
+		qb.push_bind(user_id);
+		if query.unread {
+			qb.push(" and n.read_at is null");
+		}
+
+		// id - uuid v6, поэтому порядок создания и по дате совпадают
+		let key = match page.sort {
+			Some(SortField::Name) => return unsupported_sort(),
+			Some(SortField::Date | SortField::Created) | None => SortKey::Created,
+		};
+		let keyset = Keyset {
+			rank: None,
+			key,
+			id: "n.id",
+		};
+		push_keyset(&mut qb, &keyset, page)?;
+
+		let notifications = qb
+			.build_query_as::<Notification>()
+			.fetch_all(&self.pool)
+			.await?;
+
+		Ok(into_page(notifications, page, |notification| Cursor {
+			id: notification.id,
+			date: None,
+			name: None,
+			rank: None,
+		}))
+	}
+
+	async fn count_unread_notifications(&self, user_id: Uuid) -> CoreResult<i64> {
+		sqlx::query_scalar::<_, i64>(
+			"select count(*) from notifications where user_id = $1 and read_at is null;",
+		)
+		.bind(user_id)
+		.fetch_one(&self.pool)
+		.await
+		.map_err(AppError::from)
+	}
+
+	async fn mark_notification_read(
+		&self,
+		user_id: Uuid,
+		notification_id: Uuid,
+	) -> CoreResult<bool> {
+		// повторная отметка не сдвигает время прочтения, но уведомление считается найденным
+		let res = sqlx::query(
+			"UPDATE notifications
+			SET read_at = COALESCE(read_at, CURRENT_TIMESTAMP)
+			WHERE id = $1 and user_id = $2;",
+		)
+		.bind(notification_id)
+		.bind(user_id)
+		.execute(&self.pool)
+		.await?;
+
+		Ok(res.rows_affected() > 0)
+	}
+
+	async fn mark_all_notifications_read(&self, user_id: Uuid) -> CoreResult<u64> {
+		let res = sqlx::query(
+			"UPDATE notifications
+			SET read_at = CURRENT_TIMESTAMP
+			WHERE user_id = $1 and read_at is null;",
+		)
+		.bind(user_id)
+		.execute(&self.pool)
+		.await?;
+
+		Ok(res.rows_affected())
+	}
+
+	async fn set_telegram_link_code(
+		&self,
+		user_id: Uuid,
+		code: &str,
+		expires_at: DateTime<Utc>,
+	) -> CoreResult {
+		sqlx::query("update users set tg_link_code = $1, tg_link_expires_at = $2 where id = $3;")
+			.bind(code)
+			.bind(expires_at)
+			.bind(user_id)
+			.execute(&self.pool)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn link_telegram_chat(&self, code: &str, chat_id: i64) -> CoreResult<Option<Uuid>> {
+		let mut tx = self.pool.begin().await?;
+
+		let Some(user_id) = sqlx::query_scalar::<_, Uuid>(
+			"update users
+			set tg_link_code = null, tg_link_expires_at = null
+			where tg_link_code = $1 and tg_link_expires_at > CURRENT_TIMESTAMP
+			returning id;",
+		)
+		.bind(code)
+		.fetch_optional(&mut *tx)
+		.await?
+		else {
+			return Ok(None);
+		};
+
+		// чат мог быть привязан к другому аккаунту
+		sqlx::query(
+			"update users
+			set tg_chat_id = null, tg_notifications = false
+			where tg_chat_id = $1 and id <> $2;",
+		)
+		.bind(chat_id)
+		.bind(user_id)
+		.execute(&mut *tx)
+		.await?;
+
+		sqlx::query("update users set tg_chat_id = $1, tg_notifications = true where id = $2;")
+			.bind(chat_id)
+			.bind(user_id)
+			.execute(&mut *tx)
+			.await?;
+
+		tx.commit().await?;
+
+		Ok(Some(user_id))
+	}
+
+	async fn set_telegram_notifications(&self, user_id: Uuid, enabled: bool) -> CoreResult<bool> {
+		let res = sqlx::query(
+			"update users set tg_notifications = $1 where id = $2 and tg_chat_id is not null;",
+		)
+		.bind(enabled)
+		.bind(user_id)
+		.execute(&self.pool)
+		.await?;
+
+		Ok(res.rows_affected() > 0)
+	}
+
+	async fn disable_telegram_chats(&self, chats: &[i64]) -> CoreResult<u64> {
+		let res = sqlx::query(
+			"update users set tg_notifications = false
+			where tg_chat_id = any($1) and tg_notifications;",
+		)
+		.bind(chats)
+		.execute(&self.pool)
+		.await?;
+
+		Ok(res.rows_affected())
+	}
+
+	async fn read_telegram_chats(
+		&self,
+		users: &[Uuid],
+		date: Option<DateTime<Utc>>,
+	) -> CoreResult<Vec<TelegramChat>> {
+		// смещение берётся на саму дату, а не текущее: между ними может быть перевод часов
+		sqlx::query_as::<_, TelegramChat>(
+			"select
+				u.tg_chat_id as chat_id
+				, extract(epoch from ($2::timestamptz AT TIME ZONE tz.name) - ($2::timestamptz AT TIME ZONE 'UTC'))::int
+					as utc_offset
+			from users u
+			left join pg_timezone_names tz
+				on tz.name = user_timezone(u.id)
+			where u.id = any($1) and u.tg_notifications and u.tg_chat_id is not null;",
+		)
+		.bind(users)
+		.bind(date)
+		.fetch_all(&self.pool)
+		.await
+		.map_err(AppError::from)
+	}
+
+	async fn get_locations_list(
+		&self,
+		query_args: ReadLocationDto,
+		page: &PageDto,
+	) -> CoreResult<Page<Location>> {
+		let key = match page.sort.unwrap_or(SortField::Name) {
+			SortField::Name => SortKey::Name("t.name"),
+			SortField::Created => SortKey::Created,
+			SortField::Date => return unsupported_sort(),
+		};
+
+		let mut qb: QueryBuilder<'_, Postgres> =
+			QueryBuilder::new("SELECT * FROM (SELECT l.*, r.name as region");
 
 		let query_name = query_args.name.as_deref().unwrap_or_default();
 		let query_region = query_args.region.as_deref().unwrap_or_default();
@@ -429,18 +881,27 @@ LEFT JOIN regions r ON r.name = c.region",
 			}
 		}
 
-		qb.push(" order by");
-		if !query_name.is_empty() {
-			qb.push(" rank,");
-		}
-		qb.push(" l.name asc");
+		// ранг поиска считается во вложенном запросе, чтобы на него мог сослаться курсор
+		qb.push(") t WHERE true");
+
+		let keyset = Keyset {
+			rank: (!query_name.is_empty()).then_some("t.rank"),
+			key,
+			id: "t.id",
+		};
+		push_keyset(&mut qb, &keyset, page)?;
 
 		let locations = qb
 			.build_query_as::<Location>()
 			.fetch_all(&self.pool)
 			.await?;
 
-		Ok(locations)
+		Ok(into_page(locations, page, |location| Cursor {
+			id: location.id,
+			date: None,
+			name: Some(location.name.clone()),
+			rank: location.rank,
+		}))
 	}
 
 	async fn get_location_by_id(&self, location_id: Uuid) -> CoreResult<Option<Location>> {
@@ -448,6 +909,17 @@ LEFT JOIN regions r ON r.name = c.region",
 			"SELECT
 	l.*
 	, r.name as region
+	, (
+		select COALESCE(
+			jsonb_agg(
+				jsonb_build_object('weekday', h.weekday, 'from', h.open_time, 'to', h.close_time)
+				ORDER BY h.weekday, h.open_time
+			),
+			'[]'
+		)
+		from location_hours h
+		where h.location = l.id
+	) as opening_hours
 FROM locations l
 LEFT JOIN cities c ON c.name = l.city
 LEFT JOIN regions r ON r.name = c.region
@@ -460,37 +932,171 @@ WHERE id = $1;",
 		Ok(may_be_location)
 	}
 
-	async fn add_location(
-		&self,
-		name: &str,
-		address: &Option<String>,
-		descr: &Option<String>,
-		city: &Option<String>,
-		map_link: &Option<String>,
-	) -> CoreResult<RecordId> {
+	async fn add_location(&self, data: &NewLocationDto) -> CoreResult<RecordId> {
+		let mut tx = self.pool.begin().await?;
+
 		let query_result = sqlx::query_scalar::<_, RecordId>(
-			"INSERT INTO locations (name, address, description, city, map_link) values ($1, $2, $3, $4, $5) returning id;",
+			"INSERT INTO locations (name, address, description, city, map_link, tables, latitude, longitude) values ($1, $2, $3, $4, $5, $6, $7, $8) returning id;",
 		)
-		.bind(name)
-		.bind(address)
-		.bind(descr)
-		.bind(city)
-		.bind(map_link)
-		.fetch_one(&self.pool)
+		.bind(&data.name)
+		.bind(&data.address)
+		.bind(&data.description)
+		.bind(&data.city)
+		.bind(&data.map_link)
+		.bind(data.tables)
+		.bind(data.latitude)
+		.bind(data.longitude)
+		.fetch_one(&mut *tx)
 		.await;
 
 		let new_loc_id = query_result.map_err(|err| {
 			let err_str = err.to_string();
 			if err_str.contains(DUPLICATE_KEY) {
-				AppError::scenario_error("Локация с данным названием уже существует", name.into())
+				AppError::scenario_error(
+					"Локация с данным названием уже существует",
+					data.name.as_str().into(),
+				)
 			} else {
 				AppError::system_error(err_str)
 			}
 		})?;
 
+		insert_location_hours(&mut *tx, new_loc_id.uuid(), &data.opening_hours).await?;
+
+		tx.commit().await?;
+
 		Ok(new_loc_id)
 	}
 
+	async fn read_nearby_locations(&self, near: &NearDto) -> CoreResult<Vec<NearbyLocation>> {
+		// градус широты - около 111 км, по нему отсекаются заведомо далёкие локации
+		sqlx::query_as::<_, NearbyLocation>(
+			"select *
+from (
+	select
+		l.*
+		, r.name as region
+		, geo_distance_km($1, $2, l.latitude, l.longitude) as distance_km
+	from locations l
+	left join cities c
+		on c.name = l.city
+	left join regions r
+		on r.name = c.region
+	where l.latitude between $1 - $3 / 111.0 and $1 + $3 / 111.0
+) t
+where t.distance_km <= $3
+order by t.distance_km, t.id
+limit $4;",
+		)
+		.bind(near.lat)
+		.bind(near.lon)
+		.bind(near.radius())
+		.bind(i64::from(near.limit()))
+		.fetch_all(&self.pool)
+		.await
+		.map_err(AppError::from)
+	}
+
+	async fn read_nearby_events(
+		&self,
+		near: &NearDto,
+		from: DateTime<Utc>,
+		to: DateTime<Utc>,
+	) -> CoreResult<Vec<NearbyEvent>> {
+		// в поиск рядом попадают только публичные игры
+		sqlx::query_as::<_, NearbyEvent>(
+			"select *
+from (
+	select
+		e.id
+		, c.name as company
+		, e.date
+		, e.plan_duration
+		, l.id as location_id
+		, l.name as location_name
+		, geo_distance_km($1, $2, l.latitude, l.longitude) as distance_km
+	from events e
+	inner join companies c
+		on c.id = e.company
+	inner join locations l
+		on l.id = e.location
+	where l.latitude between $1 - $3 / 111.0 and $1 + $3 / 111.0
+		and e.date between $5 and $6
+		and not e.cancelled
+		and e.visibility = 'public'
+		and c.visibility = 'public'
+) t
+where t.distance_km <= $3
+order by t.distance_km, t.date
+limit $4;",
+		)
+		.bind(near.lat)
+		.bind(near.lon)
+		.bind(near.radius())
+		.bind(i64::from(near.limit()))
+		.bind(from)
+		.bind(to)
+		.fetch_all(&self.pool)
+		.await
+		.map_err(AppError::from)
+	}
+
+	async fn read_links_without_coordinates(&self) -> CoreResult<Vec<LocationLink>> {
+		sqlx::query_as::<_, LocationLink>(
+			"select id, map_link from locations where latitude is null and map_link is not null;",
+		)
+		.fetch_all(&self.pool)
+		.await
+		.map_err(AppError::from)
+	}
+
+	async fn set_location_coordinates(
+		&self,
+		location_id: Uuid,
+		coordinates: Coordinates,
+	) -> CoreResult {
+		sqlx::query("update locations set latitude = $2, longitude = $3 where id = $1;")
+			.bind(location_id)
+			.bind(coordinates.latitude)
+			.bind(coordinates.longitude)
+			.execute(&self.pool)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn set_location_capacity(
+		&self,
+		location_id: Uuid,
+		data: &LocationCapacityDto,
+	) -> CoreResult<bool> {
+		let mut tx = self.pool.begin().await?;
+
+		let was_updated = sqlx::query_scalar::<_, bool>(
+			"update locations set tables = $2 where id = $1 returning true;",
+		)
+		.bind(location_id)
+		.bind(data.tables)
+		.fetch_optional(&mut *tx)
+		.await?
+		.unwrap_or_default();
+
+		if !was_updated {
+			return Ok(false);
+		}
+
+		sqlx::query("delete from location_hours where location = $1;")
+			.bind(location_id)
+			.execute(&mut *tx)
+			.await?;
+
+		insert_location_hours(&mut *tx, location_id, &data.opening_hours).await?;
+
+		tx.commit().await?;
+
+		Ok(true)
+	}
+
 	async fn get_company_by_id(
 		&self,
 		company_id: Uuid,
@@ -506,10 +1112,23 @@ WHERE id = $1;",
 				, u.nickname AS master_name
 				, ($2 is not null and u.id = $2) AS you_are_master
 				, c.event_style
+				, c.one_shot
+				, c.visibility
+				, (mm.user_id is not null) AS you_are_member
+				, CASE
+					WHEN u.id = $2 THEN 'master'
+					ELSE mm.role
+				END AS your_role
 			FROM companies c
 			inner join users u
 				on c.master = u.id
-			where c.id = $1;",
+			left join company_members mm
+				on mm.company = c.id
+				and mm.user_id = $2
+				and mm.status = 'active'
+			where c.id = $1
+			-- приватную кампанию видят только мастер и участники
+			and (c.visibility <> 'private' or u.id = $2 or mm.user_id is not null);",
 		)
 		.bind(company_id)
 		.bind(user_id)
@@ -533,9 +1152,16 @@ WHERE id = $1;",
 		&self,
 		query_args: ReadCompaniesDto,
 		master: Uuid,
-	) -> CoreResult<Vec<Company>> {
+		page: &PageDto,
+	) -> CoreResult<Page<Company>> {
+		let key = match page.sort.unwrap_or(SortField::Name) {
+			SortField::Name => SortKey::Name("t.name"),
+			SortField::Created => SortKey::Created,
+			SortField::Date => return unsupported_sort(),
+		};
+
 		let mut qb: QueryBuilder<'_, Postgres> = QueryBuilder::new(
-			r#"SELECT "id", "master", "name", "system", "description"
+			r#"SELECT * FROM (SELECT "id", "master", "name", "system", "description", "one_shot", "visibility"
 				, CASE
 					WHEN "cover_link" IS NOT NULL THEN ('/cover/' || "id")
 					ELSE NULL
@@ -555,8 +1181,9 @@ WHERE id = $1;",
 			qb.push("END AS rank");
 		}
 
-		qb.push(" FROM companies WHERE master = ");
+		qb.push(" FROM companies WHERE has_company_permission(id, ");
 		qb.push_bind(master);
+		qb.push(", 'events')");
 
 		if !query_name.is_empty() {
 			qb.push(" AND LOWER(name) LIKE '%' || LOWER(");
@@ -564,42 +1191,90 @@ WHERE id = $1;",
 			qb.push(") || '%'");
 		}
 
-		qb.push(" order by");
-		if !query_name.is_empty() {
-			qb.push(" rank,");
-		}
-		qb.push(" name asc");
+		qb.push(") t WHERE true");
+
+		let keyset = Keyset {
+			rank: (!query_name.is_empty()).then_some("t.rank"),
+			key,
+			id: "t.id",
+		};
+		push_keyset(&mut qb, &keyset, page)?;
 
 		let companies = qb.build_query_as::<Company>().fetch_all(&self.pool).await?;
 
-		Ok(companies)
+		Ok(into_page(companies, page, |company| Cursor {
+			id: company.id,
+			date: None,
+			name: Some(company.name.clone()),
+			rank: company.rank,
+		}))
+	}
+
+	async fn add_company(&self, master: Uuid, data: &ApiCompanyDto) -> CoreResult<RecordId> {
+		insert_company(
+			&self.pool,
+			master,
+			&data.name,
+			&data.system,
+			&data.description,
+			&data.cover_link,
+			&data.event_style,
+			false,
+			data.visibility,
+		)
+		.await
 	}
 
-	async fn add_company(
+	async fn add_one_shot(
 		&self,
 		master: Uuid,
-		name: &str,
-		system: &str,
-		descr: &Option<String>,
-		cover_link: &Option<String>,
-		event_style: &Option<String>,
-	) -> CoreResult<RecordId> {
-		let new_comp_id = sqlx::query_scalar::<_, RecordId>(
-			"INSERT INTO companies
-			(master, name, system, description, cover_link, event_style)
-			values ($1, $2, $3, $4, $5, $6)
-			returning id;",
+		data: &NewOneShotDto,
+		date: DateTime<FixedOffset>,
+		check: ScheduleCheck,
+	) -> CoreResult<Checked<OneShotIds>> {
+		let mut tx = self.pool.begin().await?;
+
+		let company_id = insert_company(
+			&mut *tx,
+			master,
+			&data.name,
+			&data.system,
+			&data.description,
+			&data.cover_link,
+			&data.event_style,
+			true,
+			data.visibility,
 		)
-		.bind(master)
-		.bind(name)
-		.bind(system)
-		.bind(descr)
-		.bind(cover_link)
-		.bind(event_style)
-		.fetch_one(&self.pool)
 		.await?;
 
-		Ok(new_comp_id)
+		lock_location(&mut *tx, data.location).await?;
+
+		let event_id = insert_event(
+			&mut *tx,
+			company_id.uuid(),
+			&data.location,
+			date,
+			data.max_slots,
+			data.plan_duration,
+			Visibility::Public,
+			&data.venue,
+			None,
+		)
+		.await?;
+
+		let checks = check_events(&mut tx, check, &[event_id.uuid()]).await?;
+		if check.rejects(&checks) {
+			return Ok(Checked::Rejected(checks));
+		}
+
+		tx.commit().await?;
+
+		let ids = OneShotIds {
+			company_id: company_id.uuid(),
+			event_id: event_id.uuid(),
+		};
+
+		Ok(Checked::Saved(ids, checks))
 	}
 
 	async fn update_company(
@@ -648,11 +1323,19 @@ WHERE id = $1;",
 			qb.push_bind(data.event_style.unwrap());
 		}
 
+		if let Some(visibility) = data.visibility {
+			if is_name_passed || is_system_passed || is_description_passed || is_event_style_passed {
+				qb.push(",");
+			}
+			qb.push(" visibility = ");
+			qb.push_bind(visibility.as_str());
+		}
+
 		qb.push(" where id = ");
 		qb.push_bind(company_id);
-		qb.push(" and master = ");
+		qb.push(" and has_company_permission(id, ");
 		qb.push_bind(master);
-		qb.push(" returning true;");
+		qb.push(", 'company') returning true;");
 
 		let was_updated = qb
 			.build_query_scalar::<bool>()
@@ -670,7 +1353,9 @@ WHERE id = $1;",
 		cover_link: &str,
 	) -> CoreResult<bool> {
 		let was_updated = sqlx::query_scalar::<_, bool>(
-			"update companies set cover_link = $1 where id = $2 and master = $3 returning true;",
+			"update companies set cover_link = $1
+			where id = $2 and has_company_permission(id, $3, 'company')
+			returning true;",
 		)
 		.bind(cover_link)
 		.bind(company_id)
@@ -682,100 +1367,363 @@ WHERE id = $1;",
 		Ok(was_updated)
 	}
 
-	async fn read_events_list(
-		&self,
-		query_args: ReadEventsDto,
-		player_id: Option<Uuid>,
-	) -> CoreResult<Vec<ShortEvent>> {
-		let mut qb: QueryBuilder<'_, Postgres> = QueryBuilder::new(
-			"SELECT
-					e.id
-					, c.name AS company
-					, e.date
-					, e.plan_duration
-					, c.event_style as style
-				FROM events e
-				INNER JOIN companies c
-					ON c.id = e.company",
-		);
-
-		if !query_args.company.is_empty() {
-			qb.push(" AND e.company = ANY(");
-			qb.push_bind(query_args.company);
-			qb.push(')');
-		}
+	async fn read_company_members(&self, company_id: Uuid) -> CoreResult<Vec<CompanyMember>> {
+		sqlx::query_as::<_, CompanyMember>(
+			"select
+	u.id as user_id
+	, u.nickname
+	, m.status
+	, m.role
+from company_members m
+inner join users u
+	on u.id = m.user_id
+where m.company = $1
+order by m.status asc, u.nickname asc;",
+		)
+		.bind(company_id)
+		.fetch_all(&self.pool)
+		.await
+		.map_err(AppError::from)
+	}
 
-		if query_args.location.is_some() || query_args.region.is_some() || query_args.city.is_some() {
-			qb.push(" INNER JOIN locations l ON l.id = e.location");
-		}
+	async fn invite_member(&self, company_id: Uuid, user_id: Uuid) -> CoreResult<bool> {
+		let was_invited = sqlx::query_scalar::<_, bool>(
+			"INSERT INTO company_members (company, user_id) values ($1, $2)
+			on conflict do nothing
+			returning true;",
+		)
+		.bind(company_id)
+		.bind(user_id)
+		.fetch_optional(&self.pool)
+		.await
+		.map_err(|err| {
+			let err_str = err.to_string();
+			if err_str.contains("FK_company_members_users") {
+				AppError::scenario_error("Пользователь не найден", user_id.into())
+			} else {
+				AppError::system_error(err_str)
+			}
+		})?
+		.unwrap_or_default();
 
-		if let Some(location_id) = query_args.location {
-			qb.push(" AND l.id = ");
-			qb.push_bind(location_id);
-		}
+		Ok(was_invited)
+	}
 
-		if query_args.region.is_some() || query_args.city.is_some() {
-			qb.push(" INNER JOIN cities ci ON ci.name = l.city");
-		}
+	async fn accept_membership(&self, company_id: Uuid, user_id: Uuid) -> CoreResult<bool> {
+		let was_accepted = sqlx::query_scalar::<_, bool>(
+			"update company_members set status = 'active'
+			where company = $1 and user_id = $2 and status = 'invited'
+			returning true;",
+		)
+		.bind(company_id)
+		.bind(user_id)
+		.fetch_optional(&self.pool)
+		.await?
+		.unwrap_or_default();
 
-		if let Some(city) = query_args.city {
-			qb.push(" AND ci.name = ");
-			qb.push_bind(city);
-		}
+		Ok(was_accepted)
+	}
 
-		if let Some(region) = query_args.region {
-			qb.push(" INNER JOIN regions r ON r.name = ci.region AND r.name = ");
-			qb.push_bind(region);
-		}
+	async fn set_member_role(
+		&self,
+		company_id: Uuid,
+		user_id: Uuid,
+		role: MemberRole,
+	) -> CoreResult<bool> {
+		let was_updated = sqlx::query_scalar::<_, bool>(
+			"update company_members set role = $3
+			where company = $1 and user_id = $2
+			returning true;",
+		)
+		.bind(company_id)
+		.bind(user_id)
+		.bind(role.as_str())
+		.fetch_optional(&self.pool)
+		.await?
+		.unwrap_or_default();
 
-		qb.push(" INNER JOIN users m ON m.id = c.master");
-
-		if let Some(master_id) = query_args.master {
-			qb.push(" AND m.id = ");
-			qb.push_bind(master_id);
-		} else if let Some(imamaster) = query_args.imamaster
-			&& let Some(my_id) = player_id
-		{
-			match imamaster {
-				true => qb.push(" AND m.id = "),
-				false => qb.push(" AND m.id <> "),
-			};
-			qb.push_bind(my_id);
+		Ok(was_updated)
+	}
+
+	async fn has_company_permission(
+		&self,
+		company_id: Uuid,
+		user_id: Uuid,
+		permission: CompanyPermission,
+	) -> CoreResult<bool> {
+		sqlx::query_scalar::<_, bool>("select has_company_permission($1, $2, $3);")
+			.bind(company_id)
+			.bind(user_id)
+			.bind(permission.as_str())
+			.fetch_one(&self.pool)
+			.await
+			.map_err(AppError::from)
+	}
+
+	async fn remove_member(&self, company_id: Uuid, user_id: Uuid) -> CoreResult<bool> {
+		let was_removed = sqlx::query_scalar::<_, bool>(
+			"delete from company_members where company = $1 and user_id = $2 returning true;",
+		)
+		.bind(company_id)
+		.bind(user_id)
+		.fetch_optional(&self.pool)
+		.await?
+		.unwrap_or_default();
+
+		Ok(was_removed)
+	}
+
+	async fn propose_transfer(
+		&self,
+		company_id: Uuid,
+		master: Uuid,
+		to_user: Uuid,
+		stay_as: Option<MemberRole>,
+	) -> CoreResult<bool> {
+		let was_proposed = sqlx::query_scalar::<_, bool>(
+			"INSERT INTO company_transfers (company, to_user, stay_as)
+			select id, $3, $4 from companies where id = $1 and master = $2
+			on conflict (company) do update
+				set to_user = excluded.to_user
+				, stay_as = excluded.stay_as
+				, created_at = CURRENT_TIMESTAMP
+			returning true;",
+		)
+		.bind(company_id)
+		.bind(master)
+		.bind(to_user)
+		.bind(stay_as.map(MemberRole::as_str))
+		.fetch_optional(&self.pool)
+		.await
+		.map_err(|err| {
+			let err_str = err.to_string();
+			if err_str.contains("FK_company_transfers_users") {
+				AppError::scenario_error("Пользователь не найден", to_user.into())
+			} else {
+				AppError::system_error(err_str)
+			}
+		})?
+		.unwrap_or_default();
+
+		Ok(was_proposed)
+	}
+
+	async fn accept_transfer(
+		&self,
+		company_id: Uuid,
+		user_id: Uuid,
+	) -> CoreResult<Option<TransferParties>> {
+		let mut tx = self.pool.begin().await?;
+
+		let may_be_transfer = sqlx::query_as::<_, TransferParties>(
+			"delete from company_transfers t
+			using companies c
+			where t.company = $1
+			and t.to_user = $2
+			and c.id = t.company
+			returning c.name as company_name, c.master, t.to_user as new_master, t.stay_as;",
+		)
+		.bind(company_id)
+		.bind(user_id)
+		.fetch_optional(&mut *tx)
+		.await?;
+
+		let Some(transfer) = may_be_transfer else {
+			return Ok(None);
+		};
+
+		// игры и заявки привязаны к кампании, поэтому меняется только владелец
+		sqlx::query("update companies set master = $2 where id = $1;")
+			.bind(company_id)
+			.bind(user_id)
+			.execute(&mut *tx)
+			.await?;
+
+		sqlx::query("delete from company_members where company = $1 and user_id = $2;")
+			.bind(company_id)
+			.bind(user_id)
+			.execute(&mut *tx)
+			.await?;
+
+		if let Some(ref role) = transfer.stay_as {
+			sqlx::query(
+				"INSERT INTO company_members (company, user_id, status, role)
+				values ($1, $2, 'active', $3);",
+			)
+			.bind(company_id)
+			.bind(transfer.master)
+			.bind(role)
+			.execute(&mut *tx)
+			.await?;
 		}
 
-		qb.push(" LEFT JOIN applications y ON y.player = ");
-		qb.push_bind(player_id);
+		tx.commit().await?;
 
-		qb.push(
-			" and y.event = e.id
-				LEFT JOIN applications ap
-					ON ap.event = e.id
-				LEFT JOIN users u
-					ON u.id = ap.player
-				WHERE e.date >= ",
+		Ok(Some(transfer))
+	}
+
+	async fn cancel_transfer(
+		&self,
+		company_id: Uuid,
+		user_id: Uuid,
+	) -> CoreResult<Option<TransferParties>> {
+		sqlx::query_as::<_, TransferParties>(
+			"delete from company_transfers t
+			using companies c
+			where t.company = $1
+			and c.id = t.company
+			and (c.master = $2 or t.to_user = $2)
+			returning c.name as company_name, c.master, t.to_user as new_master, t.stay_as;",
+		)
+		.bind(company_id)
+		.bind(user_id)
+		.fetch_optional(&self.pool)
+		.await
+		.map_err(AppError::from)
+	}
+
+	async fn read_events_list(
+		&self,
+		query_args: ReadEventsDto,
+		player_id: Option<Uuid>,
+		page: &PageDto,
+	) -> CoreResult<Page<ShortEvent>> {
+		let mut qb: QueryBuilder<'_, Postgres> = QueryBuilder::new(
+			"SELECT
+					e.id
+					, c.name AS company
+					, e.date
+					, e.plan_duration
+					, c.event_style as style
+					, e.venue_type
+					, e.online_platform",
 		);
-		qb.push_bind(query_args.date_from);
+		push_events_filters(&mut qb, query_args, player_id);
 
-		qb.push(" AND e.date <= ");
-		qb.push_bind(query_args.date_to);
+		let keyset = Keyset {
+			rank: None,
+			key: date_or_created(page, "e.date")?,
+			id: "e.id",
+		};
+		push_keyset(&mut qb, &keyset, page)?;
 
-		if let Some(applied) = query_args.applied {
-			match applied {
-				true => qb.push(" AND y.id is not null"),
-				false => qb.push(" AND y.id is null"),
-			};
+		let events = qb
+			.build_query_as::<ShortEvent>()
+			.fetch_all(&self.pool)
+			.await?;
+
+		Ok(into_page(events, page, |event| Cursor {
+			id: event.id,
+			date: Some(event.date),
+			name: None,
+			rank: None,
+		}))
+	}
+
+	async fn read_public_calendar(
+		&self,
+		query_args: ReadEventsDto,
+	) -> CoreResult<Vec<CalendarEvent>> {
+		// ссылка на подключение в публичный календарь не попадает
+		let mut qb: QueryBuilder<'_, Postgres> = QueryBuilder::new(
+			"SELECT
+					e.id
+					, c.name AS company
+					, c.description
+					, e.date
+					, e.plan_duration
+					, e.cancelled
+					, l.name AS location
+					, l.address
+					, l.map_link
+					, e.online_platform
+					, NULL::TEXT AS join_link
+					, m.nickname AS master",
+		);
+		push_events_filters(&mut qb, query_args, None);
+		qb.push(" ORDER BY e.date, e.id;");
+
+		qb.build_query_as::<CalendarEvent>()
+			.fetch_all(&self.pool)
+			.await
+			.map_err(AppError::from)
+	}
+
+	async fn search(
+		&self,
+		search: &SearchDto,
+		tsquery: &str,
+		user_id: Option<Uuid>,
+	) -> CoreResult<Vec<SearchHit>> {
+		let mut qb: QueryBuilder<'_, Postgres> = QueryBuilder::new(
+			"WITH q AS (
+				SELECT to_tsquery('russian', ",
+		);
+		qb.push_bind(tsquery);
+		qb.push(") AS ru, to_tsquery('simple', ");
+		qb.push_bind(tsquery);
+		qb.push(") AS simple) SELECT * FROM (");
+
+		let mut separated = qb.separated(" UNION ALL ");
+
+		if search.includes(SearchKind::Company) {
+			// закрытые и доступные по ссылке кампании находит только их команда
+			separated.push(
+				"SELECT 'company' AS kind, c.id, c.name AS title, c.system AS subtitle
+					, NULL::TIMESTAMPTZ AS date, ts_rank(c.search_vector, q.ru) AS rank
+				FROM companies c CROSS JOIN q
+				WHERE c.search_vector @@ q.ru
+				AND (c.visibility = 'public' OR has_company_permission(c.id, ",
+			);
+			separated.push_bind_unseparated(user_id);
+			separated.push_unseparated(", 'events'))");
 		}
 
-		if let Some(not_rejected) = query_args.not_rejected {
-			match not_rejected {
-				true => qb.push(" AND (y.approval is null OR y.approval = true)"),
-				false => qb.push(" AND y.approval = false"),
-			};
+		if search.includes(SearchKind::Location) {
+			separated.push(
+				"SELECT 'location' AS kind, l.id, l.name AS title, l.address AS subtitle
+					, NULL::TIMESTAMPTZ AS date, ts_rank(l.search_vector, q.ru) AS rank
+				FROM locations l CROSS JOIN q
+				WHERE l.search_vector @@ q.ru",
+			);
 		}
 
-		qb.push(';');
+		if search.includes(SearchKind::Master) {
+			separated.push(
+				"SELECT 'master' AS kind, u.id, u.nickname AS title, NULL AS subtitle
+					, NULL::TIMESTAMPTZ AS date, ts_rank(u.search_vector, q.simple) AS rank
+				FROM users u CROSS JOIN q
+				WHERE u.search_vector @@ q.simple
+				AND EXISTS (
+					SELECT 1 FROM companies c
+					WHERE c.master = u.id
+					AND c.visibility = 'public'
+				)",
+			);
+		}
 
-		qb.build_query_as::<ShortEvent>()
+		if search.includes(SearchKind::Event) {
+			separated.push(
+				"SELECT 'event' AS kind, e.id, c.name AS title, l.name AS subtitle, e.date
+					, ts_rank(c.search_vector, q.ru) + COALESCE(ts_rank(l.search_vector, q.ru), 0) AS rank
+				FROM events e
+				INNER JOIN companies c
+					ON c.id = e.company
+				LEFT JOIN locations l
+					ON l.id = e.location
+				CROSS JOIN q
+				WHERE (c.search_vector @@ q.ru OR l.search_vector @@ q.ru)
+				AND e.date > CURRENT_TIMESTAMP
+				AND NOT e.cancelled
+				AND c.visibility = 'public'
+				AND e.visibility = 'public'",
+			);
+		}
+
+		qb.push(") hits ORDER BY rank DESC, title LIMIT ");
+		qb.push_bind(i64::from(search.limit()));
+
+		qb.build_query_as::<SearchHit>()
 			.fetch_all(&self.pool)
 			.await
 			.map_err(AppError::from)
@@ -802,23 +1750,40 @@ WHERE id = $1;",
 				, e.max_slots
 				, e.plan_duration
 				, bool_or(y.id is not null) AS you_applied
-				, ($2 is not null and c.master = $2) as you_are_master
+				, ($2 is not null and has_company_permission(c.id, $2, 'events')) as you_are_master
+				, (mm.user_id is not null) AS you_are_member
 				, y.approval AS your_approval
+				, CASE
+					WHEN 'private' IN (c.visibility, e.visibility) THEN 'private'
+					WHEN 'unlisted' IN (c.visibility, e.visibility) THEN 'unlisted'
+					ELSE 'public'
+				END AS visibility
+				, e.venue_type
+				, e.online_platform
+				-- ссылку на подключение видят только одобренные игроки и ведущие
+				, CASE
+					WHEN y.approval is true
+						OR ($2 is not null and has_company_permission(c.id, $2, 'events'))
+					THEN e.join_link
+				END AS join_link
+				, e.series
 			FROM events e
 			INNER JOIN companies c
 				ON c.id = e.company
-			INNER JOIN locations l
+			LEFT JOIN locations l
 				ON l.id = e.location
 			INNER JOIN users m
 				ON m.id = c.master
 			LEFT JOIN applications y
 				ON y.player = $2 and y.event = e.id
+			LEFT JOIN company_members mm
+				ON mm.company = c.id and mm.user_id = $2 and mm.status = 'active'
 			LEFT JOIN applications ap
 				ON ap.event = e.id
 			LEFT JOIN users u
 				ON u.id = ap.player
 			WHERE e.id = $1
-			GROUP BY e.id, c.name, c.id, m.nickname, m.id, l.name, l.id, e.date, e.cancelled, y.approval;",
+			GROUP BY e.id, c.name, c.id, m.nickname, m.id, l.name, l.id, e.date, e.cancelled, y.approval, mm.user_id;",
 		)
 		.bind(event_id)
 		.bind(player_id)
@@ -842,129 +1807,766 @@ WHERE id = $1;",
 			)
 			select
 				e.id
-				, (c.master = $2) as you_are_master
+				, has_company_permission(c.id, $2, 'events') as you_are_master
 				, c.master as master_id
 				, c.name as company_name
 				, e.date as event_date
 				, bool_or(a.id is not null) AS already_applied
 				, (e.max_slots is null or approved_slots.count < e.max_slots) as can_auto_approve
 				, e.cancelled
+				, (mm.user_id is not null) as you_are_member
+				, CASE
+					WHEN 'private' IN (c.visibility, e.visibility) THEN 'private'
+					WHEN 'unlisted' IN (c.visibility, e.visibility) THEN 'unlisted'
+					ELSE 'public'
+				END as visibility
+				, e.plan_duration
 			from events e
 			inner join companies c
 				on c.id = e.company
 			left join applications a
 				on a.event = e.id
 				and a.player = $2
+			left join company_members mm
+				on mm.company = c.id
+				and mm.user_id = $2
+				and mm.status = 'active'
 			inner join approved_slots on true
 			where e.id = $1
-			group by e.id, c.master, c.name, e.date, approved_slots.count;",
+			group by e.id, c.id, approved_slots.count, mm.user_id;",
 		)
 		.bind(event_id)
 		.bind(player_id)
 		.fetch_optional(&self.pool)
 		.await?;
 
-		Ok(event)
-	}
+		Ok(event)
+	}
+
+	async fn read_conflicts(
+		&self,
+		user_id: Uuid,
+		event_id: Uuid,
+	) -> CoreResult<Vec<ScheduleConflict>> {
+		let conflicts = select_conflicts(&self.pool, user_id, &[event_id]).await?;
+
+		Ok(conflicts
+			.into_iter()
+			.map(|target| target.conflict)
+			.collect())
+	}
+
+	async fn apply_event(
+		&self,
+		event_id: Uuid,
+		player_id: Uuid,
+		can_auto_approve: bool,
+	) -> CoreResult<RecordId> {
+		let approval = if can_auto_approve { Some(true) } else { None };
+
+		let new_app_id = sqlx::query_scalar::<_, RecordId>(
+			"INSERT INTO applications (event, player, approval) values ($1, $2, $3) returning id;",
+		)
+		.bind(event_id)
+		.bind(player_id)
+		.bind(approval)
+		.fetch_one(&self.pool)
+		.await?;
+
+		Ok(new_app_id)
+	}
+
+	async fn cancel_event(&self, event_id: Uuid) -> CoreResult {
+		sqlx::query("UPDATE events SET cancelled = true WHERE id = $1;")
+			.bind(event_id)
+			.execute(&self.pool)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn reopen_event(&self, event_id: Uuid) -> CoreResult {
+		sqlx::query("UPDATE events SET cancelled = false WHERE id = $1;")
+			.bind(event_id)
+			.execute(&self.pool)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn read_event_players(&self, event_id: Uuid) -> CoreResult<Vec<Uuid>> {
+		sqlx::query_scalar::<_, Uuid>(
+			"select player from applications where event = $1 and approval is distinct from false;",
+		)
+		.bind(event_id)
+		.fetch_all(&self.pool)
+		.await
+		.map_err(AppError::from)
+	}
+
+	async fn add_event(
+		&self,
+		data: &NewEventDto,
+		date: DateTime<FixedOffset>,
+		check: ScheduleCheck,
+	) -> CoreResult<Checked<RecordId>> {
+		let mut tx = self.pool.begin().await?;
+
+		lock_location(&mut *tx, data.location).await?;
+
+		let event_id = insert_event(
+			&mut *tx,
+			data.company,
+			&data.location,
+			date,
+			data.max_slots,
+			data.plan_duration,
+			data.visibility,
+			&data.venue,
+			None,
+		)
+		.await?;
+
+		let checks = check_events(&mut tx, check, &[event_id.uuid()]).await?;
+		if check.rejects(&checks) {
+			return Ok(Checked::Rejected(checks));
+		}
+
+		tx.commit().await?;
+
+		Ok(Checked::Saved(event_id, checks))
+	}
+
+	async fn update_event(
+		&self,
+		event_id: Uuid,
+		master: Uuid,
+		data: UpdateEventDto,
+		date: DateTime<FixedOffset>,
+		check: ScheduleCheck,
+	) -> CoreResult<Option<Checked<Vec<MovedEvent>>>> {
+		let mut tx = self.pool.begin().await?;
+
+		lock_location(&mut *tx, data.location).await?;
+
+		let updated = if data.scope == EditScope::Following {
+			// переносим все следующие игры серии на тот же сдвиг, что и выбранную
+			// сдвиг считается в местном времени серии, чтобы игры по другую сторону
+			// перехода на летнее время не уезжали на час
+			sqlx::query_as::<_, MovedEvent>(
+				"WITH target AS (
+					select e.series, e.date, s.timezone, s.utc_offset
+					from events e
+					inner join companies c
+						on e.company = c.id
+					inner join event_series s
+						on s.id = e.series
+					where e.id = $5
+					and has_company_permission(c.id, $6, 'events')
+				)
+				update events ev
+				SET
+					location = $1,
+					date = series_utc(
+						series_local(ev.date, target.timezone, target.utc_offset)
+							+ (series_local($2, target.timezone, target.utc_offset)
+								- series_local(target.date, target.timezone, target.utc_offset)),
+						target.timezone,
+						target.utc_offset
+					),
+					max_slots = $3,
+					plan_duration = $4,
+					visibility = COALESCE($7, ev.visibility),
+					venue_type = COALESCE($8, ev.venue_type),
+					online_platform = CASE
+						WHEN COALESCE($8, ev.venue_type) = 'offline' THEN NULL
+						WHEN $9 THEN $10
+						ELSE ev.online_platform
+					END,
+					join_link = CASE
+						WHEN COALESCE($8, ev.venue_type) = 'offline' THEN NULL
+						WHEN $11 THEN $12
+						ELSE ev.join_link
+					END
+				from target, events old
+				where ev.series = target.series
+				and ev.date >= target.date
+				and old.id = ev.id
+				returning ev.id as event_id, old.date as previous_date, ev.date, ev.cancelled
+					, array(
+						select a.player from applications a
+						where a.event = ev.id and a.approval is distinct from false
+					) as players;",
+			)
+			.bind(data.location)
+			.bind(date)
+			.bind(data.max_slots)
+			.bind(data.plan_duration)
+			.bind(event_id)
+			.bind(master)
+			.bind(data.visibility.map(Visibility::as_str))
+			.bind(data.venue_type.map(VenueType::as_str))
+			.bind(data.online_platform.is_some())
+			.bind(data.online_platform.flatten().map(OnlinePlatform::as_str))
+			.bind(data.join_link.is_some())
+			.bind(data.join_link.flatten())
+			.fetch_all(&mut *tx)
+			.await
+			.map_err(map_venue_error)?
+		} else {
+			sqlx::query_as::<_, MovedEvent>(
+				"update events ev
+				SET
+					location = $1,
+					date = $2,
+					max_slots = $3,
+					plan_duration = $4,
+					visibility = COALESCE($7, ev.visibility),
+					venue_type = COALESCE($8, ev.venue_type),
+					online_platform = CASE
+						WHEN COALESCE($8, ev.venue_type) = 'offline' THEN NULL
+						WHEN $9 THEN $10
+						ELSE ev.online_platform
+					END,
+					join_link = CASE
+						WHEN COALESCE($8, ev.venue_type) = 'offline' THEN NULL
+						WHEN $11 THEN $12
+						ELSE ev.join_link
+					END
+				from events old
+				where old.id = ev.id
+				and ev.id in (
+					select e.id
+					from events e
+					inner join companies c
+						on e.company = c.id
+					where e.id = $5
+					and has_company_permission(c.id, $6, 'events')
+				)
+				returning ev.id as event_id, old.date as previous_date, ev.date, ev.cancelled
+					, array(
+						select a.player from applications a
+						where a.event = ev.id and a.approval is distinct from false
+					) as players;",
+			)
+			.bind(data.location)
+			.bind(date)
+			.bind(data.max_slots)
+			.bind(data.plan_duration)
+			.bind(event_id)
+			.bind(master)
+			.bind(data.visibility.map(Visibility::as_str))
+			.bind(data.venue_type.map(VenueType::as_str))
+			.bind(data.online_platform.is_some())
+			.bind(data.online_platform.flatten().map(OnlinePlatform::as_str))
+			.bind(data.join_link.is_some())
+			.bind(data.join_link.flatten())
+			.fetch_optional(&mut *tx)
+			.await
+			.map_err(map_venue_error)?
+			.into_iter()
+			.collect()
+		};
+
+		if updated.is_empty() {
+			return Ok(None);
+		}
+
+		let ids = updated
+			.iter()
+			.map(|event| event.event_id)
+			.collect::<Vec<_>>();
+
+		let checks = check_events(&mut tx, check, &ids).await?;
+		if check.rejects(&checks) {
+			return Ok(Some(Checked::Rejected(checks)));
+		}
+
+		tx.commit().await?;
+
+		Ok(Some(Checked::Saved(updated, checks)))
+	}
+
+	async fn default_timezone(
+		&self,
+		location_id: Option<Uuid>,
+		user_id: Uuid,
+	) -> CoreResult<Option<String>> {
+		sqlx::query_scalar::<_, Option<String>>(
+			"select COALESCE(
+				(
+					select COALESCE(c.own_timezone, r.timezone)
+					from locations l
+					inner join cities c
+						on c.name = l.city
+					left join regions r
+						on r.name = c.region
+					where l.id = $1
+				),
+				user_timezone($2)
+			);",
+		)
+		.bind(location_id)
+		.bind(user_id)
+		.fetch_one(&self.pool)
+		.await
+		.map_err(AppError::from)
+	}
+
+	async fn local_to_utc(&self, locals: &[(NaiveDateTime, &str)]) -> CoreResult<Vec<ZonedDate>> {
+		let (dates, timezones): (Vec<_>, Vec<_>) = locals.iter().copied().unzip();
+
+		// переходы на летнее время учитывает сам Postgres, но время из промежутка
+		// перевода часов он молча сдвигает, а из повторяющегося часа выбирает один
+		// из вариантов. Такие даты помечаются: обратный перевод не даёт исходное
+		// время либо к нему же переводится соседний момент
+		sqlx::query_as::<_, ZonedDate>(
+			"select
+				d.local
+				, d.timezone
+				, z.date
+				, coalesce(z.date AT TIME ZONE tz.name <> d.local, false) AS in_gap
+				, exists (
+					select 1
+					from unnest(array[interval '30 minutes', interval '1 hour', interval '2 hours']) s
+					where (z.date + s) AT TIME ZONE tz.name = d.local
+					or (z.date - s) AT TIME ZONE tz.name = d.local
+				) AS ambiguous
+			from unnest($1::timestamp[], $2::text[]) with ordinality as d(local, timezone, n)
+			left join pg_timezone_names tz
+				on tz.name = d.timezone
+			cross join lateral (select d.local AT TIME ZONE tz.name AS date) z
+			order by d.n;",
+		)
+		.bind(dates)
+		.bind(timezones)
+		.fetch_all(&self.pool)
+		.await
+		.map_err(AppError::from)
+	}
+
+	async fn add_series(
+		&self,
+		data: &NewSeriesDto,
+		dates: &[DateTime<FixedOffset>],
+		timezone: Option<&str>,
+		check: ScheduleCheck,
+	) -> CoreResult<Checked<SeriesIds>> {
+		let mut tx = self.pool.begin().await?;
+
+		let series_id = sqlx::query_scalar::<_, Uuid>(
+			"INSERT INTO event_series (company, frequency, until, count, exceptions, timezone, utc_offset)
+			values ($1, $2, $3, $4, $5, $6, $7 * interval '1 second') returning id;",
+		)
+		.bind(data.company)
+		.bind(data.frequency.as_str())
+		.bind(data.until)
+		.bind(data.count.map(|c| i16::try_from(c).unwrap_or(i16::MAX)))
+		.bind(&data.exceptions)
+		.bind(timezone)
+		.bind(series_offset(dates))
+		.fetch_one(&mut *tx)
+		.await?;
+
+		lock_location(&mut *tx, data.location).await?;
+
+		let mut events = Vec::with_capacity(dates.len());
+
+		for date in dates {
+			let event_id = insert_event(
+				&mut *tx,
+				data.company,
+				&data.location,
+				*date,
+				data.max_slots,
+				data.plan_duration,
+				data.visibility,
+				&data.venue,
+				Some(series_id),
+			)
+			.await?;
+
+			events.push(event_id.uuid());
+		}
+
+		let checks = check_events(&mut tx, check, &events).await?;
+		if check.rejects(&checks) {
+			return Ok(Checked::Rejected(checks));
+		}
+
+		tx.commit().await?;
+
+		Ok(Checked::Saved(SeriesIds { series_id, events }, checks))
+	}
+
+	async fn import_events(
+		&self,
+		company_id: Uuid,
+		data: &ImportCalendarDto,
+		games: &[ImportedGameDto],
+		check: ScheduleCheck,
+	) -> CoreResult<Checked<Vec<Uuid>>> {
+		let mut tx = self.pool.begin().await?;
+
+		lock_location(&mut *tx, data.location).await?;
+
+		let mut events = Vec::new();
+
+		for game in games.iter().filter(|game| game.skipped.is_none()) {
+			// повторяющееся событие становится серией, как при создании через add_series
+			let series_id = match game.rule {
+				Some(ref rule) => Some(
+					sqlx::query_scalar::<_, Uuid>(
+						"INSERT INTO event_series (company, frequency, until, count, exceptions, timezone, utc_offset)
+						values ($1, $2, $3, $4, $5, $6, $7 * interval '1 second') returning id;",
+					)
+					.bind(company_id)
+					.bind(rule.frequency.as_str())
+					.bind(rule.until)
+					.bind(rule.count.map(|c| i16::try_from(c).unwrap_or(i16::MAX)))
+					.bind(&game.exceptions)
+					.bind(&game.timezone)
+					.bind(series_offset(&game.dates))
+					.fetch_one(&mut *tx)
+					.await?,
+				),
+				None => None,
+			};
+
+			for date in &game.dates {
+				let event_id = insert_event(
+					&mut *tx,
+					company_id,
+					&data.location,
+					*date,
+					data.max_slots,
+					game.plan_duration,
+					data.visibility,
+					&data.venue,
+					series_id,
+				)
+				.await?;
+
+				events.push(event_id.uuid());
+			}
+		}
+
+		let checks = check_events(&mut tx, check, &events).await?;
+		if check.rejects(&checks) {
+			return Ok(Checked::Rejected(checks));
+		}
+
+		tx.commit().await?;
+
+		Ok(Checked::Saved(events, checks))
+	}
+
+	async fn add_date_poll(
+		&self,
+		company_id: Uuid,
+		title: Option<&str>,
+		dates: &[DateTime<FixedOffset>],
+	) -> CoreResult<Uuid> {
+		let mut tx = self.pool.begin().await?;
+
+		let poll_id = sqlx::query_scalar::<_, Uuid>(
+			"INSERT INTO date_polls (company, title) values ($1, $2) returning id;",
+		)
+		.bind(company_id)
+		.bind(title)
+		.fetch_one(&mut *tx)
+		.await?;
+
+		sqlx::query(
+			"INSERT INTO date_poll_slots (poll, date)
+			select $1, d from unnest($2::timestamptz[]) d
+			on conflict do nothing;",
+		)
+		.bind(poll_id)
+		.bind(dates)
+		.execute(&mut *tx)
+		.await?;
+
+		tx.commit().await?;
+
+		Ok(poll_id)
+	}
+
+	async fn read_date_poll(&self, poll_id: Uuid, user_id: Uuid) -> CoreResult<Option<DatePoll>> {
+		// время вариантов переводится в пояс смотрящего, только если пояс известен postgres
+		sqlx::query_as::<_, DatePoll>(
+			"with viewer as (
+	select tz.name as timezone
+	from pg_timezone_names tz
+	where tz.name = user_timezone($2)
+)
+select
+	p.id
+	, c.id as company_id
+	, c.name as company_name
+	, p.title
+	, p.event as event_id
+	, (select timezone from viewer) as timezone
+	, has_company_permission(c.id, $2, 'events') as you_can_manage
+	, exists (
+		select 1 from company_members m
+		where m.company = c.id and m.user_id = $2
+	) as you_can_vote
+	, (
+		select COALESCE(
+			jsonb_agg(jsonb_build_object(
+				'id', s.id
+				, 'date', s.date
+				, 'local_date', s.date at time zone (select timezone from viewer)
+				, 'yes', (select count(*) from date_poll_votes v where v.slot = s.id and v.answer = 'yes')
+				, 'maybe', (select count(*) from date_poll_votes v where v.slot = s.id and v.answer = 'maybe')
+				, 'no', (select count(*) from date_poll_votes v where v.slot = s.id and v.answer = 'no')
+				, 'your_answer', (select v.answer from date_poll_votes v where v.slot = s.id and v.user_id = $2)
+			) ORDER BY s.date),
+			'[]'
+		)
+		from date_poll_slots s
+		where s.poll = p.id
+	) as slots
+from date_polls p
+inner join companies c
+	on c.id = p.company
+where p.id = $1;",
+		)
+		.bind(poll_id)
+		.bind(user_id)
+		.fetch_optional(&self.pool)
+		.await
+		.map_err(AppError::from)
+	}
+
+	async fn read_company_polls(&self, company_id: Uuid) -> CoreResult<Vec<ShortDatePoll>> {
+		sqlx::query_as::<_, ShortDatePoll>(
+			"select
+	p.id
+	, p.title
+	, p.event as event_id
+	, (select count(*) from date_poll_slots s where s.poll = p.id) as slots
+	, (
+		select count(distinct v.user_id)
+		from date_poll_slots s
+		inner join date_poll_votes v
+			on v.slot = s.id
+		where s.poll = p.id
+	) as voters
+from date_polls p
+where p.company = $1
+order by p.id desc;",
+		)
+		.bind(company_id)
+		.fetch_all(&self.pool)
+		.await
+		.map_err(AppError::from)
+	}
+
+	async fn vote_date_poll(
+		&self,
+		poll_id: Uuid,
+		user_id: Uuid,
+		votes: &[PollVoteDto],
+	) -> CoreResult<u64> {
+		let slots = votes.iter().map(|vote| vote.slot).collect::<Vec<_>>();
+		let answers = votes
+			.iter()
+			.map(|vote| vote.answer.as_str())
+			.collect::<Vec<_>>();
+
+		// голоса за варианты из чужих опросов молча отбрасываются
+		let res = sqlx::query(
+			"INSERT INTO date_poll_votes (slot, user_id, answer)
+			select s.id, $2, v.answer
+			from unnest($3::uuid[], $4::text[]) as v(slot, answer)
+			inner join date_poll_slots s
+				on s.id = v.slot
+				and s.poll = $1
+			on conflict (slot, user_id) do update set answer = excluded.answer;",
+		)
+		.bind(poll_id)
+		.bind(user_id)
+		.bind(slots)
+		.bind(answers)
+		.execute(&self.pool)
+		.await?;
+
+		Ok(res.rows_affected())
+	}
+
+	async fn convert_date_poll(
+		&self,
+		poll_id: Uuid,
+		data: &NewEventDto,
+		date: DateTime<FixedOffset>,
+		check: ScheduleCheck,
+	) -> CoreResult<Option<Checked<ConvertedPoll>>> {
+		let mut tx = self.pool.begin().await?;
+
+		// блокировка не даёт создать две игры по одному опросу
+		let is_open = sqlx::query_scalar::<_, bool>(
+			"select true from date_polls where id = $1 and event is null for update;",
+		)
+		.bind(poll_id)
+		.fetch_optional(&mut *tx)
+		.await?
+		.unwrap_or_default();
+
+		if !is_open {
+			return Ok(None);
+		}
 
-	async fn apply_event(
-		&self,
-		event_id: Uuid,
-		player_id: Uuid,
-		can_auto_approve: bool,
-	) -> CoreResult<RecordId> {
-		let approval = if can_auto_approve { Some(true) } else { None };
+		lock_location(&mut *tx, data.location).await?;
+
+		let event_id = insert_event(
+			&mut *tx,
+			data.company,
+			&data.location,
+			date,
+			data.max_slots,
+			data.plan_duration,
+			data.visibility,
+			&data.venue,
+			None,
+		)
+		.await?
+		.uuid();
 
-		let new_app_id = sqlx::query_scalar::<_, RecordId>(
-			"INSERT INTO applications (event, player, approval) values ($1, $2, $3) returning id;",
+		let voters = sqlx::query_scalar::<_, Uuid>(
+			"with closed as (
+				update date_polls set event = $2 where id = $1
+			)
+			select distinct v.user_id
+			from date_poll_slots s
+			inner join date_poll_votes v
+				on v.slot = s.id
+			where s.poll = $1;",
 		)
+		.bind(poll_id)
 		.bind(event_id)
-		.bind(player_id)
-		.bind(approval)
-		.fetch_one(&self.pool)
+		.fetch_all(&mut *tx)
 		.await?;
 
-		Ok(new_app_id)
-	}
-
-	async fn cancel_event(&self, event_id: Uuid) -> CoreResult {
-		sqlx::query("UPDATE events SET cancelled = true WHERE id = $1;")
-			.bind(event_id)
-			.execute(&self.pool)
-			.await?;
-
-		Ok(())
-	}
+		let checks = check_events(&mut tx, check, &[event_id]).await?;
+		if check.rejects(&checks) {
+			return Ok(Some(Checked::Rejected(checks)));
+		}
 
-	async fn reopen_event(&self, event_id: Uuid) -> CoreResult {
-		sqlx::query("UPDATE events SET cancelled = false WHERE id = $1;")
-			.bind(event_id)
-			.execute(&self.pool)
-			.await?;
+		tx.commit().await?;
 
-		Ok(())
+		Ok(Some(Checked::Saved(
+			ConvertedPoll { event_id, voters },
+			checks,
+		)))
 	}
 
-	async fn add_event(
+	async fn read_series(
 		&self,
-		company: Uuid,
-		location: &Option<Uuid>,
-		date: DateTime<FixedOffset>,
-		max_slots: Option<i16>,
-		plan_duration: Option<i16>,
-	) -> CoreResult<RecordId> {
-		let new_evt_id = sqlx::query_scalar::<_, RecordId>(
-			"INSERT INTO events (company, location, date, max_slots, plan_duration) values ($1, $2, $3, $4, $5) returning id;",
+		series_id: Uuid,
+		user_id: Option<Uuid>,
+	) -> CoreResult<Option<Series>> {
+		sqlx::query_as::<_, Series>(
+			"select
+	s.id
+	, s.company as company_id
+	, s.frequency
+	, s.until
+	, s.count
+	, s.exceptions
+	, s.timezone
+	, COALESCE(
+		jsonb_agg(jsonb_build_object('id', e.id, 'date', e.date, 'cancelled', e.cancelled) ORDER BY e.date)
+			FILTER (WHERE e.id is not null),
+		'[]'
+	) AS events
+from event_series s
+left join company_members mm
+	on mm.company = s.company
+	and mm.user_id = $2
+	and mm.status = 'active'
+-- приватные игры, как в read_event, видят ведущие, участники и подавшие заявку
+left join events e
+	on e.series = s.id
+	and (
+		e.visibility <> 'private'
+		or mm.user_id is not null
+		or ($2 is not null and has_company_permission(s.company, $2, 'events'))
+		or exists (select 1 from applications a where a.event = e.id and a.player = $2)
+	)
+where s.id = $1
+group by s.id;",
 		)
-		.bind(company)
-		.bind(location)
-		.bind(date)
-		.bind(max_slots)
-		.bind(plan_duration)
-		.fetch_one(&self.pool)
-		.await?;
-
-		Ok(new_evt_id)
+		.bind(series_id)
+		.bind(user_id)
+		.fetch_optional(&self.pool)
+		.await
+		.map_err(AppError::from)
 	}
 
-	async fn update_event(
+	async fn add_series_exceptions(
 		&self,
-		event_id: Uuid,
-		master: Uuid,
-		data: UpdateEventDto,
-	) -> CoreResult<bool> {
-		let was_updated = sqlx::query_scalar::<_, bool>(
-			"update events
-			SET
-				location = $1,
-				date = $2,
-				max_slots = $3,
-				plan_duration = $4
-			where id in (
-				select e.id
-				from events e
-				inner join companies c
-					on e.company = c.id
-				where e.id = $5
-				and c.master = $6
+		series_id: Uuid,
+		user_id: Uuid,
+		dates: &[NaiveDate],
+	) -> CoreResult<Option<Vec<CancelledSeriesEvent>>> {
+		let mut tx = self.pool.begin().await?;
+
+		let updated = sqlx::query_scalar::<_, bool>(
+			"update event_series s
+			set exceptions = array(
+				select distinct d from unnest(s.exceptions || $3::date[]) d order by d
 			)
+			where s.id = $1
+			and has_company_permission(s.company, $2, 'events')
 			returning true;",
 		)
-		.bind(data.location)
-		.bind(data.date)
-		.bind(data.max_slots)
-		.bind(data.plan_duration)
-		.bind(event_id)
-		.bind(master)
-		.fetch_optional(&self.pool)
-		.await?
-		.unwrap_or_default();
+		.bind(series_id)
+		.bind(user_id)
+		.bind(dates)
+		.fetch_optional(&mut *tx)
+		.await?;
 
-		Ok(was_updated)
+		if updated.is_none() {
+			return Ok(None);
+		}
+
+		// даты исключений местные, как и при создании серии
+		let cancelled = sqlx::query_as::<_, CancelledSeriesEvent>(
+			"update events e
+			set cancelled = true
+			from event_series s, companies c
+			where e.series = $1
+			and s.id = e.series
+			and c.id = e.company
+			and not e.cancelled
+			and series_local(e.date, s.timezone, s.utc_offset)::date = any($2)
+			returning
+				e.id
+				, e.date
+				, c.name as company_name
+				, array(
+					select a.player from applications a
+					where a.event = e.id and a.approval is distinct from false
+				) as players;",
+		)
+		.bind(series_id)
+		.bind(dates)
+		.fetch_all(&mut *tx)
+		.await?;
+
+		tx.commit().await?;
+
+		Ok(Some(cancelled))
 	}
 
-	async fn read_player_apps_list(&self, player_id: Uuid) -> CoreResult<Vec<PlayerApp>> {
-		sqlx::query_as::<_, PlayerApp>(
+	async fn read_player_apps_list(
+		&self,
+		player_id: Uuid,
+		page: &PageDto,
+	) -> CoreResult<Page<PlayerApp>> {
+		let mut qb: QueryBuilder<'_, Postgres> = QueryBuilder::new(
 			"select
 	a.id
 	, e.id as event_id
@@ -982,18 +2584,33 @@ inner join events e
 	on e.id = a.event
 inner join companies c
 	on c.id = e.company
-inner join locations l
+left join locations l
 	on l.id = e.location
 inner join users m
 	on m.id = c.master
-where a.player = $1
-and e.date > CURRENT_TIMESTAMP
-order by e.date asc;",
-		)
-		.bind(player_id)
-		.fetch_all(&self.pool)
-		.await
-		.map_err(AppError::from)
+where a.player = ",
+		);
+		qb.push_bind(player_id);
+		qb.push(" and e.date > CURRENT_TIMESTAMP");
+
+		let keyset = Keyset {
+			rank: None,
+			key: date_or_created(page, "e.date")?,
+			id: "a.id",
+		};
+		push_keyset(&mut qb, &keyset, page)?;
+
+		let apps = qb
+			.build_query_as::<PlayerApp>()
+			.fetch_all(&self.pool)
+			.await?;
+
+		Ok(into_page(apps, page, |app| Cursor {
+			id: app.id,
+			date: Some(app.event_date),
+			name: None,
+			rank: None,
+		}))
 	}
 
 	async fn read_player_app(&self, player_id: Uuid, app_id: Uuid) -> CoreResult<Option<PlayerApp>> {
@@ -1015,7 +2632,7 @@ inner join events e
 	on e.id = a.event
 inner join companies c
 	on c.id = e.company
-inner join locations l
+left join locations l
 	on l.id = e.location
 inner join users m
 	on m.id = c.master
@@ -1052,7 +2669,7 @@ inner join events e
 	on e.id = a.event
 inner join companies c
 	on c.id = e.company
-inner join locations l
+left join locations l
 	on l.id = e.location
 inner join users m
 	on m.id = c.master
@@ -1097,7 +2714,7 @@ INNER JOIN events e
 	ON e.id = a.event
 INNER JOIN companies c
 	ON c.id = e.company
-INNER JOIN locations l
+LEFT JOIN locations l
 	ON l.id = e.location
 INNER JOIN users m
 	ON m.id = c.master
@@ -1111,8 +2728,12 @@ AND a.player = $2;",
 		.map_err(AppError::from)
 	}
 
-	async fn read_master_apps_list(&self, master_id: Uuid) -> CoreResult<Vec<MasterApp>> {
-		sqlx::query_as::<_, MasterApp>(
+	async fn read_master_apps_list(
+		&self,
+		master_id: Uuid,
+		page: &PageDto,
+	) -> CoreResult<Page<MasterApp>> {
+		let mut qb: QueryBuilder<'_, Postgres> = QueryBuilder::new(
 			"select
 	a.id
 	, e.id as event_id
@@ -1130,20 +2751,33 @@ inner join events e
 	on e.id = a.event
 inner join companies c
 	on c.id = e.company
-inner join locations l
+left join locations l
 	on l.id = e.location
-inner join users m
-	on m.id = c.master
 inner join users p
 	on p.id = a.player
-where m.id = $1
-and e.date > CURRENT_TIMESTAMP
-order by e.date, a.id asc;",
-		)
-		.bind(master_id)
-		.fetch_all(&self.pool)
-		.await
-		.map_err(AppError::from)
+where has_company_permission(c.id, ",
+		);
+		qb.push_bind(master_id);
+		qb.push(", 'apps') and e.date > CURRENT_TIMESTAMP");
+
+		let keyset = Keyset {
+			rank: None,
+			key: date_or_created(page, "e.date")?,
+			id: "a.id",
+		};
+		push_keyset(&mut qb, &keyset, page)?;
+
+		let apps = qb
+			.build_query_as::<MasterApp>()
+			.fetch_all(&self.pool)
+			.await?;
+
+		Ok(into_page(apps, page, |app| Cursor {
+			id: app.id,
+			date: Some(app.event_date),
+			name: None,
+			rank: None,
+		}))
 	}
 
 	async fn read_master_apps_list_by_event(
@@ -1169,13 +2803,11 @@ inner join events e
 	on e.id = a.event
 inner join companies c
 	on c.id = e.company
-inner join locations l
+left join locations l
 	on l.id = e.location
-inner join users m
-	on m.id = c.master
 inner join users p
 	on p.id = a.player
-where m.id = $1
+where has_company_permission(c.id, $1, 'apps')
 and e.id = $2
 order by a.id asc;",
 		)
@@ -1217,13 +2849,11 @@ INNER JOIN events e
 	ON e.id = a.event
 INNER JOIN companies c
 	ON c.id = e.company
-INNER JOIN locations l
+LEFT JOIN locations l
 	ON l.id = e.location
-INNER JOIN users m
-	ON m.id = c.master
 INNER JOIN users p
 	ON p.id = a.player
-WHERE m.id = $1
+WHERE has_company_permission(c.id, $1, 'apps')
 AND e.id = (SELECT id FROM nearest_event)
 ORDER BY a.id asc;",
 		)
@@ -1253,14 +2883,12 @@ inner join events e
 	on e.id = a.event
 inner join companies c
 	on c.id = e.company
-inner join locations l
+left join locations l
 	on l.id = e.location
-inner join users m
-	on m.id = c.master
 inner join users p
 	on p.id = a.player
 where a.id = $1
-and m.id = $2;",
+and has_company_permission(c.id, $2, 'apps');",
 		)
 		.bind(app_id)
 		.bind(master_id)
@@ -1276,6 +2904,9 @@ and m.id = $2;",
 	) -> CoreResult<Option<AppForApproval>> {
 		sqlx::query_as::<_, AppForApproval>(
 			"select
+	e.id as event_id,
+	c.name as company_name,
+	a.player as player_id,
 	e.date as event_date,
 	e.cancelled as event_cancelled,
 	a.approval
@@ -1284,10 +2915,8 @@ inner join events e
 	on e.id = a.event
 inner join companies c
 	on c.id = e.company
-inner join users m
-	on m.id = c.master
 where a.id = $1
-and m.id = $2;",
+and has_company_permission(c.id, $2, 'apps');",
 		)
 		.bind(app_id)
 		.bind(master_id)
@@ -1359,7 +2988,681 @@ and m.id = $2;",
 		Ok(())
 	}
 
+	async fn is_admin(&self, user_id: Uuid) -> CoreResult<bool> {
+		let is_admin =
+			sqlx::query_scalar::<_, bool>("select role = 'admin' from users where id = $1;")
+				.bind(user_id)
+				.fetch_optional(&self.pool)
+				.await?
+				.unwrap_or_default();
+
+		Ok(is_admin)
+	}
+
+	async fn set_user_role(&self, user_id: Uuid, role: &str) -> CoreResult<Option<User>> {
+		sqlx::query_as::<_, User>(
+			"update users set role = $1 where id = $2 returning id, nickname, email;",
+		)
+		.bind(role)
+		.bind(user_id)
+		.fetch_optional(&self.pool)
+		.await
+		.map_err(AppError::from)
+	}
+
+	async fn merge_locations(
+		&self,
+		duplicate: Uuid,
+		canonical: Uuid,
+	) -> CoreResult<Option<MergeStats>> {
+		let mut tx = self.pool.begin().await?;
+
+		let found =
+			sqlx::query_scalar::<_, i64>("select count(*) from locations where id = $1 or id = $2;")
+				.bind(duplicate)
+				.bind(canonical)
+				.fetch_one(&mut *tx)
+				.await?;
+
+		if found < 2 {
+			return Ok(None);
+		}
+
+		let events = sqlx::query("update events set location = $1 where location = $2;")
+			.bind(canonical)
+			.bind(duplicate)
+			.execute(&mut *tx)
+			.await?
+			.rows_affected();
+
+		sqlx::query("delete from locations where id = $1;")
+			.bind(duplicate)
+			.execute(&mut *tx)
+			.await?;
+
+		tx.commit().await?;
+
+		Ok(Some(MergeStats {
+			events,
+			..Default::default()
+		}))
+	}
+
+	async fn merge_cities(
+		&self,
+		duplicate: &str,
+		canonical: &str,
+	) -> CoreResult<Option<MergeStats>> {
+		let mut tx = self.pool.begin().await?;
+
+		let found =
+			sqlx::query_scalar::<_, i64>("select count(*) from cities where name = $1 or name = $2;")
+				.bind(duplicate)
+				.bind(canonical)
+				.fetch_one(&mut *tx)
+				.await?;
+
+		if found < 2 {
+			return Ok(None);
+		}
+
+		let users = sqlx::query("update users set city = $1 where city = $2;")
+			.bind(canonical)
+			.bind(duplicate)
+			.execute(&mut *tx)
+			.await?
+			.rows_affected();
+
+		let locations = sqlx::query("update locations set city = $1 where city = $2;")
+			.bind(canonical)
+			.bind(duplicate)
+			.execute(&mut *tx)
+			.await?
+			.rows_affected();
+
+		sqlx::query("delete from cities where name = $1;")
+			.bind(duplicate)
+			.execute(&mut *tx)
+			.await?;
+
+		tx.commit().await?;
+
+		Ok(Some(MergeStats {
+			users,
+			locations,
+			..Default::default()
+		}))
+	}
+
 	async fn close(&self) {
 		self.pool.close().await;
 	}
 }
+
+/// Сохраняет события SSE и публикует их id. NOTIFY доходит до всех экземпляров
+/// сервера, включая этот. В нём только id: тело события может не поместиться
+/// в 8000 байт, поэтому его читают из таблицы. `None` - событие для всех подключенных
+async fn insert_sse_events<'e>(
+	executor: impl PgExecutor<'e>,
+	users: &[Option<Uuid>],
+	event: &str,
+	data: &[String],
+) -> CoreResult {
+	sqlx::query(
+		"with expired as (
+			delete from sse_events where created_at < now() - interval '1 hour'
+		), inserted as (
+			insert into sse_events (user_id, event, data)
+			select m.user_id, $3, m.data
+			from unnest($2::uuid[], $4::text[]) with ordinality as m(user_id, data, n)
+			order by m.n
+			returning id
+		)
+		select pg_notify($1, inserted.id::text) from inserted order by inserted.id;",
+	)
+	.bind(listener::SSE_CHANNEL)
+	.bind(users)
+	.bind(event)
+	.bind(data)
+	.execute(executor)
+	.await?;
+
+	Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn insert_company<'e>(
+	executor: impl PgExecutor<'e>,
+	master: Uuid,
+	name: &str,
+	system: &str,
+	descr: &Option<String>,
+	cover_link: &Option<String>,
+	event_style: &Option<String>,
+	one_shot: bool,
+	visibility: Visibility,
+) -> CoreResult<RecordId> {
+	let new_comp_id = sqlx::query_scalar::<_, RecordId>(
+		"INSERT INTO companies
+		(master, name, system, description, cover_link, event_style, one_shot, visibility)
+		values ($1, $2, $3, $4, $5, $6, $7, $8)
+		returning id;",
+	)
+	.bind(master)
+	.bind(name)
+	.bind(system)
+	.bind(descr)
+	.bind(cover_link)
+	.bind(event_style)
+	.bind(one_shot)
+	.bind(visibility.as_str())
+	.fetch_one(executor)
+	.await?;
+
+	Ok(new_comp_id)
+}
+
+/// Игры пользователя, пересекающиеся с играми `events`. Сами `events`
+/// друг с другом не сравниваются.
+/// Отклонённые заявки и отменённые игры времени не занимают
+async fn select_conflicts<'e>(
+	executor: impl PgExecutor<'e>,
+	user_id: Uuid,
+	events: &[Uuid],
+) -> CoreResult<Vec<TargetConflict>> {
+	sqlx::query_as::<_, TargetConflict>(
+		"with target as (
+	select
+		e.id
+		, e.date as start
+		, e.date + make_interval(hours => COALESCE(e.plan_duration, $3)) as end
+	from events e
+	where e.id = any($2)
+)
+select target.id as target, t.*
+from (
+	select
+		e.id as event_id
+		, c.name as company_name
+		, e.date
+		, e.date + make_interval(hours => COALESCE(e.plan_duration, $3)) as end
+		, 'player' as role
+		, a.approval
+	from applications a
+	inner join events e
+		on e.id = a.event
+	inner join companies c
+		on c.id = e.company
+	where a.player = $1
+		and a.approval is not false
+		and not e.cancelled
+	union all
+	select
+		e.id as event_id
+		, c.name as company_name
+		, e.date
+		, e.date + make_interval(hours => COALESCE(e.plan_duration, $3)) as end
+		, 'master' as role
+		, null::bool as approval
+	from events e
+	inner join companies c
+		on c.id = e.company
+	where c.master = $1
+		and not e.cancelled
+) t
+inner join target
+	on t.date < target.end
+	and t.end > target.start
+where not t.event_id = any($2)
+order by target.start, target.id, t.date;",
+	)
+	.bind(user_id)
+	.bind(events)
+	.bind(i32::from(DEFAULT_DURATION_HOURS))
+	.fetch_all(executor)
+	.await
+	.map_err(AppError::from)
+}
+
+/// Загрузка локаций записываемых игр `events`. Часы работы сравниваются
+/// с местным временем игры в поясе города локации; окно может начаться
+/// накануне и закончиться после полуночи
+async fn select_location_loads<'e>(
+	executor: impl PgExecutor<'e>,
+	events: &[Uuid],
+) -> CoreResult<Vec<TargetLoad>> {
+	sqlx::query_as::<_, TargetLoad>(
+		"with target as (
+	select
+		e.id
+		, e.location
+		, e.date as start
+		, e.date + make_interval(hours => COALESCE(e.plan_duration, $2)) as end
+	from events e
+	where e.id = any($1)
+		and e.location is not null
+),
+local_target as (
+	select t.id, t.start at time zone zone.name as start, t.end at time zone zone.name as end
+	from target t
+	cross join lateral (
+		select COALESCE(
+			(
+				select z.name
+				from locations l
+				inner join cities c
+					on c.name = l.city
+				left join regions r
+					on r.name = c.region
+				inner join pg_timezone_names z
+					on z.name = COALESCE(c.own_timezone, r.timezone)
+				where l.id = t.location
+			),
+			'UTC'
+		) as name
+	) zone
+)
+select
+	t.id as target
+	, l.tables
+	, COALESCE(
+		(
+			select array_agg(e.id ORDER BY e.date)
+			from events e
+			where e.location = l.id
+				and not e.cancelled
+				and not e.id = any($1)
+				and e.date < t.end
+				and e.date + make_interval(hours => COALESCE(e.plan_duration, $2)) > t.start
+		),
+		'{}'
+	) as concurrent
+	, exists (select 1 from location_hours h where h.location = l.id) as has_hours
+	, exists (
+		select 1
+		from location_hours h
+		cross join lateral (
+			values (lt.start::date - 1), (lt.start::date)
+		) as d(day)
+		where h.location = l.id
+			and extract(isodow from d.day) = h.weekday
+			and d.day + h.open_time <= lt.start
+			and lt.end <= d.day + h.close_time
+				+ case when h.close_time < h.open_time then interval '1 day' else interval '0' end
+	) as within_hours
+from target t
+inner join local_target lt
+	on lt.id = t.id
+inner join locations l
+	on l.id = t.location
+order by t.start, t.id;",
+	)
+	.bind(events)
+	.bind(i32::from(DEFAULT_DURATION_HOURS))
+	.fetch_all(executor)
+	.await
+	.map_err(AppError::from)
+}
+
+/// Блокирует локацию до конца транзакции, чтобы параллельные записи игр
+/// на одну локацию проверяли её загрузку по очереди
+async fn lock_location<'e>(executor: impl PgExecutor<'e>, location_id: Option<Uuid>) -> CoreResult {
+	let Some(location_id) = location_id else {
+		return Ok(());
+	};
+
+	sqlx::query("select 1 from locations where id = $1 for update;")
+		.bind(location_id)
+		.execute(executor)
+		.await?;
+
+	Ok(())
+}
+
+/// Проверяет расписание и загрузку локаций игр, записанных в транзакции,
+/// и возвращает игры, которым что-то мешает. Если проверка отклоняет запись,
+/// транзакция не фиксируется и откатывается при выходе
+async fn check_events(
+	conn: &mut PgConnection,
+	check: ScheduleCheck,
+	events: &[Uuid],
+) -> CoreResult<Vec<EventCheck>> {
+	let conflicts = select_conflicts(&mut *conn, check.user_id, events).await?;
+	let loads = select_location_loads(&mut *conn, events).await?;
+
+	let mut checks = events
+		.iter()
+		.map(|event_id| EventCheck {
+			event_id: *event_id,
+			conflicts: Vec::new(),
+			location_issues: Vec::new(),
+		})
+		.collect::<Vec<_>>();
+
+	for TargetConflict { target, conflict } in conflicts {
+		if let Some(check) = checks.iter_mut().find(|check| check.event_id == target) {
+			check.conflicts.push(conflict);
+		}
+	}
+
+	for TargetLoad { target, load } in loads {
+		if let Some(check) = checks.iter_mut().find(|check| check.event_id == target) {
+			check.location_issues = scheduling::location_issues(load);
+		}
+	}
+
+	checks.retain(|check| !check.conflicts.is_empty() || !check.location_issues.is_empty());
+
+	Ok(checks)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn insert_event<'e>(
+	executor: impl PgExecutor<'e>,
+	company: Uuid,
+	location: &Option<Uuid>,
+	date: DateTime<FixedOffset>,
+	max_slots: Option<i16>,
+	plan_duration: Option<i16>,
+	visibility: Visibility,
+	venue: &VenueDto,
+	series: Option<Uuid>,
+) -> CoreResult<RecordId> {
+	// постоянные участники кампании сразу получают одобренные заявки в пределах
+	// max_slots, заявки сверх лимита остаются на рассмотрении у ведущего
+	let new_evt_id = sqlx::query_scalar::<_, RecordId>(
+		"WITH new_event AS (
+			INSERT INTO events (
+				company, location, date, max_slots, plan_duration, series, visibility
+				, venue_type, online_platform, join_link
+			)
+			values ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+			returning id, company, max_slots
+		),
+		member_apps AS (
+			INSERT INTO applications (event, player, approval)
+			select
+				ne.id
+				, m.user_id
+				, CASE
+					WHEN ne.max_slots is null
+						OR row_number() over (order by m.user_id) <= ne.max_slots
+					THEN true
+				END
+			from new_event ne
+			inner join company_members m
+				on m.company = ne.company
+				and m.status = 'active'
+				and m.role = 'player'
+		)
+		select id from new_event;",
+	)
+	.bind(company)
+	.bind(location)
+	.bind(date)
+	.bind(max_slots)
+	.bind(plan_duration)
+	.bind(series)
+	.bind(visibility.as_str())
+	.bind(venue.venue_type.as_str())
+	.bind(venue.online_platform.map(OnlinePlatform::as_str))
+	.bind(&venue.join_link)
+	.fetch_one(executor)
+	.await?;
+
+	Ok(new_evt_id)
+}
+
+async fn insert_location_hours<'e>(
+	executor: impl PgExecutor<'e>,
+	location_id: Uuid,
+	hours: &[WeeklyWindowDto],
+) -> CoreResult {
+	let weekdays = hours
+		.iter()
+		.map(|window| i16::from(window.weekday))
+		.collect::<Vec<_>>();
+	let opens = hours.iter().map(|window| window.from).collect::<Vec<_>>();
+	let closes = hours.iter().map(|window| window.to).collect::<Vec<_>>();
+
+	sqlx::query(
+		"INSERT INTO location_hours (location, weekday, open_time, close_time)
+		select $1, w.weekday, w.open_time, w.close_time
+		from unnest($2::smallint[], $3::time[], $4::time[]) as w(weekday, open_time, close_time)
+		on conflict do nothing;",
+	)
+	.bind(location_id)
+	.bind(weekdays)
+	.bind(opens)
+	.bind(closes)
+	.execute(executor)
+	.await?;
+
+	Ok(())
+}
+
+/// Смещение серии в секундах. Без имени пояса все игры серии идут с одним смещением
+fn series_offset(dates: &[DateTime<FixedOffset>]) -> i32 {
+	dates
+		.first()
+		.map_or(0, |date| date.offset().local_minus_utc())
+}
+
+fn map_venue_error(err: SqlxError) -> AppError {
+	let err_str = err.to_string();
+	if err_str.contains("venue_consistency_check") {
+		AppError::scenario_error(
+			"У онлайн-игры не может быть локации, а у офлайн-игры - платформы и ссылки",
+			None::<&str>,
+		)
+	} else {
+		AppError::system_error(err_str)
+	}
+}
+
+/// Общая часть выборки событий: соединения и фильтры `ReadEventsDto`.
+/// Локация присоединяется всегда, поэтому в списке полей доступен алиас `l`
+fn push_events_filters(
+	qb: &mut QueryBuilder<'_, Postgres>,
+	query_args: ReadEventsDto,
+	player_id: Option<Uuid>,
+) {
+	qb.push(
+		" FROM events e
+			INNER JOIN companies c
+				ON c.id = e.company",
+	);
+
+	if !query_args.company.is_empty() {
+		qb.push(" AND e.company = ANY(");
+		qb.push_bind(query_args.company);
+		qb.push(')');
+	}
+
+	if query_args.location.is_some() || query_args.region.is_some() || query_args.city.is_some() {
+		qb.push(" INNER JOIN locations l ON l.id = e.location");
+	} else {
+		qb.push(" LEFT JOIN locations l ON l.id = e.location");
+	}
+
+	if let Some(location_id) = query_args.location {
+		qb.push(" AND l.id = ");
+		qb.push_bind(location_id);
+	}
+
+	if query_args.region.is_some() || query_args.city.is_some() {
+		qb.push(" INNER JOIN cities ci ON ci.name = l.city");
+	}
+
+	if let Some(city) = query_args.city {
+		qb.push(" AND ci.name = ");
+		qb.push_bind(city);
+	}
+
+	if let Some(region) = query_args.region {
+		qb.push(" INNER JOIN regions r ON r.name = ci.region AND r.name = ");
+		qb.push_bind(region);
+	}
+
+	qb.push(" INNER JOIN users m ON m.id = c.master");
+
+	if let Some(master_id) = query_args.master {
+		qb.push(" AND m.id = ");
+		qb.push_bind(master_id);
+	} else if let Some(imamaster) = query_args.imamaster
+		&& let Some(my_id) = player_id
+	{
+		match imamaster {
+			true => qb.push(" AND has_company_permission(c.id, "),
+			false => qb.push(" AND NOT has_company_permission(c.id, "),
+		};
+		qb.push_bind(my_id);
+		qb.push(", 'events')");
+	}
+
+	qb.push(" LEFT JOIN applications y ON y.player = ");
+	qb.push_bind(player_id);
+
+	qb.push(
+		" and y.event = e.id
+			LEFT JOIN company_members mm
+				ON mm.company = c.id
+				AND mm.status = 'active'
+				AND mm.user_id = ",
+	);
+	qb.push_bind(player_id);
+
+	qb.push(" WHERE e.date >= ");
+	qb.push_bind(query_args.date_from);
+
+	// скрытые события видны только мастеру, участникам кампании и записавшимся
+	qb.push(
+		" AND ((c.visibility = 'public' AND e.visibility = 'public')
+			OR mm.user_id is not null
+			OR y.id is not null
+			OR c.master = ",
+	);
+	qb.push_bind(player_id);
+	qb.push(')');
+
+	qb.push(" AND e.date <= ");
+	qb.push_bind(query_args.date_to);
+
+	if let Some(applied) = query_args.applied {
+		match applied {
+			true => qb.push(" AND y.id is not null"),
+			false => qb.push(" AND y.id is null"),
+		};
+	}
+
+	if let Some(not_rejected) = query_args.not_rejected {
+		match not_rejected {
+			true => qb.push(" AND (y.approval is null OR y.approval = true)"),
+			false => qb.push(" AND y.approval = false"),
+		};
+	}
+}
+
+/// Ключ сортировки страницы; `Created` сортирует только по id
+enum SortKey<'a> {
+	Created,
+	Date(&'a str),
+	Name(&'a str),
+}
+
+/// Постраничная выборка по ключу `(ранг, поле сортировки, id)`. Ранг поиска
+/// всегда идёт по возрастанию, остальные поля - в выбранном направлении
+struct Keyset<'a> {
+	rank: Option<&'a str>,
+	key: SortKey<'a>,
+	id: &'a str,
+}
+
+fn unsupported_sort<T>() -> CoreResult<T> {
+	AppError::scenario_error("Сортировка по этому полю недоступна", None::<&str>).into()
+}
+
+fn date_or_created<'a>(page: &PageDto, date_column: &'a str) -> CoreResult<SortKey<'a>> {
+	match page.sort.unwrap_or(SortField::Date) {
+		SortField::Date => Ok(SortKey::Date(date_column)),
+		SortField::Created => Ok(SortKey::Created),
+		SortField::Name => unsupported_sort(),
+	}
+}
+
+/// Дописывает к запросу (с уже открытым WHERE) условие курсора, порядок и лимит.
+/// Выбирается на одну строку больше, чтобы понять, есть ли следующая страница
+fn push_keyset(
+	qb: &mut QueryBuilder<'_, Postgres>,
+	keyset: &Keyset<'_>,
+	page: &PageDto,
+) -> CoreResult {
+	let (op, direction) = match page.direction {
+		SortDirection::Asc => (">", "ASC"),
+		SortDirection::Desc => ("<", "DESC"),
+	};
+
+	if let Some(ref cursor) = page.cursor {
+		let cursor = Cursor::decode(cursor)?;
+		let invalid = || AppError::scenario_error("Некорректный курсор страницы", None::<&str>);
+
+		qb.push(" AND (");
+
+		if let Some(rank) = keyset.rank {
+			let cursor_rank = cursor.rank.ok_or_else(invalid)?;
+			qb.push(format!("{rank} > "));
+			qb.push_bind(cursor_rank);
+			qb.push(format!(" OR {rank} = "));
+			qb.push_bind(cursor_rank);
+			qb.push(" AND ");
+		}
+
+		match keyset.key {
+			SortKey::Created => {
+				qb.push(format!("{} {op} ", keyset.id));
+			}
+			SortKey::Date(column) => {
+				qb.push(format!("({column}, {}) {op} (", keyset.id));
+				qb.push_bind(cursor.date.ok_or_else(invalid)?);
+				qb.push(", ");
+			}
+			SortKey::Name(column) => {
+				qb.push(format!("({column}, {}) {op} (", keyset.id));
+				qb.push_bind(cursor.name.ok_or_else(invalid)?);
+				qb.push(", ");
+			}
+		}
+		qb.push_bind(cursor.id);
+
+		match keyset.key {
+			SortKey::Created => qb.push(')'),
+			SortKey::Date(_) | SortKey::Name(_) => qb.push("))"),
+		};
+	}
+
+	qb.push(" ORDER BY ");
+	if let Some(rank) = keyset.rank {
+		qb.push(format!("{rank} ASC, "));
+	}
+	if let SortKey::Date(column) | SortKey::Name(column) = keyset.key {
+		qb.push(format!("{column} {direction}, "));
+	}
+	qb.push(format!("{} {direction} LIMIT ", keyset.id));
+	qb.push_bind(i64::from(page.limit()) + 1);
+
+	Ok(())
+}
+
+fn into_page<T>(mut items: Vec<T>, page: &PageDto, cursor: impl Fn(&T) -> Cursor) -> Page<T> {
+	let limit = usize::from(page.limit());
+
+	let next_cursor = match items.len() > limit {
+		true => {
+			items.truncate(limit);
+			items.last().map(|item| cursor(item).encode())
+		}
+		false => None,
+	};
+
+	Page { items, next_cursor }
+}
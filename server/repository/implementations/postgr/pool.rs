@@ -16,5 +16,18 @@ pub(super) async fn create_db_connection() -> Result<PgPool, Box<dyn Error>> {
 	migrator.run(&pool).await?;
 	println!(":) Migrations finished");
 
+	if let Some(email) = config::get_admin_email() {
+		let granted = sqlx::query("update users set role = 'admin' where email = $1;")
+			.bind(email.trim())
+			.execute(&pool)
+			.await?
+			.rows_affected();
+
+		match granted {
+			0 => eprintln!("ADMIN_EMAIL user is not registered, the admin role is not granted"),
+			_ => println!(":) Admin role is granted to ADMIN_EMAIL user"),
+		}
+	}
+
 	return Ok(pool);
 }
@@ -2,11 +2,16 @@ mod implementations;
 pub(crate) mod models;
 
 use ::std::error::Error;
-use chrono::{DateTime, FixedOffset};
-use implementations::PostgresStore;
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, Utc};
+pub(crate) use implementations::SseFeed;
+use implementations::{PostgresStore, SseListener};
 use models::{
-	AppForApproval, City, Company, CompanyInfo, Event, EventForApplying, Location, MasterApp,
-	PlayerApp, Profile, Region, ShortEvent, ShortProfile, UserForAuthEmail, UserPair,
+	AppForApproval, CalendarEvent, CancelledSeriesEvent, Checked, City, Company, CompanyInfo,
+	CompanyMember, ConvertedPoll, DatePoll, Event, EventForApplying, Location, LocationLink,
+	MasterApp, MergeStats, MovedEvent, NearbyEvent, NearbyLocation, Notification, OneShotIds, Page,
+	PlayerApp, Profile, Region, ScheduleCheck, ScheduleConflict, SearchHit, Series, SeriesIds,
+	ShortDatePoll, ShortEvent, ShortProfile, TelegramChat, TransferParties, User, UserForAuthEmail,
+	UserInterval, UserPair, WeeklyWindow, ZonedDate,
 };
 use uuid::Uuid;
 
@@ -14,10 +19,23 @@ use crate::{
 	auth,
 	dto::{
 		auth::{TouchSearch, UpdateProfileDto},
-		company::{ApiUpdateCompanyDto, ReadCompaniesDto},
-		event::{ReadEventsDto, UpdateEventDto},
-		location::ReadLocationDto,
+		company::{
+			ApiCompanyDto, ApiUpdateCompanyDto, CompanyPermission, MemberRole, NewOneShotDto,
+			ReadCompaniesDto,
+		},
+		event::{
+			ImportCalendarDto, ImportedGameDto, NewEventDto, NewSeriesDto, ReadEventsDto,
+			UpdateEventDto,
+		},
+		location::{LocationCapacityDto, NearDto, NewLocationDto, ReadLocationDto},
+		notification::ReadNotificationsDto,
+		page::PageDto,
+		poll::PollVoteDto,
+		schedule::WeeklyWindowDto,
+		search::SearchDto,
 	},
+	geo::Coordinates,
+	notification::NotificationPayload,
 	shared::RecordId,
 	system_models::CoreResult,
 };
@@ -47,20 +65,84 @@ trait Store {
 	async fn update_profile(&self, user_id: Uuid, profile: UpdateProfileDto) -> CoreResult;
 	async fn get_avatar_link(&self, user_id: Uuid) -> CoreResult<Option<String>>;
 	async fn set_avatar(&self, user_id: Uuid, url: &str) -> CoreResult;
+	async fn read_availability(&self, user_id: Uuid) -> CoreResult<Vec<WeeklyWindow>>;
+	async fn set_availability(&self, user_id: Uuid, windows: &[WeeklyWindowDto]) -> CoreResult;
+	async fn read_free_intervals(
+		&self,
+		users: &[Uuid],
+		from: DateTime<Utc>,
+		to: DateTime<Utc>,
+	) -> CoreResult<Vec<UserInterval>>;
+	async fn read_busy_intervals(
+		&self,
+		users: &[Uuid],
+		from: DateTime<Utc>,
+		to: DateTime<Utc>,
+	) -> CoreResult<Vec<UserInterval>>;
+	async fn set_calendar_token(&self, user_id: Uuid, token: Option<&str>) -> CoreResult;
+	async fn read_calendar_events(&self, token: &str) -> CoreResult<Option<Vec<CalendarEvent>>>;
 	async fn verify_email(&self, verification_id: Uuid) -> CoreResult<Option<(bool, bool)>>;
 	async fn send_email_verification(&self, user_id: Uuid) -> CoreResult<(Uuid, String)>;
+	async fn add_notifications(
+		&self,
+		users: &[Uuid],
+		message: &str,
+		payload: &NotificationPayload,
+	) -> CoreResult<Vec<(Uuid, Uuid)>>;
+	fn sse_listener(&self) -> SseListener;
+	async fn publish_sse(&self, users: &[Uuid], event: &str, data: &[String]) -> CoreResult;
+	async fn publish_sse_global(&self, event: &str, data: &str) -> CoreResult;
+	async fn read_notifications(
+		&self,
+		user_id: Uuid,
+		query: &ReadNotificationsDto,
+		page: &PageDto,
+	) -> CoreResult<Page<Notification>>;
+	async fn count_unread_notifications(&self, user_id: Uuid) -> CoreResult<i64>;
+	async fn mark_notification_read(&self, user_id: Uuid, notification_id: Uuid)
+	-> CoreResult<bool>;
+	async fn mark_all_notifications_read(&self, user_id: Uuid) -> CoreResult<u64>;
+	async fn set_telegram_link_code(
+		&self,
+		user_id: Uuid,
+		code: &str,
+		expires_at: DateTime<Utc>,
+	) -> CoreResult;
+	async fn link_telegram_chat(&self, code: &str, chat_id: i64) -> CoreResult<Option<Uuid>>;
+	async fn set_telegram_notifications(&self, user_id: Uuid, enabled: bool) -> CoreResult<bool>;
+	async fn disable_telegram_chats(&self, chats: &[i64]) -> CoreResult<u64>;
+	async fn read_telegram_chats(
+		&self,
+		users: &[Uuid],
+		date: Option<DateTime<Utc>>,
+	) -> CoreResult<Vec<TelegramChat>>;
 
-	async fn get_locations_list(&self, query: ReadLocationDto) -> CoreResult<Vec<Location>>;
+	async fn get_locations_list(
+		&self,
+		query: ReadLocationDto,
+		page: &PageDto,
+	) -> CoreResult<Page<Location>>;
 	async fn get_location_by_id(&self, location_id: Uuid) -> CoreResult<Option<Location>>;
 
-	async fn add_location(
+	async fn add_location(&self, data: &NewLocationDto) -> CoreResult<RecordId>;
+	async fn read_nearby_locations(&self, near: &NearDto) -> CoreResult<Vec<NearbyLocation>>;
+	async fn read_nearby_events(
 		&self,
-		name: &str,
-		address: &Option<String>,
-		descr: &Option<String>,
-		city: &Option<String>,
-		map_link: &Option<String>,
-	) -> CoreResult<RecordId>;
+		near: &NearDto,
+		from: DateTime<Utc>,
+		to: DateTime<Utc>,
+	) -> CoreResult<Vec<NearbyEvent>>;
+	async fn read_links_without_coordinates(&self) -> CoreResult<Vec<LocationLink>>;
+	async fn set_location_coordinates(
+		&self,
+		location_id: Uuid,
+		coordinates: Coordinates,
+	) -> CoreResult;
+	async fn set_location_capacity(
+		&self,
+		location_id: Uuid,
+		data: &LocationCapacityDto,
+	) -> CoreResult<bool>;
 
 	async fn get_company_by_id(
 		&self,
@@ -74,17 +156,18 @@ trait Store {
 		&self,
 		query: ReadCompaniesDto,
 		master: Uuid,
-	) -> CoreResult<Vec<Company>>;
+		page: &PageDto,
+	) -> CoreResult<Page<Company>>;
+
+	async fn add_company(&self, master: Uuid, data: &ApiCompanyDto) -> CoreResult<RecordId>;
 
-	async fn add_company(
+	async fn add_one_shot(
 		&self,
 		master: Uuid,
-		name: &str,
-		system: &str,
-		descr: &Option<String>,
-		cover_link: &Option<String>,
-		event_style: &Option<String>,
-	) -> CoreResult<RecordId>;
+		data: &NewOneShotDto,
+		date: DateTime<FixedOffset>,
+		check: ScheduleCheck,
+	) -> CoreResult<Checked<OneShotIds>>;
 
 	async fn update_company(
 		&self,
@@ -100,11 +183,54 @@ trait Store {
 		cover_link: &str,
 	) -> CoreResult<bool>;
 
+	async fn read_company_members(&self, company_id: Uuid) -> CoreResult<Vec<CompanyMember>>;
+	async fn invite_member(&self, company_id: Uuid, user_id: Uuid) -> CoreResult<bool>;
+	async fn accept_membership(&self, company_id: Uuid, user_id: Uuid) -> CoreResult<bool>;
+	async fn remove_member(&self, company_id: Uuid, user_id: Uuid) -> CoreResult<bool>;
+	async fn set_member_role(
+		&self,
+		company_id: Uuid,
+		user_id: Uuid,
+		role: MemberRole,
+	) -> CoreResult<bool>;
+	async fn has_company_permission(
+		&self,
+		company_id: Uuid,
+		user_id: Uuid,
+		permission: CompanyPermission,
+	) -> CoreResult<bool>;
+
+	async fn propose_transfer(
+		&self,
+		company_id: Uuid,
+		master: Uuid,
+		to_user: Uuid,
+		stay_as: Option<MemberRole>,
+	) -> CoreResult<bool>;
+	async fn accept_transfer(
+		&self,
+		company_id: Uuid,
+		user_id: Uuid,
+	) -> CoreResult<Option<TransferParties>>;
+	async fn cancel_transfer(
+		&self,
+		company_id: Uuid,
+		user_id: Uuid,
+	) -> CoreResult<Option<TransferParties>>;
+
 	async fn read_events_list(
 		&self,
 		query: ReadEventsDto,
 		player_id: Option<Uuid>,
-	) -> CoreResult<Vec<ShortEvent>>;
+		page: &PageDto,
+	) -> CoreResult<Page<ShortEvent>>;
+	async fn read_public_calendar(&self, query: ReadEventsDto) -> CoreResult<Vec<CalendarEvent>>;
+	async fn search(
+		&self,
+		search: &SearchDto,
+		tsquery: &str,
+		user_id: Option<Uuid>,
+	) -> CoreResult<Vec<SearchHit>>;
 
 	async fn read_event(&self, event_id: Uuid, player_id: Option<Uuid>)
 	-> CoreResult<Option<Event>>;
@@ -115,6 +241,11 @@ trait Store {
 		player_id: Uuid,
 	) -> CoreResult<Option<EventForApplying>>;
 
+	async fn read_conflicts(
+		&self,
+		user_id: Uuid,
+		event_id: Uuid,
+	) -> CoreResult<Vec<ScheduleConflict>>;
 	async fn apply_event(
 		&self,
 		event_id: Uuid,
@@ -122,7 +253,11 @@ trait Store {
 		can_auto_approve: bool,
 	) -> CoreResult<RecordId>;
 
-	async fn read_player_apps_list(&self, player_id: Uuid) -> CoreResult<Vec<PlayerApp>>;
+	async fn read_player_apps_list(
+		&self,
+		player_id: Uuid,
+		page: &PageDto,
+	) -> CoreResult<Page<PlayerApp>>;
 	async fn read_player_app(&self, player_id: Uuid, app_id: Uuid) -> CoreResult<Option<PlayerApp>>;
 	async fn read_player_app_by_event(
 		&self,
@@ -135,7 +270,11 @@ trait Store {
 		company_id: Uuid,
 	) -> CoreResult<Option<PlayerApp>>;
 
-	async fn read_master_apps_list(&self, master_id: Uuid) -> CoreResult<Vec<MasterApp>>;
+	async fn read_master_apps_list(
+		&self,
+		master_id: Uuid,
+		page: &PageDto,
+	) -> CoreResult<Page<MasterApp>>;
 	async fn read_master_apps_list_by_event(
 		&self,
 		master_id: Uuid,
@@ -158,28 +297,94 @@ trait Store {
 
 	async fn cancel_event(&self, event_id: Uuid) -> CoreResult;
 	async fn reopen_event(&self, event_id: Uuid) -> CoreResult;
+	async fn read_event_players(&self, event_id: Uuid) -> CoreResult<Vec<Uuid>>;
 
 	async fn add_event(
 		&self,
-		company: Uuid,
-		location: &Option<Uuid>,
+		data: &NewEventDto,
 		date: DateTime<FixedOffset>,
-		max_slots: Option<i16>,
-		plan_duration: Option<i16>,
-	) -> CoreResult<RecordId>;
+		check: ScheduleCheck,
+	) -> CoreResult<Checked<RecordId>>;
 
 	async fn update_event(
 		&self,
 		event_id: Uuid,
 		master: Uuid,
 		data: UpdateEventDto,
-	) -> CoreResult<bool>;
+		date: DateTime<FixedOffset>,
+		check: ScheduleCheck,
+	) -> CoreResult<Option<Checked<Vec<MovedEvent>>>>;
+
+	async fn default_timezone(
+		&self,
+		location_id: Option<Uuid>,
+		user_id: Uuid,
+	) -> CoreResult<Option<String>>;
+	async fn local_to_utc(&self, locals: &[(NaiveDateTime, &str)]) -> CoreResult<Vec<ZonedDate>>;
+
+	async fn add_series(
+		&self,
+		data: &NewSeriesDto,
+		dates: &[DateTime<FixedOffset>],
+		timezone: Option<&str>,
+		check: ScheduleCheck,
+	) -> CoreResult<Checked<SeriesIds>>;
+	async fn read_series(
+		&self,
+		series_id: Uuid,
+		user_id: Option<Uuid>,
+	) -> CoreResult<Option<Series>>;
+	async fn add_series_exceptions(
+		&self,
+		series_id: Uuid,
+		user_id: Uuid,
+		dates: &[NaiveDate],
+	) -> CoreResult<Option<Vec<CancelledSeriesEvent>>>;
+	async fn import_events(
+		&self,
+		company_id: Uuid,
+		data: &ImportCalendarDto,
+		games: &[ImportedGameDto],
+		check: ScheduleCheck,
+	) -> CoreResult<Checked<Vec<Uuid>>>;
+
+	async fn add_date_poll(
+		&self,
+		company_id: Uuid,
+		title: Option<&str>,
+		dates: &[DateTime<FixedOffset>],
+	) -> CoreResult<Uuid>;
+	async fn read_date_poll(&self, poll_id: Uuid, user_id: Uuid) -> CoreResult<Option<DatePoll>>;
+	async fn read_company_polls(&self, company_id: Uuid) -> CoreResult<Vec<ShortDatePoll>>;
+	async fn vote_date_poll(
+		&self,
+		poll_id: Uuid,
+		user_id: Uuid,
+		votes: &[PollVoteDto],
+	) -> CoreResult<u64>;
+	async fn convert_date_poll(
+		&self,
+		poll_id: Uuid,
+		data: &NewEventDto,
+		date: DateTime<FixedOffset>,
+		check: ScheduleCheck,
+	) -> CoreResult<Option<Checked<ConvertedPoll>>>;
 
 	async fn read_regions_list(&self) -> CoreResult<Vec<Region>>;
 	async fn read_cities_list(&self, region: Option<String>) -> CoreResult<Vec<City>>;
 	async fn add_region(&self, region: Region) -> CoreResult;
 	async fn add_city(&self, city: City) -> CoreResult;
 
+	async fn is_admin(&self, user_id: Uuid) -> CoreResult<bool>;
+	async fn set_user_role(&self, user_id: Uuid, role: &str) -> CoreResult<Option<User>>;
+	async fn merge_locations(
+		&self,
+		duplicate: Uuid,
+		canonical: Uuid,
+	) -> CoreResult<Option<MergeStats>>;
+	async fn merge_cities(&self, duplicate: &str, canonical: &str)
+	-> CoreResult<Option<MergeStats>>;
+
 	async fn close(&self);
 }
 
@@ -259,6 +464,47 @@ impl Repository {
 		return self.store.set_avatar(user_id, url).await;
 	}
 
+	pub(crate) async fn read_availability(&self, user_id: Uuid) -> CoreResult<Vec<WeeklyWindow>> {
+		return self.store.read_availability(user_id).await;
+	}
+
+	pub(crate) async fn set_availability(
+		&self,
+		user_id: Uuid,
+		windows: &[WeeklyWindowDto],
+	) -> CoreResult {
+		return self.store.set_availability(user_id, windows).await;
+	}
+
+	pub(crate) async fn read_free_intervals(
+		&self,
+		users: &[Uuid],
+		from: DateTime<Utc>,
+		to: DateTime<Utc>,
+	) -> CoreResult<Vec<UserInterval>> {
+		return self.store.read_free_intervals(users, from, to).await;
+	}
+
+	pub(crate) async fn read_busy_intervals(
+		&self,
+		users: &[Uuid],
+		from: DateTime<Utc>,
+		to: DateTime<Utc>,
+	) -> CoreResult<Vec<UserInterval>> {
+		return self.store.read_busy_intervals(users, from, to).await;
+	}
+
+	pub(crate) async fn set_calendar_token(&self, user_id: Uuid, token: Option<&str>) -> CoreResult {
+		return self.store.set_calendar_token(user_id, token).await;
+	}
+
+	pub(crate) async fn read_calendar_events(
+		&self,
+		token: &str,
+	) -> CoreResult<Option<Vec<CalendarEvent>>> {
+		return self.store.read_calendar_events(token).await;
+	}
+
 	pub(crate) async fn verify_email(
 		&self,
 		verification_id: Uuid,
@@ -270,11 +516,117 @@ impl Repository {
 		return self.store.send_email_verification(user_id).await;
 	}
 
+	/// Возвращает пары (получатель, id уведомления)
+	pub(crate) async fn add_notifications(
+		&self,
+		users: &[Uuid],
+		message: &str,
+		payload: &NotificationPayload,
+	) -> CoreResult<Vec<(Uuid, Uuid)>> {
+		return self.store.add_notifications(users, message, payload).await;
+	}
+
+	pub(crate) fn sse_listener(&self) -> SseListener {
+		return self.store.sse_listener();
+	}
+
+	/// Рассылает события SSE через БД; `data[i]` предназначено `users[i]`
+	pub(crate) async fn publish_sse(
+		&self,
+		users: &[Uuid],
+		event: &str,
+		data: &[String],
+	) -> CoreResult {
+		return self.store.publish_sse(users, event, data).await;
+	}
+
+	/// Рассылает событие SSE всем подключенным, в том числе без входа
+	pub(crate) async fn publish_sse_global(&self, event: &str, data: &str) -> CoreResult {
+		return self.store.publish_sse_global(event, data).await;
+	}
+
+	pub(crate) async fn read_notifications(
+		&self,
+		user_id: Uuid,
+		query: &ReadNotificationsDto,
+		page: &PageDto,
+	) -> CoreResult<Page<Notification>> {
+		return self.store.read_notifications(user_id, query, page).await;
+	}
+
+	pub(crate) async fn count_unread_notifications(&self, user_id: Uuid) -> CoreResult<i64> {
+		return self.store.count_unread_notifications(user_id).await;
+	}
+
+	pub(crate) async fn mark_notification_read(
+		&self,
+		user_id: Uuid,
+		notification_id: Uuid,
+	) -> CoreResult<bool> {
+		return self
+			.store
+			.mark_notification_read(user_id, notification_id)
+			.await;
+	}
+
+	pub(crate) async fn mark_all_notifications_read(&self, user_id: Uuid) -> CoreResult<u64> {
+		return self.store.mark_all_notifications_read(user_id).await;
+	}
+
+	/// Новый код заменяет выданный ранее
+	pub(crate) async fn set_telegram_link_code(
+		&self,
+		user_id: Uuid,
+		code: &str,
+		expires_at: DateTime<Utc>,
+	) -> CoreResult {
+		return self
+			.store
+			.set_telegram_link_code(user_id, code, expires_at)
+			.await;
+	}
+
+	/// Привязывает чат к владельцу неистекшего кода и включает уведомления.
+	/// Код одноразовый; `None`, если он неверен или истёк
+	pub(crate) async fn link_telegram_chat(
+		&self,
+		code: &str,
+		chat_id: i64,
+	) -> CoreResult<Option<Uuid>> {
+		return self.store.link_telegram_chat(code, chat_id).await;
+	}
+
+	/// `false`, если чат с ботом не привязан
+	pub(crate) async fn set_telegram_notifications(
+		&self,
+		user_id: Uuid,
+		enabled: bool,
+	) -> CoreResult<bool> {
+		return self
+			.store
+			.set_telegram_notifications(user_id, enabled)
+			.await;
+	}
+
+	/// Выключает уведомления в чатах, например после того как бота заблокировали
+	pub(crate) async fn disable_telegram_chats(&self, chats: &[i64]) -> CoreResult<u64> {
+		return self.store.disable_telegram_chats(chats).await;
+	}
+
+	pub(crate) async fn read_telegram_chats(
+		&self,
+		users: &[Uuid],
+		date: Option<DateTime<Utc>>,
+	) -> CoreResult<Vec<TelegramChat>> {
+		return self.store.read_telegram_chats(users, date).await;
+	}
+
 	pub(crate) async fn get_locations_list(
 		&self,
 		query: ReadLocationDto,
-	) -> CoreResult<Vec<Location>> {
-		return self.store.get_locations_list(query).await;
+		page: &PageDto,
+	) -> CoreResult<Page<Location>> {
+		return self.store.get_locations_list(query, page).await;
 	}
 
 	pub(crate) async fn get_location_by_id(
@@ -284,20 +636,49 @@ impl Repository {
 		return self.store.get_location_by_id(location_id).await;
 	}
 
-	pub(crate) async fn add_location(
+	pub(crate) async fn add_location(&self, data: &NewLocationDto) -> CoreResult<RecordId> {
+		return self.store.add_location(data).await;
+	}
+
+	pub(crate) async fn read_nearby_locations(
 		&self,
-		name: &str,
-		address: &Option<String>,
-		descr: &Option<String>,
-		city: &Option<String>,
-		map_link: &Option<String>,
-	) -> CoreResult<RecordId> {
+		near: &NearDto,
+	) -> CoreResult<Vec<NearbyLocation>> {
+		return self.store.read_nearby_locations(near).await;
+	}
+
+	pub(crate) async fn read_nearby_events(
+		&self,
+		near: &NearDto,
+		from: DateTime<Utc>,
+		to: DateTime<Utc>,
+	) -> CoreResult<Vec<NearbyEvent>> {
+		return self.store.read_nearby_events(near, from, to).await;
+	}
+
+	pub(crate) async fn read_links_without_coordinates(&self) -> CoreResult<Vec<LocationLink>> {
+		return self.store.read_links_without_coordinates().await;
+	}
+
+	pub(crate) async fn set_location_coordinates(
+		&self,
+		location_id: Uuid,
+		coordinates: Coordinates,
+	) -> CoreResult {
 		return self
 			.store
-			.add_location(name, address, descr, city, map_link)
+			.set_location_coordinates(location_id, coordinates)
 			.await;
 	}
 
+	pub(crate) async fn set_location_capacity(
+		&self,
+		location_id: Uuid,
+		data: &LocationCapacityDto,
+	) -> CoreResult<bool> {
+		return self.store.set_location_capacity(location_id, data).await;
+	}
+
 	pub(crate) async fn get_company_by_id(
 		&self,
 		company_id: Uuid,
@@ -314,23 +695,27 @@ impl Repository {
 		&self,
 		query: ReadCompaniesDto,
 		master: Uuid,
-	) -> CoreResult<Vec<Company>> {
-		return self.store.get_my_companies(query, master).await;
+		page: &PageDto,
+	) -> CoreResult<Page<Company>> {
+		return self.store.get_my_companies(query, master, page).await;
 	}
 
 	pub(crate) async fn add_company(
 		&self,
 		master: Uuid,
-		name: &str,
-		system: &str,
-		descr: &Option<String>,
-		cover_link: &Option<String>,
-		event_style: &Option<String>,
+		data: &ApiCompanyDto,
 	) -> CoreResult<RecordId> {
-		return self
-			.store
-			.add_company(master, name, system, descr, cover_link, event_style)
-			.await;
+		return self.store.add_company(master, data).await;
+	}
+
+	pub(crate) async fn add_one_shot(
+		&self,
+		master: Uuid,
+		data: &NewOneShotDto,
+		date: DateTime<FixedOffset>,
+		check: ScheduleCheck,
+	) -> CoreResult<Checked<OneShotIds>> {
+		return self.store.add_one_shot(master, data, date, check).await;
 	}
 
 	pub(crate) async fn update_company(
@@ -354,12 +739,102 @@ impl Repository {
 			.await;
 	}
 
+	pub(crate) async fn read_company_members(
+		&self,
+		company_id: Uuid,
+	) -> CoreResult<Vec<CompanyMember>> {
+		return self.store.read_company_members(company_id).await;
+	}
+
+	pub(crate) async fn invite_member(&self, company_id: Uuid, user_id: Uuid) -> CoreResult<bool> {
+		return self.store.invite_member(company_id, user_id).await;
+	}
+
+	pub(crate) async fn accept_membership(
+		&self,
+		company_id: Uuid,
+		user_id: Uuid,
+	) -> CoreResult<bool> {
+		return self.store.accept_membership(company_id, user_id).await;
+	}
+
+	pub(crate) async fn remove_member(&self, company_id: Uuid, user_id: Uuid) -> CoreResult<bool> {
+		return self.store.remove_member(company_id, user_id).await;
+	}
+
+	pub(crate) async fn set_member_role(
+		&self,
+		company_id: Uuid,
+		user_id: Uuid,
+		role: MemberRole,
+	) -> CoreResult<bool> {
+		return self.store.set_member_role(company_id, user_id, role).await;
+	}
+
+	pub(crate) async fn has_company_permission(
+		&self,
+		company_id: Uuid,
+		user_id: Uuid,
+		permission: CompanyPermission,
+	) -> CoreResult<bool> {
+		return self
+			.store
+			.has_company_permission(company_id, user_id, permission)
+			.await;
+	}
+
+	pub(crate) async fn propose_transfer(
+		&self,
+		company_id: Uuid,
+		master: Uuid,
+		to_user: Uuid,
+		stay_as: Option<MemberRole>,
+	) -> CoreResult<bool> {
+		return self
+			.store
+			.propose_transfer(company_id, master, to_user, stay_as)
+			.await;
+	}
+
+	pub(crate) async fn accept_transfer(
+		&self,
+		company_id: Uuid,
+		user_id: Uuid,
+	) -> CoreResult<Option<TransferParties>> {
+		return self.store.accept_transfer(company_id, user_id).await;
+	}
+
+	pub(crate) async fn cancel_transfer(
+		&self,
+		company_id: Uuid,
+		user_id: Uuid,
+	) -> CoreResult<Option<TransferParties>> {
+		return self.store.cancel_transfer(company_id, user_id).await;
+	}
+
 	pub(crate) async fn read_events_list(
 		&self,
 		query: ReadEventsDto,
 		player_id: Option<Uuid>,
-	) -> CoreResult<Vec<ShortEvent>> {
-		return self.store.read_events_list(query, player_id).await;
+		page: &PageDto,
+	) -> CoreResult<Page<ShortEvent>> {
+		return self.store.read_events_list(query, player_id, page).await;
+	}
+
+	pub(crate) async fn read_public_calendar(
+		&self,
+		query_args: ReadEventsDto,
+	) -> CoreResult<Vec<CalendarEvent>> {
+		return self.store.read_public_calendar(query_args).await;
+	}
+
+	pub(crate) async fn search(
+		&self,
+		search: &SearchDto,
+		tsquery: &str,
+		user_id: Option<Uuid>,
+	) -> CoreResult<Vec<SearchHit>> {
+		return self.store.search(search, tsquery, user_id).await;
 	}
 
 	pub(crate) async fn read_event(
@@ -378,6 +853,15 @@ impl Repository {
 		return self.store.get_event_for_applying(event_id, player_id).await;
 	}
 
+	/// Игры пользователя, пересекающиеся с игрой `event_id`
+	pub(crate) async fn read_conflicts(
+		&self,
+		user_id: Uuid,
+		event_id: Uuid,
+	) -> CoreResult<Vec<ScheduleConflict>> {
+		return self.store.read_conflicts(user_id, event_id).await;
+	}
+
 	pub(crate) async fn apply_event(
 		&self,
 		event_id: Uuid,
@@ -398,18 +882,18 @@ impl Repository {
 		return self.store.reopen_event(event_id).await;
 	}
 
+	/// Игроки с заявками на игру, кроме отклонённых
+	pub(crate) async fn read_event_players(&self, event_id: Uuid) -> CoreResult<Vec<Uuid>> {
+		return self.store.read_event_players(event_id).await;
+	}
+
 	pub(crate) async fn add_event(
 		&self,
-		company: Uuid,
-		location: &Option<Uuid>,
+		data: &NewEventDto,
 		date: DateTime<FixedOffset>,
-		max_slots: Option<i16>,
-		plan_duration: Option<i16>,
-	) -> CoreResult<RecordId> {
-		return self
-			.store
-			.add_event(company, location, date, max_slots, plan_duration)
-			.await;
+		check: ScheduleCheck,
+	) -> CoreResult<Checked<RecordId>> {
+		return self.store.add_event(data, date, check).await;
 	}
 
 	pub(crate) async fn update_event(
@@ -417,12 +901,125 @@ impl Repository {
 		event_id: Uuid,
 		master: Uuid,
 		data: UpdateEventDto,
-	) -> CoreResult<bool> {
-		return self.store.update_event(event_id, master, data).await;
+		date: DateTime<FixedOffset>,
+		check: ScheduleCheck,
+	) -> CoreResult<Option<Checked<Vec<MovedEvent>>>> {
+		return self
+			.store
+			.update_event(event_id, master, data, date, check)
+			.await;
+	}
+
+	pub(crate) async fn default_timezone(
+		&self,
+		location_id: Option<Uuid>,
+		user_id: Uuid,
+	) -> CoreResult<Option<String>> {
+		return self.store.default_timezone(location_id, user_id).await;
+	}
+
+	pub(crate) async fn local_to_utc(
+		&self,
+		locals: &[(NaiveDateTime, &str)],
+	) -> CoreResult<Vec<ZonedDate>> {
+		return self.store.local_to_utc(locals).await;
+	}
+
+	pub(crate) async fn add_series(
+		&self,
+		data: &NewSeriesDto,
+		dates: &[DateTime<FixedOffset>],
+		timezone: Option<&str>,
+		check: ScheduleCheck,
+	) -> CoreResult<Checked<SeriesIds>> {
+		return self.store.add_series(data, dates, timezone, check).await;
+	}
+
+	pub(crate) async fn read_series(
+		&self,
+		series_id: Uuid,
+		user_id: Option<Uuid>,
+	) -> CoreResult<Option<Series>> {
+		return self.store.read_series(series_id, user_id).await;
+	}
+
+	pub(crate) async fn add_series_exceptions(
+		&self,
+		series_id: Uuid,
+		user_id: Uuid,
+		dates: &[NaiveDate],
+	) -> CoreResult<Option<Vec<CancelledSeriesEvent>>> {
+		return self
+			.store
+			.add_series_exceptions(series_id, user_id, dates)
+			.await;
+	}
+
+	pub(crate) async fn import_events(
+		&self,
+		company_id: Uuid,
+		data: &ImportCalendarDto,
+		games: &[ImportedGameDto],
+		check: ScheduleCheck,
+	) -> CoreResult<Checked<Vec<Uuid>>> {
+		return self
+			.store
+			.import_events(company_id, data, games, check)
+			.await;
 	}
 
-	pub(crate) async fn read_player_apps_list(&self, player_id: Uuid) -> CoreResult<Vec<PlayerApp>> {
-		return self.store.read_player_apps_list(player_id).await;
+	pub(crate) async fn add_date_poll(
+		&self,
+		company_id: Uuid,
+		title: Option<&str>,
+		dates: &[DateTime<FixedOffset>],
+	) -> CoreResult<Uuid> {
+		return self.store.add_date_poll(company_id, title, dates).await;
+	}
+
+	pub(crate) async fn read_date_poll(
+		&self,
+		poll_id: Uuid,
+		user_id: Uuid,
+	) -> CoreResult<Option<DatePoll>> {
+		return self.store.read_date_poll(poll_id, user_id).await;
+	}
+
+	pub(crate) async fn read_company_polls(
+		&self,
+		company_id: Uuid,
+	) -> CoreResult<Vec<ShortDatePoll>> {
+		return self.store.read_company_polls(company_id).await;
+	}
+
+	pub(crate) async fn vote_date_poll(
+		&self,
+		poll_id: Uuid,
+		user_id: Uuid,
+		votes: &[PollVoteDto],
+	) -> CoreResult<u64> {
+		return self.store.vote_date_poll(poll_id, user_id, votes).await;
+	}
+
+	pub(crate) async fn convert_date_poll(
+		&self,
+		poll_id: Uuid,
+		data: &NewEventDto,
+		date: DateTime<FixedOffset>,
+		check: ScheduleCheck,
+	) -> CoreResult<Option<Checked<ConvertedPoll>>> {
+		return self
+			.store
+			.convert_date_poll(poll_id, data, date, check)
+			.await;
+	}
+
+	pub(crate) async fn read_player_apps_list(
+		&self,
+		player_id: Uuid,
+		page: &PageDto,
+	) -> CoreResult<Page<PlayerApp>> {
+		return self.store.read_player_apps_list(player_id, page).await;
 	}
 
 	pub(crate) async fn read_player_app(
@@ -455,8 +1052,12 @@ impl Repository {
 			.await;
 	}
 
-	pub(crate) async fn read_master_apps_list(&self, master_id: Uuid) -> CoreResult<Vec<MasterApp>> {
-		return self.store.read_master_apps_list(master_id).await;
+	pub(crate) async fn read_master_apps_list(
+		&self,
+		master_id: Uuid,
+		page: &PageDto,
+	) -> CoreResult<Page<MasterApp>> {
+		return self.store.read_master_apps_list(master_id, page).await;
 	}
 
 	pub(crate) async fn read_master_apps_list_by_event(
@@ -521,6 +1122,31 @@ impl Repository {
 		return self.store.add_city(city).await;
 	}
 
+	pub(crate) async fn is_admin(&self, user_id: Uuid) -> CoreResult<bool> {
+		return self.store.is_admin(user_id).await;
+	}
+
+	/// `None`, если пользователь не найден
+	pub(crate) async fn set_user_role(&self, user_id: Uuid, role: &str) -> CoreResult<Option<User>> {
+		return self.store.set_user_role(user_id, role).await;
+	}
+
+	pub(crate) async fn merge_locations(
+		&self,
+		duplicate: Uuid,
+		canonical: Uuid,
+	) -> CoreResult<Option<MergeStats>> {
+		return self.store.merge_locations(duplicate, canonical).await;
+	}
+
+	pub(crate) async fn merge_cities(
+		&self,
+		duplicate: &str,
+		canonical: &str,
+	) -> CoreResult<Option<MergeStats>> {
+		return self.store.merge_cities(duplicate, canonical).await;
+	}
+
 	pub async fn close(&self) {
 		return self.store.close().await;
 	}
@@ -1,9 +1,16 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
 use derive_masked::DebugMasked;
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, types::Json as SqlxJson};
 use uuid::Uuid;
 
+/// Страница списка. `next_cursor` отсутствует на последней странице
+#[derive(Debug, Serialize)]
+pub(crate) struct Page<T> {
+	pub items: Vec<T>,
+	pub next_cursor: Option<String>,
+}
+
 #[derive(DebugMasked, Deserialize, Serialize, FromRow)]
 pub(crate) struct UserForAuthEmail {
 	pub id: Uuid,
@@ -44,6 +51,9 @@ pub(crate) struct Profile {
 	pub get_tz_from_device: bool,
 	pub email_verified: bool,
 	pub tg_id: Option<i32>,
+	/// чат с ботом привязан
+	pub tg_linked: bool,
+	pub tg_notifications: bool,
 }
 
 #[derive(Debug, Deserialize, Serialize, FromRow)]
@@ -60,6 +70,12 @@ pub(crate) struct Company {
 	pub system: String,
 	pub description: Option<String>,
 	pub cover_link: Option<String>,
+	pub one_shot: bool,
+	pub visibility: String,
+	/// ранг совпадения с поисковой строкой, нужен только для курсора страницы
+	#[serde(skip)]
+	#[sqlx(default)]
+	pub rank: Option<i32>,
 }
 
 #[derive(Debug, Deserialize, Serialize, FromRow)]
@@ -73,6 +89,32 @@ pub(crate) struct CompanyInfo {
 	pub cover_link: Option<String>,
 	pub you_are_master: bool,
 	pub event_style: Option<String>,
+	pub one_shot: bool,
+	pub visibility: String,
+	pub you_are_member: bool,
+	pub your_role: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, FromRow)]
+pub(crate) struct CompanyMember {
+	pub user_id: Uuid,
+	pub nickname: String,
+	pub status: String,
+	pub role: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, FromRow)]
+pub(crate) struct TransferParties {
+	pub company_name: String,
+	pub master: Uuid,
+	pub new_master: Uuid,
+	pub stay_as: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct OneShotIds {
+	pub company_id: Uuid,
+	pub event_id: Uuid,
 }
 
 #[derive(Debug, Deserialize, Serialize, FromRow)]
@@ -84,6 +126,16 @@ pub(crate) struct Location {
 	pub region: Option<String>,
 	pub city: Option<String>,
 	pub map_link: Option<String>,
+	pub latitude: Option<f64>,
+	pub longitude: Option<f64>,
+	pub tables: Option<i16>,
+	/// заполняется только при чтении одной локации
+	#[sqlx(default)]
+	pub opening_hours: Option<SqlxJson<Vec<WeeklyWindow>>>,
+	/// ранг совпадения с поисковой строкой, нужен только для курсора страницы
+	#[serde(skip)]
+	#[sqlx(default)]
+	pub rank: Option<i32>,
 }
 
 #[derive(Debug, Deserialize, Serialize, FromRow)]
@@ -93,6 +145,8 @@ pub(crate) struct ShortEvent {
 	pub date: DateTime<Utc>,
 	pub plan_duration: Option<i16>,
 	pub style: Option<String>,
+	pub venue_type: String,
+	pub online_platform: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, FromRow)]
@@ -102,8 +156,8 @@ pub(crate) struct Event {
 	pub company_id: Uuid,
 	pub master: String,
 	pub master_id: Uuid,
-	pub location: String,
-	pub location_id: Uuid,
+	pub location: Option<String>,
+	pub location_id: Option<Uuid>,
 	pub location_map_link: Option<String>,
 	pub date: DateTime<Utc>,
 	pub players: SqlxJson<Vec<(Uuid, String)>>,
@@ -111,8 +165,62 @@ pub(crate) struct Event {
 	pub plan_duration: Option<i16>,
 	pub you_applied: bool,
 	pub you_are_master: bool,
+	pub you_are_member: bool,
 	pub your_approval: Option<bool>,
 	pub cancelled: bool,
+	pub visibility: String,
+	pub venue_type: String,
+	pub online_platform: Option<String>,
+	pub join_link: Option<String>,
+	pub series: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct SeriesIds {
+	pub series_id: Uuid,
+	pub events: Vec<Uuid>,
+}
+
+#[derive(Debug, Deserialize, Serialize, FromRow)]
+pub(crate) struct SeriesEvent {
+	pub id: Uuid,
+	pub date: DateTime<Utc>,
+	pub cancelled: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, FromRow)]
+pub(crate) struct Series {
+	pub id: Uuid,
+	pub company_id: Uuid,
+	pub frequency: String,
+	pub until: Option<NaiveDate>,
+	pub count: Option<i16>,
+	pub exceptions: Vec<NaiveDate>,
+	/// `None` у серии, созданной по абсолютному времени
+	pub timezone: Option<String>,
+	pub events: SqlxJson<Vec<SeriesEvent>>,
+}
+
+/// Местное время, переведённое по правилам пояса
+#[derive(Debug, FromRow)]
+pub(crate) struct ZonedDate {
+	pub local: NaiveDateTime,
+	pub timezone: String,
+	/// `None` - неизвестный пояс
+	pub date: Option<DateTime<Utc>>,
+	/// время попадает в пропущенный при переводе часов промежуток
+	pub in_gap: bool,
+	/// время повторяется при переводе часов назад
+	pub ambiguous: bool,
+}
+
+/// Игра серии, отменённая исключением, и её игроки для уведомления
+#[derive(Debug, FromRow)]
+pub(crate) struct CancelledSeriesEvent {
+	pub id: Uuid,
+	pub date: DateTime<Utc>,
+	pub company_name: String,
+	pub players: Vec<Uuid>,
 }
 
 #[derive(Debug, Deserialize, Serialize, FromRow)]
@@ -125,6 +233,9 @@ pub(crate) struct EventForApplying {
 	pub already_applied: bool,
 	pub can_auto_approve: bool,
 	pub cancelled: bool,
+	pub you_are_member: bool,
+	pub visibility: String,
+	pub plan_duration: Option<i16>,
 }
 
 #[derive(Debug, Deserialize, Serialize, FromRow)]
@@ -135,8 +246,8 @@ pub(crate) struct PlayerApp {
 	pub event_cancelled: bool,
 	pub company_id: Uuid,
 	pub company_name: String,
-	pub location_id: Uuid,
-	pub location_name: String,
+	pub location_id: Option<Uuid>,
+	pub location_name: Option<String>,
 	pub master_id: Uuid,
 	pub master_name: String,
 	pub approval: Option<bool>,
@@ -150,15 +261,44 @@ pub(crate) struct MasterApp {
 	pub event_cancelled: bool,
 	pub company_id: Uuid,
 	pub company_name: String,
-	pub location_id: Uuid,
-	pub location_name: String,
+	pub location_id: Option<Uuid>,
+	pub location_name: Option<String>,
 	pub player_id: Uuid,
 	pub player_name: String,
 	pub approval: Option<bool>,
 }
 
+#[derive(Debug, Deserialize, Serialize, FromRow)]
+pub(crate) struct CalendarEvent {
+	pub id: Uuid,
+	pub company: String,
+	pub description: Option<String>,
+	pub date: DateTime<Utc>,
+	pub plan_duration: Option<i16>,
+	pub cancelled: bool,
+	pub location: Option<String>,
+	pub address: Option<String>,
+	pub map_link: Option<String>,
+	pub online_platform: Option<String>,
+	pub join_link: Option<String>,
+	pub master: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, FromRow)]
+pub(crate) struct SearchHit {
+	pub kind: String,
+	pub id: Uuid,
+	pub title: String,
+	pub subtitle: Option<String>,
+	pub date: Option<DateTime<Utc>>,
+	pub rank: f32,
+}
+
 #[derive(Debug, Deserialize, Serialize, FromRow)]
 pub(crate) struct AppForApproval {
+	pub event_id: Uuid,
+	pub company_name: String,
+	pub player_id: Uuid,
 	pub event_date: DateTime<Utc>,
 	pub event_cancelled: bool,
 	pub approval: Option<bool>,
@@ -176,3 +316,197 @@ pub(crate) struct City {
 	pub region: String,
 	pub own_timezone: Option<String>,
 }
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub(crate) struct MergeStats {
+	pub events: u64,
+	pub users: u64,
+	pub locations: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct PollSlot {
+	pub id: Uuid,
+	pub date: DateTime<Utc>,
+	/// время варианта в поясе смотрящего, если пояс известен
+	pub local_date: Option<NaiveDateTime>,
+	pub yes: i64,
+	pub maybe: i64,
+	pub no: i64,
+	pub your_answer: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, FromRow)]
+pub(crate) struct DatePoll {
+	pub id: Uuid,
+	pub company_id: Uuid,
+	pub company_name: String,
+	pub title: Option<String>,
+	pub event_id: Option<Uuid>,
+	pub timezone: Option<String>,
+	pub you_can_manage: bool,
+	pub you_can_vote: bool,
+	pub slots: SqlxJson<Vec<PollSlot>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, FromRow)]
+pub(crate) struct ShortDatePoll {
+	pub id: Uuid,
+	pub title: Option<String>,
+	pub event_id: Option<Uuid>,
+	pub slots: i64,
+	pub voters: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct ConvertedPoll {
+	pub event_id: Uuid,
+	pub voters: Vec<Uuid>,
+}
+
+#[derive(Debug, Deserialize, Serialize, FromRow)]
+pub(crate) struct WeeklyWindow {
+	pub weekday: i16,
+	pub from: NaiveTime,
+	pub to: NaiveTime,
+}
+
+/// Интервал времени конкретного пользователя, уже переведённый в UTC
+#[derive(Debug, Deserialize, Serialize, FromRow)]
+pub(crate) struct UserInterval {
+	pub user_id: Uuid,
+	pub start: DateTime<Utc>,
+	pub end: DateTime<Utc>,
+}
+
+/// Игра пользователя, пересекающаяся по времени с новой.
+/// `role` - "player" для заявки (`approval` - её статус) или "master" для своей игры
+#[derive(Debug, Deserialize, Serialize, FromRow)]
+pub(crate) struct ScheduleConflict {
+	pub event_id: Uuid,
+	pub company_name: String,
+	pub date: DateTime<Utc>,
+	pub end: DateTime<Utc>,
+	pub role: String,
+	pub approval: Option<bool>,
+}
+
+/// Пересечение записываемой игры `target` с другой игрой пользователя
+#[derive(Debug, FromRow)]
+pub(crate) struct TargetConflict {
+	pub target: Uuid,
+	#[sqlx(flatten)]
+	pub conflict: ScheduleConflict,
+}
+
+/// Чьё расписание проверяется при записи игр. В строгом режиме найденные
+/// пересечения отменяют запись
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ScheduleCheck {
+	pub user_id: Uuid,
+	pub strict: bool,
+}
+
+impl ScheduleCheck {
+	pub(crate) const fn rejects(&self, checks: &[EventCheck]) -> bool {
+		self.strict && !checks.is_empty()
+	}
+}
+
+/// Почему локация не может принять игру в это время
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(crate) enum LocationIssue {
+	/// все столы заняты играми `concurrent`
+	Full { tables: i16, concurrent: Vec<Uuid> },
+	/// игра не укладывается в часы работы
+	Closed,
+}
+
+/// Записанная игра, которой что-то мешает
+#[derive(Debug, Serialize)]
+pub(crate) struct EventCheck {
+	pub event_id: Uuid,
+	pub conflicts: Vec<ScheduleConflict>,
+	pub location_issues: Vec<LocationIssue>,
+}
+
+/// Результат записи игр с проверкой расписания
+#[derive(Debug)]
+pub(crate) enum Checked<T> {
+	Saved(T, Vec<EventCheck>),
+	/// строгий режим: ничего не записано
+	Rejected(Vec<EventCheck>),
+}
+
+/// Загрузка локации на время игры: другие игры в это же время
+/// и попадание в часы работы
+#[derive(Debug, Deserialize, Serialize, FromRow)]
+pub(crate) struct LocationLoad {
+	pub tables: Option<i16>,
+	pub concurrent: Vec<Uuid>,
+	pub has_hours: bool,
+	pub within_hours: bool,
+}
+
+/// Игра, изменённая вместе с выбранной, и её игроки
+#[derive(Debug, FromRow)]
+pub(crate) struct MovedEvent {
+	pub event_id: Uuid,
+	pub previous_date: DateTime<Utc>,
+	pub date: DateTime<Utc>,
+	pub cancelled: bool,
+	pub players: Vec<Uuid>,
+}
+
+/// Загрузка локации на время записываемой игры `target`
+#[derive(Debug, FromRow)]
+pub(crate) struct TargetLoad {
+	pub target: Uuid,
+	#[sqlx(flatten)]
+	pub load: LocationLoad,
+}
+
+#[derive(Debug, Deserialize, Serialize, FromRow)]
+pub(crate) struct NearbyLocation {
+	#[serde(flatten)]
+	#[sqlx(flatten)]
+	pub location: Location,
+	pub distance_km: f64,
+}
+
+#[derive(Debug, Deserialize, Serialize, FromRow)]
+pub(crate) struct NearbyEvent {
+	pub id: Uuid,
+	pub company: String,
+	pub date: DateTime<Utc>,
+	pub plan_duration: Option<i16>,
+	pub location_id: Uuid,
+	pub location_name: String,
+	pub distance_km: f64,
+}
+
+/// Входящее уведомление; `read_at` пуст, пока пользователь его не прочитал
+#[derive(Debug, Clone, Deserialize, Serialize, FromRow)]
+pub(crate) struct Notification {
+	pub id: Uuid,
+	pub message: String,
+	/// `NotificationPayload` с тегом `type`
+	pub payload: Option<SqlxJson<serde_json::Value>>,
+	pub created_at: DateTime<Utc>,
+	pub read_at: Option<DateTime<Utc>>,
+}
+
+/// Чат с ботом пользователя, согласившегося получать уведомления в Telegram
+#[derive(Debug, Deserialize, Serialize, FromRow)]
+pub(crate) struct TelegramChat {
+	pub chat_id: i64,
+	/// смещение часового пояса пользователя в секундах на дату из уведомления
+	pub utc_offset: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, Serialize, FromRow)]
+pub(crate) struct LocationLink {
+	pub id: Uuid,
+	pub map_link: String,
+}
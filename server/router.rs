@@ -2,10 +2,10 @@ use ::std::{error::Error, sync::Arc};
 use axum::{Router as AxumRouter, middleware};
 #[cfg(feature = "cors")]
 use axum::{http::StatusCode, routing::options};
-use okapi_operation::axum_integration::{Router as OkapiRouter, get, post, put};
+use okapi_operation::axum_integration::{Router as OkapiRouter, delete, get, post, put};
 #[cfg(feature = "static")]
 use tower_http::services::{ServeDir, ServeFile};
-use utoipa::openapi::OpenApi as UtoipaSpec;
+use utoipa::{OpenApi as _, openapi::OpenApi as UtoipaSpec};
 use utoipa_swagger_ui::SwaggerUi;
 
 #[cfg(feature = "cors")]
@@ -18,6 +18,19 @@ pub fn create_router(state: Arc<AppState>) -> Result<AxumRouter, Box<dyn Error>>
 	let okapi_router = OkapiRouter::new()
 		.route("/avatar/{id}", get(H::read_avatar))
 		.route("/cover/{id}", get(H::companies::read_company_cover))
+		.route("/calendar/{file}", get(H::calendar::read_personal_feed))
+		.route(
+			"/calendar/company/{file}",
+			get(H::calendar::read_company_feed),
+		)
+		.route(
+			"/calendar/location/{file}",
+			get(H::calendar::read_location_feed),
+		)
+		.route(
+			"/calendar/master/{file}",
+			get(H::calendar::read_master_feed),
+		)
 		.nest(
 			"/api",
 			OkapiRouter::new()
@@ -27,16 +40,25 @@ pub fn create_router(state: Arc<AppState>) -> Result<AxumRouter, Box<dyn Error>>
 				.route("/logout", post(H::logout))
 				.route("/verify", post(H::verify::verify))
 				.route("/locations", get(H::locations::get_locations_list))
+				.route("/locations/near", get(H::locations::get_nearby_locations))
 				.route("/locations/{id}", get(H::locations::get_location_by_id))
 				.route("/regions", get(H::regions::read_regions_list))
 				.route("/cities", get(H::regions::read_cities_list))
+				.route("/telegram/webhook", post(H::telegram::telegram_webhook))
 				.merge(
 					OkapiRouter::new()
 						.route("/sse", get(H::sse::sse_handler))
 						.route("/profile/{id}", get(H::read_another_profile))
 						.route("/companies/{id}", get(H::companies::get_company_by_id))
+						.route(
+							"/companies/{id}/members",
+							get(H::members::read_company_members),
+						)
 						.route("/events", get(H::events::read_events_list))
+						.route("/events/near", get(H::events::read_nearby_events))
 						.route("/events/{id}", get(H::events::read_event))
+						.route("/series/{id}", get(H::series::read_series))
+						.route("/search", get(H::search::search))
 						.layer(middleware::from_fn(auth::optional_auth_middleware)),
 				)
 				.merge(
@@ -50,6 +72,33 @@ pub fn create_router(state: Arc<AppState>) -> Result<AxumRouter, Box<dyn Error>>
 							post(H::verify::send_email_verification),
 						)
 						.route("/touches-history", get(H::read_touches_history))
+						.route(
+							"/calendar/token",
+							post(H::calendar::issue_calendar_token)
+								.delete(H::calendar::revoke_calendar_token),
+						)
+						.route(
+							"/profile/availability",
+							get(H::schedule::read_my_availability).put(H::schedule::set_my_availability),
+						)
+						.route("/notifications", get(H::notifications::read_notifications))
+						.route(
+							"/notifications/unread",
+							get(H::notifications::count_unread_notifications),
+						)
+						.route(
+							"/notifications/read",
+							post(H::notifications::mark_all_notifications_read),
+						)
+						.route(
+							"/notifications/{id}/read",
+							post(H::notifications::mark_notification_read),
+						)
+						.route("/telegram/link", post(H::telegram::create_telegram_link))
+						.route(
+							"/telegram/notifications",
+							put(H::telegram::set_telegram_notifications),
+						)
 						.layer(middleware::from_fn(auth::auth_middleware)),
 				)
 				.merge(
@@ -59,13 +108,60 @@ pub fn create_router(state: Arc<AppState>) -> Result<AxumRouter, Box<dyn Error>>
 						.route("/locations", post(H::locations::add_location))
 						.route("/companies", post(H::companies::add_company))
 						.route("/companies/my", get(H::companies::get_my_companies))
+						.route("/companies/one-shot", post(H::companies::add_one_shot))
 						.route("/companies/{id}", put(H::companies::update_company))
 						.route("/companies/{id}/cover", put(H::companies::set_cover))
+						.route(
+							"/companies/{id}/transfer",
+							post(H::companies::propose_transfer).delete(H::companies::cancel_transfer),
+						)
+						.route(
+							"/companies/{id}/transfer/accept",
+							post(H::companies::accept_transfer),
+						)
+						.route(
+							"/companies/{id}/calendar/import",
+							post(H::calendar::import_calendar),
+						)
+						.route("/companies/{id}/members", post(H::members::invite_member))
+						.route(
+							"/companies/{id}/members/accept",
+							post(H::members::accept_membership),
+						)
+						.route(
+							"/companies/{id}/members/leave",
+							post(H::members::leave_company),
+						)
+						.route(
+							"/companies/{id}/members/{user_id}",
+							delete(H::members::remove_member),
+						)
+						.route(
+							"/companies/{id}/members/{user_id}/role",
+							put(H::members::set_member_role),
+						)
+						.route(
+							"/companies/{id}/polls",
+							get(H::polls::read_company_polls).post(H::polls::add_date_poll),
+						)
+						.route(
+							"/companies/{id}/schedule/suggestions",
+							get(H::schedule::suggest_times),
+						)
+						.route("/polls/{id}", get(H::polls::read_date_poll))
+						.route("/polls/{id}/votes", put(H::polls::vote_date_poll))
+						.route("/polls/{id}/event", post(H::polls::convert_date_poll))
 						.route("/events", post(H::events::add_event))
 						.route("/events/apply/{id}", post(H::events::apply_event))
 						.route("/events/cancel/{id}", post(H::events::cancel_event))
 						.route("/events/reopen/{id}", post(H::events::reopen_event))
 						.route("/events/{id}", put(H::events::update_event))
+						.route("/events/{id}/invite", post(H::events::create_invite))
+						.route("/series", post(H::series::add_series))
+						.route(
+							"/series/{id}/exceptions",
+							post(H::series::add_series_exceptions),
+						)
 						.route("/apps", get(H::apps::read_player_apps_list))
 						.route("/apps/{id}", get(H::apps::read_player_app))
 						.route(
@@ -91,6 +187,31 @@ pub fn create_router(state: Arc<AppState>) -> Result<AxumRouter, Box<dyn Error>>
 						.route("/regions", post(H::regions::add_region))
 						.route("/cities", post(H::regions::add_city))
 						.layer(middleware::from_fn(auth::auth_and_verified_middleware)),
+				)
+				.merge(
+					OkapiRouter::new()
+						.route("/admin/locations/merge", post(H::admin::merge_locations))
+						.route(
+							"/admin/locations/coordinates",
+							post(H::admin::fill_location_coordinates),
+						)
+						.route(
+							"/admin/locations/{id}/capacity",
+							put(H::admin::set_location_capacity),
+						)
+						.route("/admin/cities/merge", post(H::admin::merge_cities))
+						.route("/admin/users/{id}/role", put(H::admin::set_user_role))
+						.route("/admin/sse/metrics", get(H::admin::read_sse_metrics))
+						.route("/admin/announcements", post(H::admin::publish_announcement))
+						.route(
+							"/admin/telegram/webhook",
+							post(H::admin::set_telegram_webhook),
+						)
+						.layer(middleware::from_fn_with_state(
+							state.clone(),
+							auth::admin_middleware,
+						))
+						.layer(middleware::from_fn(auth::auth_and_verified_middleware)),
 				),
 		)
 		.with_state(state);
@@ -127,5 +248,8 @@ fn generate_spec(okapi_router: &OkapiRouter) -> Result<UtoipaSpec, Box<dyn Error
 
 	let spec = serde_json::to_string(&okapi_spec)?;
 
-	serde_json::from_str::<UtoipaSpec>(&spec).map_err(Into::into)
+	let mut spec = serde_json::from_str::<UtoipaSpec>(&spec)?;
+	spec.merge(H::sse::SseApi::openapi());
+
+	Ok(spec)
 }
@@ -0,0 +1,439 @@
+use ::std::cmp::Reverse;
+use chrono::{DateTime, Duration, DurationRound as _, NaiveTime, Timelike as _, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+	dto::schedule::WeeklyWindowDto,
+	repository::models::{LocationIssue, LocationLoad},
+};
+
+/// Длительность игры, если `plan_duration` не указан, как и на клиенте
+pub(crate) const DEFAULT_DURATION_HOURS: i16 = 4;
+// начала предлагаемых окон выравниваются по получасу
+const STEP_MINUTES: i64 = 30;
+const DAY_SECONDS: u32 = 24 * 60 * 60;
+const WEEK_SECONDS: u32 = 7 * DAY_SECONDS;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Interval {
+	pub start: DateTime<Utc>,
+	pub end: DateTime<Utc>,
+}
+
+impl Interval {
+	const fn covers(&self, other: &Interval) -> bool {
+		self.start.timestamp() <= other.start.timestamp()
+			&& other.end.timestamp() <= self.end.timestamp()
+	}
+
+	const fn overlaps(&self, other: &Interval) -> bool {
+		self.start.timestamp() < other.end.timestamp()
+			&& other.start.timestamp() < self.end.timestamp()
+	}
+}
+
+/// Свободное время игрока (уже в UTC) и его одобренные игры
+#[derive(Debug, Default)]
+pub(crate) struct PlayerSchedule {
+	pub user_id: Uuid,
+	pub free: Vec<Interval>,
+	pub busy: Vec<Interval>,
+}
+
+impl PlayerSchedule {
+	fn is_free(&self, window: &Interval) -> bool {
+		self.free.iter().any(|free| free.covers(window))
+			&& !self.busy.iter().any(|busy| busy.overlaps(window))
+	}
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct Suggestion {
+	pub start: DateTime<Utc>,
+	pub end: DateTime<Utc>,
+	pub available: Vec<Uuid>,
+	pub unavailable: Vec<Uuid>,
+}
+
+/// Подбирает до `limit` непересекающихся окон длиной `duration` не раньше `from`,
+/// в которые свободно больше всего игроков. При равенстве выигрывает более раннее окно
+pub(crate) fn suggest(
+	players: &[PlayerSchedule],
+	from: DateTime<Utc>,
+	duration: Duration,
+	limit: usize,
+) -> Vec<Suggestion> {
+	let mut starts = players
+		.iter()
+		.flat_map(|player| {
+			let free = player.free.iter().map(|free| free.start);
+			// окно может начаться сразу после уже назначенной игры
+			let busy = player.busy.iter().map(|busy| busy.end);
+			free.chain(busy)
+		})
+		.map(|start| align(start.max(from)))
+		.collect::<Vec<_>>();
+
+	starts.sort_unstable();
+	starts.dedup();
+
+	let mut candidates = starts
+		.into_iter()
+		.filter_map(|start| {
+			let window = Interval {
+				start,
+				end: start + duration,
+			};
+			let available = players
+				.iter()
+				.filter(|player| player.is_free(&window))
+				.map(|player| player.user_id)
+				.collect::<Vec<_>>();
+
+			(!available.is_empty()).then_some((window, available))
+		})
+		.collect::<Vec<_>>();
+
+	// сортировка устойчивая, поэтому среди равных остаются более ранние окна
+	candidates.sort_by_key(|(_, available)| Reverse(available.len()));
+
+	let mut chosen: Vec<(Interval, Vec<Uuid>)> = Vec::with_capacity(limit);
+	for (window, available) in candidates {
+		if chosen.len() >= limit {
+			break;
+		}
+		if chosen.iter().any(|(taken, _)| taken.overlaps(&window)) {
+			continue;
+		}
+		chosen.push((window, available));
+	}
+
+	chosen
+		.into_iter()
+		.map(|(window, available)| Suggestion {
+			start: window.start,
+			end: window.end,
+			unavailable: players
+				.iter()
+				.map(|player| player.user_id)
+				.filter(|user_id| !available.contains(user_id))
+				.collect(),
+			available,
+		})
+		.collect()
+}
+
+/// Что мешает игре на локации: заняты все столы или игра не укладывается
+/// в часы работы. Без числа столов и без часов работы ограничений нет
+pub(crate) fn location_issues(load: LocationLoad) -> Vec<LocationIssue> {
+	let mut issues = Vec::new();
+
+	if let Some(tables) = load.tables
+		&& load.concurrent.len() >= usize::try_from(tables).unwrap_or_default()
+	{
+		issues.push(LocationIssue::Full {
+			tables,
+			concurrent: load.concurrent,
+		});
+	}
+
+	if load.has_hours && !load.within_hours {
+		issues.push(LocationIssue::Closed);
+	}
+
+	issues
+}
+
+/// Склеивает пересекающиеся и соседние интервалы
+pub(crate) fn merge(mut intervals: Vec<Interval>) -> Vec<Interval> {
+	intervals.sort_unstable_by_key(|interval| interval.start);
+
+	let mut merged: Vec<Interval> = Vec::with_capacity(intervals.len());
+	for interval in intervals {
+		match merged.last_mut() {
+			Some(last) if interval.start <= last.end => last.end = last.end.max(interval.end),
+			_ => merged.push(interval),
+		}
+	}
+
+	merged
+}
+
+/// Приводит еженедельные окна к непересекающимся: пересекающиеся и соседние
+/// окна склеиваются, в том числе через ночь с воскресенья на понедельник.
+/// Окно не может быть длиннее суток, поэтому склеенное окно режется по полуночи,
+/// а целые сутки - ещё и по полудню
+pub(crate) fn merge_weekly(windows: &[WeeklyWindowDto]) -> Vec<WeeklyWindowDto> {
+	// окна в секундах от начала недели, конец может уходить в следующую неделю
+	let mut spans = windows
+		.iter()
+		.map(|window| {
+			let from = window.from.num_seconds_from_midnight();
+			let to = window.to.num_seconds_from_midnight();
+			let start = u32::from(window.weekday.saturating_sub(1)) * DAY_SECONDS + from;
+			let length = match to > from {
+				true => to - from,
+				false => to + DAY_SECONDS - from,
+			};
+			(start, start + length)
+		})
+		.collect::<Vec<_>>();
+
+	spans.sort_unstable();
+
+	let mut merged: Vec<(u32, u32)> = Vec::with_capacity(spans.len());
+	for (start, end) in spans {
+		match merged.last_mut() {
+			Some(last) if start <= last.1 => last.1 = last.1.max(end),
+			_ => merged.push((start, end)),
+		}
+	}
+
+	while let [first, .., last] = merged.as_mut_slice()
+		&& first.0 + WEEK_SECONDS <= last.1
+	{
+		last.1 = last.1.max(first.1 + WEEK_SECONDS);
+		merged.remove(0);
+	}
+
+	if let [only] = merged.as_mut_slice()
+		&& only.1 - only.0 >= WEEK_SECONDS
+	{
+		*only = (0, WEEK_SECONDS);
+	}
+
+	merged.into_iter().flat_map(split_span).collect()
+}
+
+fn split_span((start, end): (u32, u32)) -> Vec<WeeklyWindowDto> {
+	if end - start < DAY_SECONDS {
+		return vec![weekly_window(start, end)];
+	}
+
+	let mut pieces = Vec::new();
+	let mut cut = start;
+	while cut < end {
+		let next = ((cut / DAY_SECONDS + 1) * DAY_SECONDS).min(end);
+		if next - cut == DAY_SECONDS {
+			let noon = cut + DAY_SECONDS / 2;
+			pieces.push(weekly_window(cut, noon));
+			pieces.push(weekly_window(noon, next));
+		} else {
+			pieces.push(weekly_window(cut, next));
+		}
+		cut = next;
+	}
+
+	pieces
+}
+
+fn weekly_window(start: u32, end: u32) -> WeeklyWindowDto {
+	let time = |seconds: u32| {
+		NaiveTime::from_num_seconds_from_midnight_opt(seconds % DAY_SECONDS, 0).unwrap_or_default()
+	};
+
+	WeeklyWindowDto {
+		weekday: u8::try_from(start % WEEK_SECONDS / DAY_SECONDS + 1).unwrap_or(1),
+		from: time(start),
+		to: time(end),
+	}
+}
+
+fn align(date: DateTime<Utc>) -> DateTime<Utc> {
+	let step = Duration::minutes(STEP_MINUTES);
+	match date.duration_trunc(step) {
+		Ok(truncated) if truncated == date => date,
+		Ok(truncated) => truncated + step,
+		Err(_) => date,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn t(s: &str) -> DateTime<Utc> {
+		DateTime::parse_from_rfc3339(s).unwrap().to_utc()
+	}
+
+	fn iv(start: &str, end: &str) -> Interval {
+		Interval {
+			start: t(start),
+			end: t(end),
+		}
+	}
+
+	fn player(n: u128, free: Vec<Interval>, busy: Vec<Interval>) -> PlayerSchedule {
+		PlayerSchedule {
+			user_id: Uuid::from_u128(n),
+			free,
+			busy,
+		}
+	}
+
+	#[test]
+	fn test_prefers_window_where_most_players_are_free() {
+		let players = [
+			player(
+				1,
+				vec![
+					iv("2026-11-02T16:00:00Z", "2026-11-02T21:00:00Z"),
+					iv("2026-11-04T16:00:00Z", "2026-11-04T21:00:00Z"),
+				],
+				vec![],
+			),
+			player(
+				2,
+				vec![iv("2026-11-04T17:00:00Z", "2026-11-04T22:00:00Z")],
+				vec![],
+			),
+		];
+
+		let res = suggest(&players, t("2026-11-01T00:00:00Z"), Duration::hours(4), 2);
+
+		assert_eq!(res.len(), 2);
+		assert_eq!(res[0].start, t("2026-11-04T17:00:00Z"));
+		assert_eq!(res[0].available.len(), 2);
+		assert!(res[0].unavailable.is_empty());
+		assert_eq!(res[1].start, t("2026-11-02T16:00:00Z"));
+		assert_eq!(res[1].unavailable, vec![Uuid::from_u128(2)]);
+	}
+
+	#[test]
+	fn test_approved_games_block_the_window() {
+		let players = [
+			player(
+				1,
+				vec![iv("2026-11-02T16:00:00Z", "2026-11-02T23:00:00Z")],
+				vec![iv("2026-11-02T15:00:00Z", "2026-11-02T18:00:00Z")],
+			),
+			player(
+				2,
+				vec![iv("2026-11-02T16:00:00Z", "2026-11-02T23:00:00Z")],
+				vec![],
+			),
+		];
+
+		let res = suggest(&players, t("2026-11-01T00:00:00Z"), Duration::hours(4), 1);
+
+		assert_eq!(res[0].start, t("2026-11-02T18:00:00Z"));
+		assert_eq!(res[0].available.len(), 2);
+	}
+
+	#[test]
+	fn test_starts_are_aligned_and_not_in_the_past() {
+		let players = [player(
+			1,
+			vec![iv("2026-11-02T16:00:00Z", "2026-11-02T23:00:00Z")],
+			vec![],
+		)];
+
+		let res = suggest(&players, t("2026-11-02T17:10:00Z"), Duration::hours(2), 5);
+
+		assert_eq!(res.len(), 1);
+		assert_eq!(res[0].start, t("2026-11-02T17:30:00Z"));
+	}
+
+	#[test]
+	fn test_nothing_fits_short_windows() {
+		let players = [player(
+			1,
+			vec![iv("2026-11-02T16:00:00Z", "2026-11-02T18:00:00Z")],
+			vec![],
+		)];
+
+		let res = suggest(&players, t("2026-11-01T00:00:00Z"), Duration::hours(4), 5);
+
+		assert!(res.is_empty());
+	}
+
+	#[test]
+	fn test_location_issues() {
+		let load = |tables, concurrent: u128, has_hours, within_hours| LocationLoad {
+			tables,
+			concurrent: (1..=concurrent).map(Uuid::from_u128).collect(),
+			has_hours,
+			within_hours,
+		};
+
+		assert!(location_issues(load(None, 5, false, false)).is_empty());
+		assert!(location_issues(load(Some(2), 1, true, true)).is_empty());
+		assert!(matches!(
+			location_issues(load(Some(2), 2, true, true)).as_slice(),
+			[LocationIssue::Full { tables: 2, concurrent }] if concurrent.len() == 2
+		));
+		assert!(matches!(
+			location_issues(load(Some(0), 0, true, false)).as_slice(),
+			[LocationIssue::Full { .. }, LocationIssue::Closed]
+		));
+	}
+
+	#[test]
+	fn test_merge_joins_adjacent_intervals() {
+		let merged = merge(vec![
+			iv("2026-11-02T20:00:00Z", "2026-11-03T00:00:00Z"),
+			iv("2026-11-02T16:00:00Z", "2026-11-02T21:00:00Z"),
+			iv("2026-11-03T00:00:00Z", "2026-11-03T02:00:00Z"),
+			iv("2026-11-04T16:00:00Z", "2026-11-04T18:00:00Z"),
+		]);
+
+		assert_eq!(
+			merged,
+			vec![
+				iv("2026-11-02T16:00:00Z", "2026-11-03T02:00:00Z"),
+				iv("2026-11-04T16:00:00Z", "2026-11-04T18:00:00Z"),
+			]
+		);
+	}
+
+	fn window(weekday: u8, from: &str, to: &str) -> WeeklyWindowDto {
+		WeeklyWindowDto {
+			weekday,
+			from: from.parse().unwrap(),
+			to: to.parse().unwrap(),
+		}
+	}
+
+	#[test]
+	fn test_merge_weekly_joins_overlapping_windows() {
+		let merged = merge_weekly(&[
+			window(1, "20:00", "23:00"),
+			window(1, "18:00", "21:00"),
+			window(3, "10:00", "12:00"),
+			window(3, "12:00", "14:00"),
+		]);
+
+		assert_eq!(
+			merged,
+			vec![window(1, "18:00", "23:00"), window(3, "10:00", "14:00")]
+		);
+	}
+
+	#[test]
+	fn test_merge_weekly_joins_through_sunday_night() {
+		let merged = merge_weekly(&[window(1, "00:00", "03:00"), window(7, "20:00", "00:00")]);
+
+		assert_eq!(merged, vec![window(7, "20:00", "03:00")]);
+	}
+
+	#[test]
+	fn test_merge_weekly_splits_windows_longer_than_a_day() {
+		let merged = merge_weekly(&[
+			window(1, "20:00", "02:00"),
+			window(2, "00:00", "12:00"),
+			window(2, "12:00", "00:00"),
+			window(3, "00:00", "01:00"),
+		]);
+
+		assert_eq!(
+			merged,
+			vec![
+				window(1, "20:00", "00:00"),
+				window(2, "00:00", "12:00"),
+				window(2, "12:00", "00:00"),
+				window(3, "00:00", "01:00"),
+			]
+		);
+	}
+}
@@ -31,6 +31,10 @@ impl RecordId {
 	pub fn into_api(self) -> Option<Value> {
 		Some(self.into())
 	}
+
+	pub const fn uuid(&self) -> Uuid {
+		self.0
+	}
 }
 
 impl From<RecordId> for Value {
@@ -1,35 +1,49 @@
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
+use chrono::FixedOffset;
 use tokio::{
 	sync::broadcast::{Sender, channel},
-	time::interval,
+	time::{interval, sleep},
 };
 use uuid::Uuid;
 
-use crate::repository::Repository;
+use crate::{
+	event_log::EventLog,
+	ical::FeedCache,
+	notification::{NotificationEvent, NotificationPayload},
+	repository::{Repository, SseFeed},
+	subscribers::Subscribers,
+	telegram::bot::{self, BotApi},
+};
+
+const LISTENER_RETRY: Duration = Duration::from_secs(1);
 
 pub struct AppState {
 	pub(crate) repo: Repository,
-	pub(crate) message_sender: Sender<(Option<Uuid>, String)>,
+	pub(crate) subscribers: Arc<Subscribers>,
+	pub(crate) event_log: Arc<EventLog>,
 	pub(crate) shutdown_sender: Sender<()>,
 	pub(crate) heartbeat_sender: Sender<()>,
+	pub(crate) calendar_cache: FeedCache,
+	pub(crate) telegram: BotApi,
 }
 
 impl AppState {
 	pub fn new(repo: Repository) -> Self {
-		let (message_sender, _) = channel(16);
+		let subscribers = Arc::new(Subscribers::default());
 		let (shutdown_sender, mut shutdown_receiver) = channel(1);
 		let (heartbeat_sender, _) = channel(2);
 
 		let heartbeat_sender_clone = heartbeat_sender.clone();
-		let message_sender_clone = message_sender.clone();
+		let subscribers_clone = subscribers.clone();
+		let event_log = Arc::new(EventLog::default());
 
 		tokio::spawn(async move {
 			let mut interval = interval(Duration::from_secs(10));
 			loop {
 				tokio::select! {
 					_ = interval.tick() => {
-						if message_sender_clone.receiver_count() > 0 {
+						if subscribers_clone.clients() > 0 {
 							heartbeat_sender_clone.send(()).ok();
 						}
 					}
@@ -40,15 +54,126 @@ impl AppState {
 			}
 		});
 
+		// события SSE публикуются через NOTIFY, поэтому доходят до клиентов,
+		// подключенных к любому экземпляру сервера
+		let mut sse_listener = repo.sse_listener();
+		let mut listener_shutdown_receiver = shutdown_sender.subscribe();
+		let subscribers_clone = subscribers.clone();
+		let event_log_clone = event_log.clone();
+
+		tokio::spawn(async move {
+			loop {
+				tokio::select! {
+					feed = sse_listener.recv() => match feed {
+						Ok(SseFeed::Message(message)) => {
+							event_log_clone.record(&subscribers_clone, message);
+						}
+						Ok(SseFeed::Resync(head)) => event_log_clone.resync(head),
+						Err(err) => {
+							eprintln!("Подписка на события SSE прервана: {err}");
+							sleep(LISTENER_RETRY).await;
+						}
+					},
+					_ = listener_shutdown_receiver.recv() => {
+						break;
+					}
+				}
+			}
+		});
+
 		Self {
 			repo,
-			message_sender,
+			subscribers,
+			event_log,
 			shutdown_sender,
 			heartbeat_sender,
+			calendar_cache: FeedCache::default(),
+			telegram: BotApi::from_env(),
 		}
 	}
 }
 
+impl AppState {
+	/// Сохраняет уведомление во входящие получателей и сразу отправляет его в SSE тем,
+	/// кто онлайн на любом экземпляре сервера, а важные - ещё и в Telegram. Действие,
+	/// о котором уведомляем, уже выполнено, поэтому ошибки не прерывают запрос,
+	/// а только пишутся в лог
+	pub(crate) async fn notify(self: &Arc<Self>, users: &[Uuid], payload: NotificationPayload) {
+		let message = payload.message();
+
+		let stored = match self.repo.add_notifications(users, &message, &payload).await {
+			Ok(stored) => stored,
+			Err(err) => {
+				eprintln!("Не удалось сохранить уведомление: {err}");
+				Vec::new()
+			}
+		};
+
+		let data = users
+			.iter()
+			.map(|user| {
+				let event = NotificationEvent {
+					id: stored
+						.iter()
+						.find(|(owner, _)| owner == user)
+						.map(|(_, id)| *id),
+					message: &message,
+					payload: &payload,
+				};
+				serde_json::to_string(&event)
+			})
+			.collect::<Result<Vec<_>, _>>();
+
+		let published = match data {
+			Ok(data) => {
+				self
+					.repo
+					.publish_sse(users, payload.event_name(), &data)
+					.await
+			}
+			Err(err) => Err(err.into()),
+		};
+		if let Err(err) = published {
+			eprintln!("Не удалось отправить уведомление в SSE: {err}");
+		}
+
+		if payload.is_for_telegram() {
+			self.notify_telegram(users.to_vec(), payload);
+		}
+	}
+
+	/// Рассылка в Telegram идёт в фоне, чтобы не задерживать ответ на запрос
+	fn notify_telegram(self: &Arc<Self>, users: Vec<Uuid>, payload: NotificationPayload) {
+		let state = self.clone();
+
+		tokio::spawn(async move {
+			let chats = match state.repo.read_telegram_chats(&users, payload.date()).await {
+				Ok(chats) => chats,
+				Err(err) => {
+					eprintln!("Не удалось получить чаты Telegram: {err}");
+					return;
+				}
+			};
+
+			let messages = chats
+				.iter()
+				.map(|chat| {
+					let offset = chat.utc_offset.and_then(FixedOffset::east_opt);
+					(chat.chat_id, payload.local_message(offset))
+				})
+				.collect::<Vec<_>>();
+
+			// заблокировавшим бота больше не пишем
+			let forbidden = bot::deliver(&state.telegram, &messages).await;
+			if !forbidden.is_empty()
+				&& let Err(err) = state.repo.disable_telegram_chats(&forbidden).await
+			{
+				eprintln!("Не удалось отключить уведомления в Telegram: {err}");
+			}
+		});
+	}
+}
+
 impl Drop for AppState {
 	fn drop(&mut self) {
 		self.shutdown_sender.send(()).ok();
@@ -0,0 +1,267 @@
+use ::std::{
+	collections::HashMap,
+	sync::{
+		Arc, Mutex, PoisonError,
+		atomic::{AtomicU64, AtomicUsize, Ordering},
+	},
+};
+use serde::Serialize;
+use tokio::sync::{
+	broadcast,
+	mpsc::{self, error::TrySendError},
+};
+use uuid::Uuid;
+
+use crate::event_log::SseMessage;
+
+/// Очередь одного подключения. Кто не успевает её разбирать, отключается
+/// и при переподключении получает пропущенное по Last-Event-ID
+const CLIENT_CAPACITY: usize = 64;
+const GLOBAL_CAPACITY: usize = 64;
+
+/// Очереди подключений пользователя с их номерами
+type Connections = Vec<(u64, mpsc::Sender<SseMessage>)>;
+
+#[derive(Debug, Serialize)]
+pub(crate) struct SseMetrics {
+	/// открытые подключения, включая анонимные
+	pub clients: usize,
+	/// пользователи хотя бы с одним подключением
+	pub users: usize,
+	/// события, не доставленные из-за переполненной личной очереди
+	/// или отставания подключения от общего канала
+	pub dropped: u64,
+}
+
+/// Подписчики SSE этого экземпляра сервера. Личные события идут прямо
+/// в очереди подключений пользователя, общие - через broadcast
+pub(crate) struct Subscribers {
+	users: Mutex<HashMap<Uuid, Connections>>,
+	global: broadcast::Sender<SseMessage>,
+	next_connection: AtomicU64,
+	clients: AtomicUsize,
+	dropped: AtomicU64,
+}
+
+pub(crate) struct Subscription {
+	/// `None` у анонимного подключения
+	pub personal: Option<mpsc::Receiver<SseMessage>>,
+	pub global: broadcast::Receiver<SseMessage>,
+	/// снимает подписку при закрытии подключения
+	pub guard: SubscriptionGuard,
+}
+
+pub(crate) struct SubscriptionGuard {
+	subscribers: Arc<Subscribers>,
+	user: Option<Uuid>,
+	connection: u64,
+}
+
+impl Drop for SubscriptionGuard {
+	fn drop(&mut self) {
+		self.subscribers.clients.fetch_sub(1, Ordering::Relaxed);
+
+		let Some(user) = self.user else {
+			return;
+		};
+
+		let mut users = self
+			.subscribers
+			.users
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner);
+
+		if let Some(senders) = users.get_mut(&user) {
+			senders.retain(|(connection, _)| *connection != self.connection);
+			if senders.is_empty() {
+				users.remove(&user);
+			}
+		}
+	}
+}
+
+impl Default for Subscribers {
+	fn default() -> Self {
+		let (global, _) = broadcast::channel(GLOBAL_CAPACITY);
+
+		Self {
+			users: Mutex::default(),
+			global,
+			next_connection: AtomicU64::new(0),
+			clients: AtomicUsize::new(0),
+			dropped: AtomicU64::new(0),
+		}
+	}
+}
+
+impl Subscribers {
+	pub(crate) fn subscribe(self: &Arc<Self>, user: Option<Uuid>) -> Subscription {
+		let connection = self.next_connection.fetch_add(1, Ordering::Relaxed);
+		self.clients.fetch_add(1, Ordering::Relaxed);
+
+		let personal = user.map(|user| {
+			let (sender, receiver) = mpsc::channel(CLIENT_CAPACITY);
+			self
+				.users
+				.lock()
+				.unwrap_or_else(PoisonError::into_inner)
+				.entry(user)
+				.or_default()
+				.push((connection, sender));
+			receiver
+		});
+
+		Subscription {
+			personal,
+			global: self.global.subscribe(),
+			guard: SubscriptionGuard {
+				subscribers: self.clone(),
+				user,
+				connection,
+			},
+		}
+	}
+
+	pub(crate) fn dispatch(&self, message: SseMessage) {
+		let Some(user) = message.user else {
+			self.global.send(message).ok();
+			return;
+		};
+
+		let mut users = self.users.lock().unwrap_or_else(PoisonError::into_inner);
+
+		let Some(senders) = users.get_mut(&user) else {
+			return;
+		};
+
+		senders.retain(|(_, sender)| match sender.try_send(message.clone()) {
+			Ok(()) => true,
+			// закрытие очереди завершает поток SSE, и браузер переподключится сам
+			Err(TrySendError::Full(_)) => {
+				self.dropped.fetch_add(1, Ordering::Relaxed);
+				false
+			}
+			Err(TrySendError::Closed(_)) => false,
+		});
+
+		if senders.is_empty() {
+			users.remove(&user);
+		}
+	}
+
+	/// Подключение отстало от общего канала и пропустило `missed` событий
+	pub(crate) fn lagged(&self, missed: u64) {
+		self.dropped.fetch_add(missed, Ordering::Relaxed);
+	}
+
+	pub(crate) fn clients(&self) -> usize {
+		self.clients.load(Ordering::Relaxed)
+	}
+
+	pub(crate) fn metrics(&self) -> SseMetrics {
+		SseMetrics {
+			clients: self.clients(),
+			users: self
+				.users
+				.lock()
+				.unwrap_or_else(PoisonError::into_inner)
+				.len(),
+			dropped: self.dropped.load(Ordering::Relaxed),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn message(id: u64, user: Option<Uuid>) -> SseMessage {
+		SseMessage {
+			id,
+			seq: 0,
+			user,
+			event: String::from("test"),
+			data: String::new(),
+		}
+	}
+
+	#[test]
+	fn test_personal_events_reach_only_their_user() {
+		let subscribers = Arc::new(Subscribers::default());
+		let (alice, bob) = (Uuid::from_u128(1), Uuid::from_u128(2));
+
+		let mut first = subscribers.subscribe(Some(alice));
+		let mut second = subscribers.subscribe(Some(alice));
+		let mut other = subscribers.subscribe(Some(bob));
+		let mut anonymous = subscribers.subscribe(None);
+
+		subscribers.dispatch(message(1, Some(alice)));
+		subscribers.dispatch(message(2, None));
+
+		for subscription in [&mut first, &mut second] {
+			let personal = subscription.personal.as_mut().unwrap();
+			assert_eq!(personal.try_recv().unwrap().id, 1);
+			assert_eq!(subscription.global.try_recv().unwrap().id, 2);
+		}
+		assert!(other.personal.as_mut().unwrap().try_recv().is_err());
+		assert_eq!(other.global.try_recv().unwrap().id, 2);
+		assert_eq!(anonymous.global.try_recv().unwrap().id, 2);
+
+		let metrics = subscribers.metrics();
+		assert_eq!((metrics.clients, metrics.users, metrics.dropped), (4, 2, 0));
+	}
+
+	#[test]
+	fn test_closed_connections_are_unregistered() {
+		let subscribers = Arc::new(Subscribers::default());
+		let user = Uuid::from_u128(1);
+
+		let subscription = subscribers.subscribe(Some(user));
+		drop(subscription);
+
+		let metrics = subscribers.metrics();
+		assert_eq!((metrics.clients, metrics.users), (0, 0));
+	}
+
+	#[test]
+	fn test_slow_client_is_disconnected() {
+		let subscribers = Arc::new(Subscribers::default());
+		let user = Uuid::from_u128(1);
+
+		let mut subscription = subscribers.subscribe(Some(user));
+
+		for id in 0..=CLIENT_CAPACITY as u64 {
+			subscribers.dispatch(message(id, Some(user)));
+		}
+
+		let personal = subscription.personal.as_mut().unwrap();
+		for _ in 0..CLIENT_CAPACITY {
+			assert!(personal.try_recv().is_ok());
+		}
+		// очередь закрыта: поток SSE завершится
+		assert!(matches!(
+			personal.try_recv(),
+			Err(mpsc::error::TryRecvError::Disconnected)
+		));
+		assert_eq!(subscribers.metrics().dropped, 1);
+	}
+
+	#[test]
+	fn test_lagging_behind_global_events_is_counted() {
+		let subscribers = Arc::new(Subscribers::default());
+
+		let mut subscription = subscribers.subscribe(None);
+
+		for id in 0..=GLOBAL_CAPACITY as u64 {
+			subscribers.dispatch(message(id, None));
+		}
+
+		let Err(broadcast::error::TryRecvError::Lagged(missed)) = subscription.global.try_recv()
+		else {
+			panic!("подключение должно отстать от общего канала");
+		};
+		subscribers.lagged(missed);
+
+		assert_eq!(subscribers.metrics().dropped, 1);
+	}
+}
@@ -0,0 +1,293 @@
+use ::std::{
+	fmt::{Display, Formatter, Result as FmtResult},
+	sync::LazyLock,
+	time::Duration,
+};
+use serde::{
+	Deserialize, Serialize,
+	de::{DeserializeOwned, IgnoredAny},
+};
+use serde_json::json;
+use subtle::ConstantTimeEq as _;
+use tokio::sync::OnceCell;
+
+use crate::auth::derive_secret;
+
+const DEFAULT_API_URL: &str = "https://api.telegram.org";
+/// Без ограничений зависший API задержит рассылку и ответ на вебхук навсегда
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+/// Сколько действует ссылка на бота для привязки чата
+pub(crate) const LINK_TTL_MINUTES: i64 = 15;
+
+/// Telegram присылает его в заголовке каждого запроса на вебхук
+pub(crate) const WEBHOOK_SECRET_HEADER: &str = "x-telegram-bot-api-secret-token";
+static WEBHOOK_SECRET: LazyLock<String> =
+	LazyLock::new(|| hex::encode(derive_secret("telegram-webhook")));
+
+pub(super) fn init_static() {
+	let _ = *WEBHOOK_SECRET;
+}
+
+#[derive(Debug)]
+pub(crate) enum SendError {
+	/// бот заблокирован или чат удалён - писать туда больше нельзя
+	Forbidden,
+	Failed(String),
+}
+
+impl Display for SendError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+		return match self {
+			SendError::Forbidden => write!(f, "Forbidden"),
+			SendError::Failed(msg) => write!(f, "{msg}"),
+		};
+	}
+}
+
+/// Исходящие сообщения бота
+pub(crate) trait Notifier {
+	async fn send_message(&self, chat_id: i64, text: &str) -> Result<(), SendError>;
+}
+
+/// Клиент Telegram Bot API. Адрес API можно заменить через `TG_API_URL`
+pub(crate) struct BotApi {
+	client: reqwest::Client,
+	/// `<адрес API>/bot<токен>`
+	base_url: String,
+	username: OnceCell<String>,
+}
+
+#[derive(Deserialize)]
+struct ApiResponse<T> {
+	ok: bool,
+	result: Option<T>,
+	error_code: Option<u16>,
+	description: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct BotUser {
+	username: String,
+}
+
+impl BotApi {
+	pub(crate) fn new(api_url: &str, token: &str) -> Self {
+		Self {
+			client: reqwest::Client::builder()
+				.connect_timeout(CONNECT_TIMEOUT)
+				.timeout(REQUEST_TIMEOUT)
+				.build()
+				.expect("Telegram HTTP client cannot be built"),
+			base_url: format!("{}/bot{token}", api_url.trim_end_matches('/')),
+			username: OnceCell::new(),
+		}
+	}
+
+	pub(crate) fn from_env() -> Self {
+		let token =
+			::std::env::var("TG_BOT_TOKEN").expect("TG_BOT_TOKEN environment variable is not defined");
+		let api_url = ::std::env::var("TG_API_URL").unwrap_or_else(|_| DEFAULT_API_URL.to_owned());
+
+		Self::new(&api_url, &token)
+	}
+
+	async fn call<T: DeserializeOwned>(
+		&self,
+		method: &str,
+		body: &impl Serialize,
+	) -> Result<T, SendError> {
+		let response = self
+			.client
+			.post(format!("{}/{method}", self.base_url))
+			.json(body)
+			.send()
+			.await
+			.map_err(|err| SendError::Failed(err.to_string()))?
+			.json::<ApiResponse<T>>()
+			.await
+			.map_err(|err| SendError::Failed(err.to_string()))?;
+
+		match response {
+			ApiResponse {
+				ok: true,
+				result: Some(result),
+				..
+			} => Ok(result),
+			ApiResponse {
+				error_code: Some(403),
+				..
+			} => Err(SendError::Forbidden),
+			ApiResponse { description, .. } => Err(SendError::Failed(
+				description.unwrap_or_else(|| format!("{method} failed")),
+			)),
+		}
+	}
+
+	/// Имя бота для ссылок t.me. Запрашивается один раз
+	pub(crate) async fn username(&self) -> Result<&str, SendError> {
+		self
+			.username
+			.get_or_try_init(|| async {
+				let me = self.call::<BotUser>("getMe", &json!({})).await?;
+				Ok(me.username)
+			})
+			.await
+			.map(String::as_str)
+	}
+
+	/// Направляет обновления бота на `url`, подписывая их секретом вебхука
+	pub(crate) async fn set_webhook(&self, url: &str) -> Result<(), SendError> {
+		let body = json!({
+			"url": url,
+			"secret_token": *WEBHOOK_SECRET,
+			"allowed_updates": ["message"],
+		});
+
+		self.call::<bool>("setWebhook", &body).await.map(|_| ())
+	}
+}
+
+impl Notifier for BotApi {
+	async fn send_message(&self, chat_id: i64, text: &str) -> Result<(), SendError> {
+		let body = json!({
+			"chat_id": chat_id,
+			"text": text,
+			"link_preview_options": { "is_disabled": true },
+		});
+
+		self
+			.call::<IgnoredAny>("sendMessage", &body)
+			.await
+			.map(|_| ())
+	}
+}
+
+/// Рассылает сообщения по чатам и возвращает чаты, куда писать больше нельзя.
+/// Остальные ошибки только пишутся в лог: уведомление уже лежит во входящих
+pub(crate) async fn deliver(notifier: &impl Notifier, messages: &[(i64, String)]) -> Vec<i64> {
+	let mut forbidden = Vec::new();
+
+	for (chat_id, text) in messages {
+		match notifier.send_message(*chat_id, text).await {
+			Ok(()) => {}
+			Err(SendError::Forbidden) => forbidden.push(*chat_id),
+			Err(err) => eprintln!("Не удалось отправить уведомление в Telegram: {err}"),
+		}
+	}
+
+	forbidden
+}
+
+pub(crate) fn verify_webhook_secret(secret: Option<&str>) -> bool {
+	secret.is_some_and(|secret| bool::from(secret.as_bytes().ct_eq(WEBHOOK_SECRET.as_bytes())))
+}
+
+/// Обновление, которое Telegram присылает на вебхук. Нужны только
+/// текстовые сообщения в личном чате
+#[derive(Debug, Deserialize)]
+pub(crate) struct Update {
+	pub message: Option<IncomingMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct IncomingMessage {
+	pub chat: Chat,
+	pub text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Chat {
+	pub id: i64,
+	#[serde(rename = "type")]
+	pub kind: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum Command<'a> {
+	/// `/start <код>` - переход по ссылке из профиля
+	Start(Option<&'a str>),
+	Stop,
+}
+
+pub(crate) fn parse_command(text: &str) -> Option<Command<'_>> {
+	let mut parts = text.split_whitespace();
+	// в группах команда может быть адресована боту: /start@nri_bot
+	let command = parts.next()?.split('@').next()?;
+
+	match command {
+		"/start" => Some(Command::Start(parts.next())),
+		"/stop" => Some(Command::Stop),
+		_ => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use ::std::sync::{Arc, Mutex};
+	use axum::{
+		Json, Router,
+		extract::{Path, State},
+		routing::post,
+	};
+	use serde_json::Value;
+	use tokio::net::TcpListener;
+
+	use super::*;
+
+	type Calls = Arc<Mutex<Vec<(String, String, Value)>>>;
+
+	/// Заглушка Bot API: запоминает вызовы и отвечает 403 для чата 403
+	async fn stub_method(
+		State(calls): State<Calls>,
+		Path((bot, method)): Path<(String, String)>,
+		Json(body): Json<Value>,
+	) -> Json<Value> {
+		let blocked = body["chat_id"] == 403;
+		calls.lock().unwrap().push((bot, method, body));
+
+		Json(match blocked {
+			true => json!({
+				"ok": false,
+				"error_code": 403,
+				"description": "Forbidden: bot was blocked by the user",
+			}),
+			false => json!({ "ok": true, "result": { "message_id": 1 } }),
+		})
+	}
+
+	#[tokio::test]
+	async fn test_delivers_through_bot_api() {
+		let calls = Calls::default();
+		let app = Router::new()
+			.route("/{bot}/{method}", post(stub_method))
+			.with_state(calls.clone());
+
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let addr = listener.local_addr().unwrap();
+		tokio::spawn(async move { axum::serve(listener, app).await });
+
+		let bot = BotApi::new(&format!("http://{addr}/"), "123:abc");
+		let messages = [(1, String::from("первое")), (403, String::from("второе"))];
+
+		assert_eq!(deliver(&bot, &messages).await, vec![403]);
+
+		let calls = calls.lock().unwrap();
+		assert_eq!(calls.len(), 2);
+		assert_eq!(calls[0].0, "bot123:abc");
+		assert_eq!(calls[0].1, "sendMessage");
+		assert_eq!(calls[0].2["chat_id"], 1);
+		assert_eq!(calls[0].2["text"], "первое");
+	}
+
+	#[test]
+	fn test_parses_bot_commands() {
+		assert_eq!(
+			parse_command("/start 0a1b2c"),
+			Some(Command::Start(Some("0a1b2c")))
+		);
+		assert_eq!(parse_command("/start"), Some(Command::Start(None)));
+		assert_eq!(parse_command("/stop@nri_bot"), Some(Command::Stop));
+		assert_eq!(parse_command("привет"), None);
+	}
+}
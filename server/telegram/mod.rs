@@ -1,3 +1,5 @@
+pub(crate) mod bot;
+
 use ::std::{collections::BTreeMap, sync::LazyLock};
 use chrono::Utc;
 use hmac::{Hmac, Mac};
@@ -22,6 +24,7 @@ static TG_BOT_SECRET_KEY: LazyLock<[u8; 32]> = LazyLock::new(|| {
 
 pub(super) fn init_static() {
 	let _ = *TG_BOT_SECRET_KEY;
+	bot::init_static();
 	println!("+ telegram static values are ok");
 }
 